@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
+use super::call_tracer::{CallTracerLog, CallTracerRuntimeCall};
 use crate::{
 	listeners::call_list::Listener,
 	types::{
@@ -91,4 +92,12 @@ pub struct BlockscoutCall {
 	pub gas_used: U256,
 	#[serde(flatten)]
 	pub inner: BlockscoutCallInner,
+	/// Logs emitted by this call.
+	/// Not needed for Blockscout, but needed for the callTracer `withLog` option.
+	#[serde(skip)]
+	pub logs: Vec<CallTracerLog>,
+	/// Runtime calls dispatched by this call, if it is a precompile.
+	/// Not needed for Blockscout, but shown by the callTracer.
+	#[serde(skip)]
+	pub runtime_calls: Vec<CallTracerRuntimeCall>,
 }
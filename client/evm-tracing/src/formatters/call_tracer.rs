@@ -25,7 +25,7 @@ use crate::listeners::call_list::Listener;
 use crate::types::serialization::*;
 use serde::Serialize;
 
-use ethereum_types::{H160, U256};
+use ethereum_types::{H160, H256, U256};
 use parity_scale_codec::{Decode, Encode};
 use sp_std::{cmp::Ordering, vec::Vec};
 
@@ -38,11 +38,12 @@ impl super::ResponseFormatter for Formatter {
 	fn format(mut listener: Listener) -> Option<Vec<TransactionTrace>> {
 		// Remove empty BTreeMaps pushed to `entries`.
 		// I.e. InvalidNonce or other pallet_evm::runner exits
-		listener.entries.retain(|x| !x.is_empty());
+		listener.remove_empty_call_stacks();
 		let mut traces = Vec::new();
 		for entry in listener.entries.iter() {
 			let mut result: Vec<Call> = entry
 				.iter()
+				.filter(|(_, it)| !listener.only_top_call || it.trace_address.is_empty())
 				.map(|(_, it)| {
 					let from = it.from;
 					let trace_address = it.trace_address.clone();
@@ -97,7 +98,10 @@ impl super::ResponseFormatter for Formatter {
 									call_type: "SELFDESTRUCT".as_bytes().to_vec(),
 								},
 						},
+						logs: it.logs.clone(),
 						calls: Vec::new(),
+						runtime_calls: it.runtime_calls.clone(),
+						tx_hash: None,
 					})
 				})
 				.collect();
@@ -237,8 +241,38 @@ pub struct CallTracerCall {
 	#[serde(flatten)]
 	pub inner: CallTracerInner,
 
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub logs: Vec<CallTracerLog>,
+
 	#[serde(skip_serializing_if = "Vec::is_empty")]
 	pub calls: Vec<Call>,
+	/// Runtime calls dispatched by this call, if it is a precompile.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub runtime_calls: Vec<CallTracerRuntimeCall>,
+	/// Synthetic transaction hash of the Substrate extrinsic which started the call, only set on
+	/// the top-level call of its trace when tracing a block.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub tx_hash: Option<H256>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallTracerLog {
+	pub address: H160,
+	pub topics: Vec<H256>,
+	#[serde(serialize_with = "bytes_0x_serialize")]
+	pub data: Vec<u8>,
+	/// Number of subcalls of the emitting call made before the log.
+	pub position: U256,
+}
+
+/// Runtime call dispatched by a precompile.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, Serialize)]
+pub struct CallTracerRuntimeCall {
+	#[serde(serialize_with = "string_serialize")]
+	pub pallet: Vec<u8>,
+	#[serde(serialize_with = "string_serialize")]
+	pub call: Vec<u8>,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, Serialize)]
@@ -283,3 +317,39 @@ pub enum CallTracerInner {
 		value: U256,
 	},
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn test_call(tx_hash: Option<H256>) -> CallTracerCall {
+		CallTracerCall {
+			from: H160::repeat_byte(1),
+			trace_address: None,
+			gas: U256::from(100),
+			gas_used: U256::from(10),
+			inner: CallTracerInner::Call {
+				call_type: b"CALL".to_vec(),
+				to: H160::repeat_byte(2),
+				input: vec![],
+				res: CallResult::Output(vec![]),
+				value: Some(U256::zero()),
+			},
+			logs: vec![],
+			calls: vec![],
+			runtime_calls: vec![],
+			tx_hash,
+		}
+	}
+
+	#[test]
+	fn only_pseudo_transactions_have_a_tx_hash() {
+		let ethereum = serde_json::to_value(test_call(None)).unwrap();
+		assert_eq!(ethereum.get("txHash"), None);
+
+		let tx_hash = H256::repeat_byte(3);
+		let substrate = serde_json::to_value(test_call(Some(tx_hash))).unwrap();
+		assert_eq!(substrate["txHash"], serde_json::json!(format!("{:?}", tx_hash)));
+		assert_eq!(substrate["type"], "CALL");
+	}
+}
@@ -0,0 +1,83 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use crate::{listeners::four_byte::Listener, types::single::TransactionTrace};
+use std::collections::btree_map::BTreeMap;
+
+pub struct Formatter;
+
+impl super::ResponseFormatter for Formatter {
+	type Listener = Listener;
+	type Response = TransactionTrace;
+
+	/// Keys are formatted as Geth does, i.e. `0x<selector>-<calldata size>`.
+	fn format(listener: Listener) -> Option<TransactionTrace> {
+		let calls: BTreeMap<String, u64> = listener
+			.calls
+			.into_iter()
+			.map(|((selector, size), count)| {
+				(format!("0x{}-{}", hex::encode(selector), size), count)
+			})
+			.collect();
+		Some(TransactionTrace::FourByte(calls))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::formatters::ResponseFormatter;
+	use ethereum_types::{H160, U256};
+	use evm_tracing_events::{
+		runtime::{ExitReason, ExitSucceed},
+		Context, Event, EvmEvent, Listener as _,
+	};
+
+	fn call(input: Vec<u8>) -> Event {
+		Event::Evm(EvmEvent::Call {
+			code_address: H160::repeat_byte(2),
+			transfer: None,
+			input,
+			target_gas: None,
+			is_static: false,
+			context: Context {
+				address: H160::repeat_byte(2),
+				caller: H160::repeat_byte(1),
+				apparent_value: U256::zero(),
+			},
+		})
+	}
+
+	#[test]
+	fn counts_calls_by_selector_and_size() {
+		let events = vec![
+			Event::Evm(EvmEvent::TransactCall {
+				caller: H160::repeat_byte(1),
+				address: H160::repeat_byte(2),
+				value: U256::zero(),
+				data: vec![0xa9, 0x05, 0x9c, 0xbb, 0, 0],
+				gas_limit: 100_000,
+			}),
+			call(vec![0x70, 0xa0, 0x82, 0x31, 1]),
+			call(vec![0x70, 0xa0, 0x82, 0x31, 2]),
+			call(vec![0x70, 0xa0, 0x82, 0x31]),
+			// Plain transfers are not counted.
+			call(vec![]),
+			Event::Evm(EvmEvent::Exit {
+				reason: ExitReason::Succeed(ExitSucceed::Returned),
+				return_value: vec![],
+			}),
+		];
+
+		let mut listener = Listener::default();
+		events.into_iter().for_each(|event| listener.event(event));
+
+		assert_eq!(
+			serde_json::to_value(Formatter::format(listener).unwrap()).unwrap(),
+			serde_json::json!({
+				"0xa9059cbb-2": 1,
+				"0x70a08231-1": 2,
+				"0x70a08231-0": 1,
+			})
+		);
+	}
+}
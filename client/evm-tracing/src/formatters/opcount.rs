@@ -0,0 +1,66 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use crate::{listeners::opcount::Listener, types::single::TransactionTrace};
+
+pub struct Formatter;
+
+impl super::ResponseFormatter for Formatter {
+	type Listener = Listener;
+	type Response = TransactionTrace;
+
+	fn format(listener: Listener) -> Option<TransactionTrace> {
+		Some(TransactionTrace::Opcount(listener.count))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::formatters::ResponseFormatter;
+	use ethereum_types::{H160, U256};
+	use evm_tracing_events::{
+		runtime::{Capture, ExitReason, ExitSucceed},
+		Context, Event, Listener as _, RuntimeEvent,
+	};
+
+	fn step(opcode: &str) -> Event {
+		Event::Runtime(RuntimeEvent::Step {
+			context: Context {
+				address: H160::repeat_byte(2),
+				caller: H160::repeat_byte(1),
+				apparent_value: U256::zero(),
+			},
+			opcode: opcode.as_bytes().to_vec(),
+			position: Ok(0),
+			stack: None,
+			memory: None,
+		})
+	}
+
+	fn step_result(result: Result<(), Capture<ExitReason, Vec<u8>>>) -> Event {
+		Event::Runtime(RuntimeEvent::StepResult { result, return_value: vec![] })
+	}
+
+	#[test]
+	fn counts_steps_of_all_contexts() {
+		let events = vec![
+			step("Push1"),
+			step_result(Ok(())),
+			step("Call"),
+			step_result(Err(Capture::Trap(b"Call".to_vec()))),
+			// Nested context.
+			step("Stop"),
+			step_result(Err(Capture::Exit(ExitReason::Succeed(ExitSucceed::Stopped)))),
+			step("Return"),
+			step_result(Err(Capture::Exit(ExitReason::Succeed(ExitSucceed::Returned)))),
+		];
+
+		let mut listener = Listener::default();
+		events.into_iter().for_each(|event| listener.event(event));
+
+		assert_eq!(
+			serde_json::to_value(Formatter::format(listener).unwrap()).unwrap(),
+			serde_json::json!(4)
+		);
+	}
+}
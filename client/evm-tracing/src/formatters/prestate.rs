@@ -0,0 +1,198 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use crate::{
+	listeners::prestate::Listener,
+	types::{serialization::*, single::TransactionTrace},
+};
+use ethereum_types::{H160, H256, U256};
+use parity_scale_codec::{Decode, Encode};
+use serde::Serialize;
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// State of an account as returned by Geth's `prestateTracer`.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Encode, Decode, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountState {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub balance: Option<U256>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nonce: Option<u64>,
+	#[serde(serialize_with = "option_bytes_0x_serialize", skip_serializing_if = "Option::is_none")]
+	pub code: Option<Vec<u8>>,
+	#[serde(skip_serializing_if = "BTreeMap::is_empty")]
+	pub storage: BTreeMap<H256, H256>,
+}
+
+impl AccountState {
+	/// Account with no balance, nonce nor code, i.e. which doesn't exist.
+	pub(crate) fn is_empty(&self) -> bool {
+		self.balance.unwrap_or_default().is_zero() &&
+			self.nonce.unwrap_or_default() == 0 &&
+			self.code.as_ref().map_or(true, |code| code.is_empty())
+	}
+
+	/// Geth omits a zero nonce and an empty code.
+	fn normalized(mut self) -> Self {
+		self.balance = Some(self.balance.unwrap_or_default());
+		self.nonce = self.nonce.filter(|nonce| *nonce != 0);
+		self.code = self.code.filter(|code| !code.is_empty());
+		self
+	}
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, Serialize)]
+#[serde(untagged)]
+pub enum PrestateTrace {
+	/// State of the accessed accounts before the execution.
+	Prestate(BTreeMap<H160, AccountState>),
+	/// Fields modified by the execution, before and after it.
+	Diff { pre: BTreeMap<H160, AccountState>, post: BTreeMap<H160, AccountState> },
+}
+
+pub struct Formatter;
+
+impl super::ResponseFormatter for Formatter {
+	type Listener = Listener;
+	type Response = TransactionTrace;
+
+	fn format(mut listener: Listener) -> Option<TransactionTrace> {
+		if !listener.diff_mode {
+			let pre = listener
+				.pre_state
+				.into_iter()
+				.map(|(address, state)| (address, state.normalized()))
+				.collect();
+			return Some(TransactionTrace::Prestate(PrestateTrace::Prestate(pre)))
+		}
+
+		let mut pre = BTreeMap::new();
+		let mut post = BTreeMap::new();
+		for (address, pre_state) in listener.pre_state.into_iter() {
+			let post_state = listener.post_state.remove(&address).unwrap_or_default();
+			let pre_state = pre_state.normalized();
+			let post_state = post_state.normalized();
+
+			let mut modified = false;
+			let mut pre_diff = AccountState { storage: BTreeMap::new(), ..pre_state.clone() };
+			let mut post_diff = AccountState::default();
+			if pre_state.balance != post_state.balance {
+				modified = true;
+				post_diff.balance = post_state.balance;
+			}
+			if pre_state.nonce != post_state.nonce {
+				modified = true;
+				post_diff.nonce = post_state.nonce;
+			}
+			if pre_state.code != post_state.code {
+				modified = true;
+				post_diff.code = post_state.code.clone();
+			}
+			for (key, value) in pre_state.storage.iter() {
+				let new_value = post_state.storage.get(key).copied().unwrap_or_default();
+				if *value == new_value {
+					continue
+				}
+				modified = true;
+				if !value.is_zero() {
+					pre_diff.storage.insert(*key, *value);
+				}
+				if !new_value.is_zero() {
+					post_diff.storage.insert(*key, new_value);
+				}
+			}
+
+			if !modified {
+				continue
+			}
+			// Created accounts have no pre state and destroyed accounts no post state.
+			if !pre_state.is_empty() {
+				pre.insert(address, pre_diff);
+			}
+			if !post_state.is_empty() {
+				post.insert(address, post_diff);
+			}
+		}
+
+		Some(TransactionTrace::Prestate(PrestateTrace::Diff { pre, post }))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::formatters::ResponseFormatter;
+
+	fn account(balance: u64, nonce: u64, storage: &[(H256, H256)]) -> AccountState {
+		AccountState {
+			balance: Some(balance.into()),
+			nonce: Some(nonce),
+			code: Some(vec![]),
+			storage: storage.iter().copied().collect(),
+		}
+	}
+
+	#[test]
+	fn prestate_omits_zero_nonce_and_empty_code() {
+		let address = H160::repeat_byte(1);
+		let mut listener = Listener::new(false);
+		listener.pre_state.insert(address, account(10, 0, &[]));
+
+		assert_eq!(
+			serde_json::to_value(Formatter::format(listener).unwrap()).unwrap(),
+			serde_json::json!({ format!("{:?}", address): { "balance": "0xa" } })
+		);
+	}
+
+	#[test]
+	fn diff_mode_only_returns_modified_fields() {
+		let sender = H160::repeat_byte(1);
+		let untouched = H160::repeat_byte(2);
+		let created = H160::repeat_byte(3);
+		let (slot_a, slot_b) = (H256::repeat_byte(4), H256::repeat_byte(5));
+		let value = H256::repeat_byte(6);
+
+		let mut listener = Listener::new(true);
+		listener
+			.pre_state
+			.insert(sender, account(10, 1, &[(slot_a, value), (slot_b, value)]));
+		listener
+			.post_state
+			.insert(sender, account(7, 2, &[(slot_a, value), (slot_b, H256::zero())]));
+		listener.pre_state.insert(untouched, account(10, 0, &[]));
+		listener.post_state.insert(untouched, account(10, 0, &[]));
+		listener.pre_state.insert(created, account(0, 0, &[]));
+		listener.post_state.insert(created, account(1, 1, &[]));
+
+		let Some(TransactionTrace::Prestate(PrestateTrace::Diff { pre, post })) =
+			Formatter::format(listener)
+		else {
+			panic!("expected a diff")
+		};
+
+		assert_eq!(
+			pre,
+			BTreeMap::from([(
+				sender,
+				AccountState {
+					balance: Some(10.into()),
+					nonce: Some(1),
+					code: None,
+					storage: BTreeMap::from([(slot_b, value)]),
+				}
+			)])
+		);
+		assert_eq!(
+			post,
+			BTreeMap::from([
+				(
+					sender,
+					AccountState { balance: Some(7.into()), nonce: Some(2), ..Default::default() }
+				),
+				(
+					created,
+					AccountState { balance: Some(1.into()), nonce: Some(1), ..Default::default() }
+				),
+			])
+		);
+	}
+}
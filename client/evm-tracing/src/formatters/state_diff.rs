@@ -0,0 +1,206 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Formats the state read around a transaction into the `stateDiff` of Parity's
+//! `trace_replayTransaction`. It reuses the prestate listener, whose `pre_state` and `post_state`
+//! must be filled by the caller.
+
+use super::prestate::AccountState;
+use crate::{listeners::prestate::Listener, types::serialization::*};
+use ethereum_types::{H160, H256, U256};
+use serde::Serialize;
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// Changes made by a transaction, for each modified account.
+pub type StateDiff = BTreeMap<H160, AccountDiff>;
+
+/// Contract code, serialized as 0x-prefixed hex.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+pub struct Code(#[serde(serialize_with = "bytes_0x_serialize")] pub Vec<u8>);
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+pub struct ChangedType<T> {
+	pub from: T,
+	pub to: T,
+}
+
+/// Change of a single value.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+pub enum Diff<T> {
+	#[serde(rename = "=")]
+	Same,
+	#[serde(rename = "+")]
+	Born(T),
+	#[serde(rename = "-")]
+	Died(T),
+	#[serde(rename = "*")]
+	Changed(ChangedType<T>),
+}
+
+impl<T: PartialEq> Diff<T> {
+	fn new(from: T, to: T) -> Self {
+		if from == to {
+			Diff::Same
+		} else {
+			Diff::Changed(ChangedType { from, to })
+		}
+	}
+
+	fn is_same(&self) -> bool {
+		matches!(self, Diff::Same)
+	}
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+pub struct AccountDiff {
+	pub balance: Diff<U256>,
+	pub nonce: Diff<U256>,
+	pub code: Diff<Code>,
+	pub storage: BTreeMap<H256, Diff<H256>>,
+}
+
+impl AccountDiff {
+	fn born(state: AccountState) -> Self {
+		Self {
+			balance: Diff::Born(state.balance.unwrap_or_default()),
+			nonce: Diff::Born(state.nonce.unwrap_or_default().into()),
+			code: Diff::Born(Code(state.code.unwrap_or_default())),
+			storage: state
+				.storage
+				.into_iter()
+				.filter(|(_, value)| !value.is_zero())
+				.map(|(key, value)| (key, Diff::Born(value)))
+				.collect(),
+		}
+	}
+
+	fn died(state: AccountState) -> Self {
+		Self {
+			balance: Diff::Died(state.balance.unwrap_or_default()),
+			nonce: Diff::Died(state.nonce.unwrap_or_default().into()),
+			code: Diff::Died(Code(state.code.unwrap_or_default())),
+			storage: state
+				.storage
+				.into_iter()
+				.filter(|(_, value)| !value.is_zero())
+				.map(|(key, value)| (key, Diff::Died(value)))
+				.collect(),
+		}
+	}
+
+	fn changed(pre: AccountState, mut post: AccountState) -> Self {
+		Self {
+			balance: Diff::new(pre.balance.unwrap_or_default(), post.balance.unwrap_or_default()),
+			nonce: Diff::new(
+				pre.nonce.unwrap_or_default().into(),
+				post.nonce.unwrap_or_default().into(),
+			),
+			code: Diff::new(
+				Code(pre.code.unwrap_or_default()),
+				Code(post.code.unwrap_or_default()),
+			),
+			storage: pre
+				.storage
+				.into_iter()
+				.map(|(key, value)| {
+					(key, Diff::new(value, post.storage.remove(&key).unwrap_or_default()))
+				})
+				.filter(|(_, diff)| !diff.is_same())
+				.collect(),
+		}
+	}
+
+	fn is_same(&self) -> bool {
+		self.balance.is_same() &&
+			self.nonce.is_same() &&
+			self.code.is_same() &&
+			self.storage.is_empty()
+	}
+}
+
+pub struct Formatter;
+
+impl super::ResponseFormatter for Formatter {
+	type Listener = Listener;
+	type Response = StateDiff;
+
+	fn format(mut listener: Listener) -> Option<StateDiff> {
+		let mut state_diff = StateDiff::new();
+		for (address, pre) in listener.pre_state.into_iter() {
+			let post = listener.post_state.remove(&address).unwrap_or_default();
+			let diff = match (pre.is_empty(), post.is_empty()) {
+				(true, true) => continue,
+				(true, false) => AccountDiff::born(post),
+				(false, true) => AccountDiff::died(pre),
+				(false, false) => AccountDiff::changed(pre, post),
+			};
+			if !diff.is_same() {
+				state_diff.insert(address, diff);
+			}
+		}
+
+		Some(state_diff)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::formatters::ResponseFormatter;
+
+	fn account(balance: u64, nonce: u64, storage: &[(H256, H256)]) -> AccountState {
+		AccountState {
+			balance: Some(balance.into()),
+			nonce: Some(nonce),
+			code: Some(vec![]),
+			storage: storage.iter().copied().collect(),
+		}
+	}
+
+	#[test]
+	fn only_returns_modified_accounts() {
+		let sender = H160::repeat_byte(1);
+		let untouched = H160::repeat_byte(2);
+		let created = H160::repeat_byte(3);
+		let slot = H256::repeat_byte(4);
+		let value = H256::repeat_byte(5);
+
+		let mut listener = Listener::new(false);
+		listener.pre_state.insert(sender, account(10, 1, &[(slot, value)]));
+		listener.post_state.insert(sender, account(7, 2, &[(slot, value)]));
+		listener.pre_state.insert(untouched, account(10, 0, &[]));
+		listener.post_state.insert(untouched, account(10, 0, &[]));
+		listener.pre_state.insert(created, account(0, 0, &[(slot, H256::zero())]));
+		listener.post_state.insert(created, account(1, 1, &[(slot, value)]));
+
+		assert_eq!(
+			serde_json::to_value(Formatter::format(listener).unwrap()).unwrap(),
+			serde_json::json!({
+				format!("{:?}", sender): {
+					"balance": { "*": { "from": "0xa", "to": "0x7" } },
+					"nonce": { "*": { "from": "0x1", "to": "0x2" } },
+					"code": "=",
+					"storage": {},
+				},
+				format!("{:?}", created): {
+					"balance": { "+": "0x1" },
+					"nonce": { "+": "0x1" },
+					"code": { "+": "0x" },
+					"storage": { format!("{:?}", slot): { "+": format!("{:?}", value) } },
+				},
+			})
+		);
+	}
+
+	#[test]
+	fn destroyed_accounts_are_died() {
+		let destroyed = H160::repeat_byte(1);
+
+		let mut listener = Listener::new(false);
+		listener.pre_state.insert(destroyed, account(3, 1, &[]));
+		listener.post_state.insert(destroyed, account(0, 0, &[]));
+
+		let state_diff = Formatter::format(listener).unwrap();
+		assert_eq!(state_diff[&destroyed].balance, Diff::Died(3.into()));
+		assert_eq!(state_diff[&destroyed].nonce, Diff::Died(1.into()));
+	}
+}
@@ -36,7 +36,7 @@ impl super::ResponseFormatter for Formatter {
 	fn format(mut listener: Listener) -> Option<Vec<TransactionTrace>> {
 		// Remove empty BTreeMaps pushed to `entries`.
 		// I.e. InvalidNonce or other pallet_evm::runner exits
-		listener.entries.retain(|x| !x.is_empty());
+		listener.remove_empty_call_stacks();
 		let mut traces = Vec::new();
 		for (eth_tx_index, entry) in listener.entries.iter().enumerate() {
 			let mut tx_traces: Vec<_> = entry
@@ -0,0 +1,103 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Formats the operations executed by a transaction into the `vmTrace` of Parity's
+//! `trace_replayTransaction`. The code of the called contracts must be filled in the listener's
+//! `codes` by the caller.
+
+use crate::{
+	listeners::vm_trace::{Frame, FrameCode, Listener, Operation},
+	types::serialization::*,
+};
+use ethereum_types::{H160, U256};
+use serde::Serialize;
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// Operations executed by a call, with the code they are read from.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+pub struct VmTrace {
+	#[serde(serialize_with = "bytes_0x_serialize")]
+	pub code: Vec<u8>,
+	pub ops: Vec<VmOperation>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+pub struct VmOperation {
+	pub pc: u64,
+	pub cost: u64,
+	/// Effects of the operation, `None` if it failed.
+	pub ex: Option<VmExecutedOperation>,
+	/// Call or create made by the operation.
+	pub sub: Option<VmTrace>,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+pub struct VmExecutedOperation {
+	/// Gas left after the operation.
+	pub used: u64,
+	/// Stack items pushed by the operation.
+	pub push: Vec<U256>,
+	pub mem: Option<MemoryDiff>,
+	pub store: Option<StorageDiff>,
+}
+
+/// Memory written by an operation.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+pub struct MemoryDiff {
+	pub off: u64,
+	#[serde(serialize_with = "bytes_0x_serialize")]
+	pub data: Vec<u8>,
+}
+
+/// Storage slot written by an operation.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+pub struct StorageDiff {
+	pub key: U256,
+	pub val: U256,
+}
+
+impl VmTrace {
+	fn new(frame: Frame, codes: &BTreeMap<H160, Vec<u8>>) -> Self {
+		let code = match frame.code {
+			FrameCode::Init(code) => code,
+			FrameCode::Deployed(address) => codes.get(&address).cloned().unwrap_or_default(),
+		};
+
+		// The gas left after an operation is the gas left before the next one.
+		let mut ops: Vec<VmOperation> = Vec::with_capacity(frame.ops.len());
+		let mut gas_after = None;
+		for op in frame.ops.into_iter().rev() {
+			let gas = op.gas;
+			ops.push(VmOperation::new(op, gas_after, codes));
+			gas_after = gas;
+		}
+		ops.reverse();
+
+		Self { code, ops }
+	}
+}
+
+impl VmOperation {
+	fn new(op: Operation, gas_after: Option<u64>, codes: &BTreeMap<H160, Vec<u8>>) -> Self {
+		let gas = op.gas.unwrap_or_default();
+		let ex = (op.executed == Some(true)).then(|| VmExecutedOperation {
+			used: gas_after.unwrap_or_else(|| gas.saturating_sub(op.cost)),
+			push: op.push,
+			mem: op.mem,
+			store: op.store,
+		});
+
+		Self { pc: op.pc, cost: op.cost, ex, sub: op.sub.map(|sub| VmTrace::new(sub, codes)) }
+	}
+}
+
+pub struct Formatter;
+
+impl super::ResponseFormatter for Formatter {
+	type Listener = Listener;
+	type Response = VmTrace;
+
+	fn format(listener: Listener) -> Option<VmTrace> {
+		let codes = listener.codes;
+		listener.trace.map(|trace| VmTrace::new(trace, &codes))
+	}
+}
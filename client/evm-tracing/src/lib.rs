@@ -18,4 +18,5 @@
 
 pub mod formatters;
 pub mod listeners;
+pub mod replay;
 pub mod types;
@@ -15,13 +15,17 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-	formatters::blockscout::{BlockscoutCall as Call, BlockscoutCallInner as CallInner},
+	formatters::{
+		blockscout::{BlockscoutCall as Call, BlockscoutCallInner as CallInner},
+		call_tracer::{CallTracerLog, CallTracerRuntimeCall},
+	},
 	types::{CallResult, CallType, ContextType, CreateResult},
 };
-use ethereum_types::{H160, U256};
+use ethereum_types::{H160, H256, U256};
 use evm_tracing_events::{
-	runtime::{Capture, ExitError, ExitReason, ExitSucceed},
+	runtime::{Capture, ExitError, ExitReason, ExitSucceed, Memory, Stack},
 	Event, EvmEvent, GasometerEvent, Listener as ListenerT, RuntimeEvent, StepEventFilter,
+	SubstrateEvent,
 };
 use std::{collections::btree_map::BTreeMap, vec, vec::Vec};
 
@@ -37,6 +41,34 @@ enum TracingVersion {
 	Legacy,
 }
 
+/// What started the EVM executions of a call stack, when tracing a block.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum CallStackOrigin {
+	/// An Ethereum transaction, by index among the traced Ethereum transactions.
+	Ethereum(u32),
+	/// A Substrate extrinsic (`pallet_evm` call, XCM, ...), by index in the block.
+	Substrate(u32),
+}
+
+impl CallStackOrigin {
+	/// Hash of the transaction of the call stack. Substrate extrinsics are given a synthetic
+	/// hash, derived from the hash of their Substrate block and their index.
+	pub fn transaction_hash(
+		self,
+		substrate_block_hash: H256,
+		eth_tx_hashes: &[H256],
+	) -> Option<H256> {
+		match self {
+			CallStackOrigin::Ethereum(index) => eth_tx_hashes.get(index as usize).copied(),
+			CallStackOrigin::Substrate(index) => {
+				let mut preimage = substrate_block_hash.as_bytes().to_vec();
+				preimage.extend_from_slice(&index.to_be_bytes());
+				Some(H256(sp_core::hashing::keccak_256(&preimage)))
+			},
+		}
+	}
+}
+
 pub struct Listener {
 	/// Version of the tracing.
 	/// Defaults to legacy, and switch to a more modern version if recently added events are
@@ -48,6 +80,8 @@ pub struct Listener {
 
 	// Final logs.
 	pub entries: Vec<BTreeMap<u32, Call>>,
+	/// Origin of each call stack of `entries`, when tracing a block.
+	pub origins: Vec<CallStackOrigin>,
 	// Next index to use.
 	entries_next_index: u32,
 	// Stack of contexts with data to keep between events.
@@ -80,6 +114,13 @@ pub struct Listener {
 	/// True if only the `GasometerEvent::RecordTransaction` event has been received.
 	/// Allow to correctly handle transactions that cannot pay for the tx data in Legacy mode.
 	record_transaction_event_only: bool,
+
+	/// Record the logs emitted by each call. Requires the stack and memory of `LOG` steps.
+	pub with_log: bool,
+	/// Only format the top call, without its subcalls.
+	pub only_top_call: bool,
+	/// Log of the current `LOG` step, recorded if the step succeeds.
+	pending_log: Option<CallTracerLog>,
 }
 
 struct Context {
@@ -99,6 +140,11 @@ struct Context {
 	data: Vec<u8>,
 	// to / create address
 	to: H160,
+
+	// logs emitted by this context, discarded if it fails
+	logs: Vec<CallTracerLog>,
+	// runtime calls dispatched by this context, if it is a precompile
+	runtime_calls: Vec<CallTracerRuntimeCall>,
 }
 
 impl Default for Listener {
@@ -108,6 +154,7 @@ impl Default for Listener {
 			transaction_cost: 0,
 
 			entries: vec![],
+			origins: vec![],
 			entries_next_index: 0,
 
 			context_stack: vec![],
@@ -117,6 +164,10 @@ impl Default for Listener {
 			skip_next_context: false,
 			call_list_first_transaction: true,
 			record_transaction_event_only: false,
+
+			with_log: false,
+			only_top_call: false,
+			pending_log: None,
 		}
 	}
 }
@@ -141,6 +192,7 @@ impl Listener {
 			if context.entries_index == 0 {
 				gas_used += self.transaction_cost;
 			}
+			self.discard_logs_since(context.entries_index);
 
 			let entry = match context.context_type {
 				ContextType::Call(call_type) => {
@@ -161,6 +213,8 @@ impl Listener {
 							input: context.data,
 							res,
 						},
+						logs: vec![],
+						runtime_calls: context.runtime_calls,
 					}
 				},
 				ContextType::Create => {
@@ -176,6 +230,8 @@ impl Listener {
 						gas_used: gas_used.into(),
 						from: context.from,
 						inner: CallInner::Create { init: context.data, res },
+						logs: vec![],
+						runtime_calls: context.runtime_calls,
 					}
 				},
 			};
@@ -206,6 +262,8 @@ impl Listener {
 					input: vec![],
 					res,
 				},
+				logs: vec![],
+				runtime_calls: vec![],
 			};
 
 			self.insert_entry(self.entries_next_index, entry);
@@ -236,7 +294,24 @@ impl Listener {
 	}
 
 	pub fn runtime_event(&mut self, event: RuntimeEvent) {
+		// A log is only emitted if its step succeeds.
+		let pending_log = match event {
+			RuntimeEvent::StepResult { .. } => self.pending_log.take(),
+			_ => None,
+		};
+
 		match event {
+			RuntimeEvent::Step { context, opcode, stack, memory, .. } if self.with_log => {
+				self.pending_log = Self::log_from_step(context.address, &opcode, stack, memory);
+			},
+			RuntimeEvent::StepResult { result: Ok(()), .. } => {
+				if let (Some(mut log), Some(context)) = (pending_log, self.context_stack.last_mut())
+				{
+					// Position of the log among the subcalls of the context.
+					log.position = context.subtraces.into();
+					context.logs.push(log);
+				}
+			},
 			RuntimeEvent::StepResult { result: Err(Capture::Trap(opcode)), .. } => {
 				if let Some(ContextType::Call(call_type)) = ContextType::from(opcode) {
 					self.call_type = Some(call_type)
@@ -286,6 +361,9 @@ impl Listener {
 
 					data,
 					to: address,
+
+					logs: vec![],
+					runtime_calls: vec![],
 				});
 
 				self.entries_next_index += 1;
@@ -310,6 +388,9 @@ impl Listener {
 
 					data: init_code,
 					to: address,
+
+					logs: vec![],
+					runtime_calls: vec![],
 				});
 
 				self.entries_next_index += 1;
@@ -334,6 +415,9 @@ impl Listener {
 
 					data: init_code,
 					to: address,
+
+					logs: vec![],
+					runtime_calls: vec![],
 				});
 
 				self.entries_next_index += 1;
@@ -383,6 +467,9 @@ impl Listener {
 
 						data: input.to_vec(),
 						to: code_address,
+
+						logs: vec![],
+						runtime_calls: vec![],
 					});
 
 					self.entries_next_index += 1;
@@ -426,6 +513,9 @@ impl Listener {
 
 						data: init_code.to_vec(),
 						to: address,
+
+						logs: vec![],
+						runtime_calls: vec![],
 					});
 
 					self.entries_next_index += 1;
@@ -453,6 +543,8 @@ impl Listener {
 						gas: 0.into(),
 						gas_used: 0.into(),
 						inner: CallInner::SelfDestruct { to: target, balance },
+						logs: vec![],
+						runtime_calls: vec![],
 					},
 				);
 				self.entries_next_index += 1;
@@ -505,6 +597,14 @@ impl Listener {
 				gas_used += self.transaction_cost;
 			}
 
+			// Logs of failed calls, including the ones of their subcalls, are discarded.
+			let logs = if matches!(reason, ExitReason::Succeed(_)) {
+				context.logs
+			} else {
+				self.discard_logs_since(context.entries_index);
+				vec![]
+			};
+
 			Some((
 				context.entries_index,
 				match context.context_type {
@@ -533,6 +633,8 @@ impl Listener {
 								input: context.data,
 								res,
 							},
+							logs,
+							runtime_calls: context.runtime_calls,
 						}
 					},
 					ContextType::Create => {
@@ -556,6 +658,8 @@ impl Listener {
 							gas_used: gas_used.into(),
 							from: context.from,
 							inner: CallInner::Create { init: context.data, res },
+							logs,
+							runtime_calls: context.runtime_calls,
 						}
 					},
 				},
@@ -564,6 +668,79 @@ impl Listener {
 			None
 		}
 	}
+
+	/// Starts the call stack of the next transaction of the traced block.
+	fn new_call_stack(&mut self, origin: CallStackOrigin) {
+		if !self.call_list_first_transaction {
+			self.finish_transaction();
+			self.skip_next_context = false;
+		} else {
+			self.call_list_first_transaction = false;
+		}
+		// Each call stack has its own entries, even if it doesn't record any call, to stay
+		// aligned with `origins`.
+		self.entries.push(BTreeMap::new());
+		self.origins.push(origin);
+	}
+
+	/// Removes the call stacks which didn't record any call, i.e. invalid Ethereum transactions
+	/// and Substrate extrinsics which didn't execute the EVM.
+	pub fn remove_empty_call_stacks(&mut self) {
+		// Not tracing a block, there is no origin to keep aligned.
+		if self.origins.len() != self.entries.len() {
+			self.entries.retain(|entry| !entry.is_empty());
+			return
+		}
+
+		let entries = core::mem::take(&mut self.entries);
+		let origins = core::mem::take(&mut self.origins);
+		(self.entries, self.origins) =
+			entries.into_iter().zip(origins).filter(|(entry, _)| !entry.is_empty()).unzip();
+	}
+
+	/// Discards the logs of the entries of the current transaction from `entries_index`, i.e. of
+	/// a context and of its subcalls.
+	fn discard_logs_since(&mut self, entries_index: u32) {
+		if let Some(entries) = self.entries.last_mut() {
+			for (_, entry) in entries.range_mut(entries_index..) {
+				entry.logs.clear();
+			}
+		}
+	}
+
+	/// Builds the log of a `LOG` step from its stack and memory.
+	fn log_from_step(
+		address: H160,
+		opcode: &[u8],
+		stack: Option<Stack>,
+		memory: Option<Memory>,
+	) -> Option<CallTracerLog> {
+		let topics_count = match opcode {
+			b"Log0" => 0,
+			b"Log1" => 1,
+			b"Log2" => 2,
+			b"Log3" => 3,
+			b"Log4" => 4,
+			_ => return None,
+		};
+		let (stack, memory) = (stack?.data, memory?.data);
+
+		// The top of the stack is its last item: memory offset, size and topics.
+		let mut items = stack.iter().rev();
+		let offset = usize::try_from(U256::from_big_endian(items.next()?.as_bytes())).ok()?;
+		let size = usize::try_from(U256::from_big_endian(items.next()?.as_bytes())).ok()?;
+		let topics: Vec<H256> = items.take(topics_count).copied().collect();
+		if topics.len() != topics_count {
+			return None
+		}
+
+		// Memory is expanded by the step itself, so it might be shorter than the log data.
+		let data = (offset..offset.checked_add(size)?)
+			.map(|i| memory.get(i).copied().unwrap_or_default())
+			.collect();
+
+		Some(CallTracerLog { address, topics, data, position: U256::zero() })
+	}
 }
 
 fn error_message(error: &ExitError) -> Vec<u8> {
@@ -592,19 +769,31 @@ impl ListenerT for Listener {
 			Event::Gasometer(gasometer_event) => self.gasometer_event(gasometer_event),
 			Event::Runtime(runtime_event) => self.runtime_event(runtime_event),
 			Event::Evm(evm_event) => self.evm_event(evm_event),
-			Event::CallListNew() =>
-				if !self.call_list_first_transaction {
-					self.finish_transaction();
-					self.skip_next_context = false;
-					self.entries.push(BTreeMap::new());
-				} else {
-					self.call_list_first_transaction = false;
+			Event::CallListNew() => {
+				let eth_index = self
+					.origins
+					.iter()
+					.filter(|origin| matches!(origin, CallStackOrigin::Ethereum(_)))
+					.count();
+				self.new_call_stack(CallStackOrigin::Ethereum(eth_index as u32));
+			},
+			Event::Substrate(SubstrateEvent::CallListNew { extrinsic_index }) =>
+				self.new_call_stack(CallStackOrigin::Substrate(extrinsic_index)),
+			Event::Substrate(SubstrateEvent::Dispatch { pallet, call }) =>
+				if let Some(context) = self.context_stack.last_mut() {
+					context.runtime_calls.push(CallTracerRuntimeCall { pallet, call });
 				},
+			Event::Substrate(SubstrateEvent::Log { address, topics, data }) =>
+				if let (true, Some(context)) = (self.with_log, self.context_stack.last_mut()) {
+					let position = context.subtraces.into();
+					context.logs.push(CallTracerLog { address, topics, data, position });
+				},
+			Event::Substrate(SubstrateEvent::Precompile) => (),
 		};
 	}
 
 	fn step_event_filter(&self) -> StepEventFilter {
-		StepEventFilter { enable_memory: false, enable_stack: false }
+		StepEventFilter { enable_memory: self.with_log, enable_stack: self.with_log }
 	}
 }
 
@@ -616,7 +805,7 @@ mod tests {
 	use evm_tracing_events::{
 		evm::CreateScheme,
 		gasometer::Snapshot,
-		runtime::{Memory, Stack},
+		runtime::{ExitRevert, Memory, Stack},
 		Context as EvmContext,
 	};
 
@@ -1050,4 +1239,189 @@ mod tests {
 		// nested.
 		assert_eq!(listener.entries[0].len(), (depth * (subdepth + 1)) + 1);
 	}
+
+	fn do_log_step_event(listener: &mut Listener, topic: H256, data: &[u8]) {
+		listener.runtime_event(RuntimeEvent::Step {
+			context: test_context(),
+			opcode: b"Log1".to_vec(),
+			position: Ok(0),
+			// The top of the stack is its last item.
+			stack: Some(Stack {
+				data: vec![topic, H256::from_low_u64_be(data.len() as u64), H256::zero()],
+				limit: 1024,
+			}),
+			memory: Some(Memory {
+				data: data.to_vec(),
+				effective_len: data.len().into(),
+				limit: u64::MAX,
+			}),
+		});
+		listener.runtime_event(RuntimeEvent::StepResult { result: Ok(()), return_value: vec![] });
+	}
+
+	fn do_exit_with_reason_event(listener: &mut Listener, reason: ExitReason) {
+		listener.evm_event(EvmEvent::Exit { reason, return_value: vec![] });
+	}
+
+	fn test_log(topic: H256, data: &[u8], position: u32) -> CallTracerLog {
+		CallTracerLog {
+			address: H160::default(),
+			topics: vec![topic],
+			data: data.to_vec(),
+			position: position.into(),
+		}
+	}
+
+	fn do_precompile_log_event(listener: &mut Listener, topic: H256, data: &[u8]) {
+		listener.event(Event::Substrate(SubstrateEvent::Log {
+			address: H160::default(),
+			topics: vec![topic],
+			data: data.to_vec(),
+		}));
+	}
+
+	#[test]
+	fn call_with_log_records_logs_of_precompiles() {
+		let mut listener = Listener::default();
+		listener.with_log = true;
+		do_transact_call_event(&mut listener);
+		do_gasometer_event(&mut listener);
+		do_evm_call_event(&mut listener);
+		// Precompile call
+		do_evm_call_event(&mut listener);
+		do_precompile_log_event(&mut listener, H256::repeat_byte(1), &[0xaa]);
+		listener.event(Event::Substrate(SubstrateEvent::Precompile));
+		do_exit_with_reason_event(&mut listener, ExitReason::Succeed(ExitSucceed::Returned));
+		// Main
+		do_log_step_event(&mut listener, H256::repeat_byte(2), &[]);
+		do_exit_with_reason_event(&mut listener, ExitReason::Succeed(ExitSucceed::Stopped));
+		listener.finish_transaction();
+
+		assert_eq!(listener.entries[0][&0].logs, vec![test_log(H256::repeat_byte(2), &[], 1)]);
+		assert_eq!(listener.entries[0][&1].logs, vec![test_log(H256::repeat_byte(1), &[0xaa], 0)]);
+	}
+
+	#[test]
+	fn call_without_log_ignores_logs_of_precompiles() {
+		let mut listener = Listener::default();
+		do_transact_call_event(&mut listener);
+		do_gasometer_event(&mut listener);
+		do_evm_call_event(&mut listener);
+		do_precompile_log_event(&mut listener, H256::repeat_byte(1), &[0xaa]);
+		do_exit_with_reason_event(&mut listener, ExitReason::Succeed(ExitSucceed::Returned));
+		listener.finish_transaction();
+
+		assert!(listener.entries[0][&0].logs.is_empty());
+	}
+
+	#[test]
+	fn call_with_log_records_logs_with_position() {
+		let mut listener = Listener::default();
+		listener.with_log = true;
+		do_transact_call_event(&mut listener);
+		do_gasometer_event(&mut listener);
+		do_evm_call_event(&mut listener);
+		do_log_step_event(&mut listener, H256::repeat_byte(1), &[0xaa, 0xbb]);
+		// Nested
+		do_evm_call_event(&mut listener);
+		do_log_step_event(&mut listener, H256::repeat_byte(2), &[]);
+		do_exit_with_reason_event(&mut listener, ExitReason::Succeed(ExitSucceed::Stopped));
+		// Main
+		do_log_step_event(&mut listener, H256::repeat_byte(3), &[0xcc]);
+		do_exit_with_reason_event(&mut listener, ExitReason::Succeed(ExitSucceed::Stopped));
+		listener.finish_transaction();
+
+		assert_eq!(
+			listener.entries[0][&0].logs,
+			vec![
+				test_log(H256::repeat_byte(1), &[0xaa, 0xbb], 0),
+				test_log(H256::repeat_byte(3), &[0xcc], 1),
+			]
+		);
+		assert_eq!(listener.entries[0][&1].logs, vec![test_log(H256::repeat_byte(2), &[], 0)]);
+	}
+
+	#[test]
+	fn call_without_log_ignores_logs() {
+		let mut listener = Listener::default();
+		do_transact_call_event(&mut listener);
+		do_gasometer_event(&mut listener);
+		do_evm_call_event(&mut listener);
+		do_log_step_event(&mut listener, H256::repeat_byte(1), &[0xaa]);
+		do_exit_with_reason_event(&mut listener, ExitReason::Succeed(ExitSucceed::Stopped));
+		listener.finish_transaction();
+
+		assert!(listener.entries[0][&0].logs.is_empty());
+	}
+
+	#[test]
+	fn failed_call_discards_logs_of_its_subcalls() {
+		let mut listener = Listener::default();
+		listener.with_log = true;
+		do_transact_call_event(&mut listener);
+		do_gasometer_event(&mut listener);
+		do_evm_call_event(&mut listener);
+		do_log_step_event(&mut listener, H256::repeat_byte(1), &[]);
+		// Nested, reverting after a successful subcall.
+		do_evm_call_event(&mut listener);
+		do_log_step_event(&mut listener, H256::repeat_byte(2), &[]);
+		do_evm_call_event(&mut listener);
+		do_log_step_event(&mut listener, H256::repeat_byte(3), &[]);
+		do_exit_with_reason_event(&mut listener, ExitReason::Succeed(ExitSucceed::Stopped));
+		do_exit_with_reason_event(&mut listener, ExitReason::Revert(ExitRevert::Reverted));
+		// Main
+		do_exit_with_reason_event(&mut listener, ExitReason::Succeed(ExitSucceed::Stopped));
+		listener.finish_transaction();
+
+		assert_eq!(listener.entries[0][&0].logs, vec![test_log(H256::repeat_byte(1), &[], 0)]);
+		assert!(listener.entries[0][&1].logs.is_empty());
+		assert!(listener.entries[0][&2].logs.is_empty());
+	}
+
+	#[test]
+	fn block_call_stacks_keep_their_origin() {
+		let mut listener = Listener::default();
+		listener.event(Event::CallListNew());
+		do_transact_call_event(&mut listener);
+		do_exit_with_reason_event(&mut listener, ExitReason::Succeed(ExitSucceed::Stopped));
+		// Substrate extrinsic without EVM execution.
+		listener.event(Event::Substrate(SubstrateEvent::CallListNew { extrinsic_index: 1 }));
+		// Substrate extrinsic calling a precompile dispatching a runtime call.
+		listener.event(Event::Substrate(SubstrateEvent::CallListNew { extrinsic_index: 2 }));
+		do_transact_call_event(&mut listener);
+		listener.event(Event::Substrate(SubstrateEvent::Dispatch {
+			pallet: b"PeaqDid".to_vec(),
+			call: b"add_attribute".to_vec(),
+		}));
+		do_exit_with_reason_event(&mut listener, ExitReason::Succeed(ExitSucceed::Stopped));
+		listener.finish_transaction();
+		listener.remove_empty_call_stacks();
+
+		assert_eq!(
+			listener.origins,
+			vec![CallStackOrigin::Ethereum(0), CallStackOrigin::Substrate(2)]
+		);
+		assert_eq!(listener.entries.len(), 2);
+		assert!(listener.entries[0][&0].runtime_calls.is_empty());
+		assert_eq!(
+			listener.entries[1].values().next().unwrap().runtime_calls,
+			vec![CallTracerRuntimeCall {
+				pallet: b"PeaqDid".to_vec(),
+				call: b"add_attribute".to_vec()
+			}]
+		);
+
+		let block_hash = H256::repeat_byte(1);
+		let eth_tx_hashes = [H256::repeat_byte(2)];
+		assert_eq!(
+			listener.origins[0].transaction_hash(block_hash, &eth_tx_hashes),
+			Some(eth_tx_hashes[0])
+		);
+		let substrate_hash = listener.origins[1].transaction_hash(block_hash, &eth_tx_hashes);
+		assert!(substrate_hash.is_some());
+		assert_ne!(
+			substrate_hash,
+			CallStackOrigin::Substrate(1).transaction_hash(block_hash, &eth_tx_hashes)
+		);
+	}
 }
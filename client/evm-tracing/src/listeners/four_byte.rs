@@ -0,0 +1,149 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Listener counting the calls by function selector and calldata size, used by the
+//! `4byteTracer`.
+
+use evm_tracing_events::{Event, EvmEvent, Listener as ListenerT, StepEventFilter, SubstrateEvent};
+use std::collections::btree_map::BTreeMap;
+
+#[derive(Debug, Default)]
+pub struct Listener {
+	/// Number of calls by selector and size of the calldata following it.
+	pub calls: BTreeMap<([u8; 4], usize), u64>,
+	/// Input of the calls being executed, `None` for creates. A call is only counted once it
+	/// exits, as whether it was executed by a precompile is only known then.
+	call_stack: Vec<Option<Vec<u8>>>,
+}
+
+impl Listener {
+	pub fn using<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+		evm_tracing_events::using(self, f)
+	}
+
+	fn record_input(&mut self, input: &[u8]) {
+		// Calls without a full selector are not function calls.
+		if input.len() < 4 {
+			return
+		}
+		let mut selector = [0u8; 4];
+		selector.copy_from_slice(&input[..4]);
+		*self.calls.entry((selector, input.len() - 4)).or_default() += 1;
+	}
+
+	pub fn evm_event(&mut self, event: EvmEvent) {
+		match event {
+			// The transaction itself is also emitted as a `Call`, and a precompile subcall is
+			// followed by the `Call` of its target.
+			EvmEvent::Call { input, .. } => self.call_stack.push(Some(input)),
+			EvmEvent::Create { .. } => self.call_stack.push(None),
+			EvmEvent::Exit { .. } =>
+				if let Some(Some(input)) = self.call_stack.pop() {
+					self.record_input(&input);
+				},
+			_ => (),
+		}
+	}
+}
+
+impl ListenerT for Listener {
+	fn event(&mut self, event: Event) {
+		match event {
+			Event::Evm(e) => self.evm_event(e),
+			// Like Geth, calls to precompiles are not counted as they are not contract calls.
+			Event::Substrate(SubstrateEvent::Precompile) =>
+				if let Some(input) = self.call_stack.last_mut() {
+					*input = None;
+				},
+			_ => (),
+		}
+	}
+
+	fn step_event_filter(&self) -> StepEventFilter {
+		StepEventFilter { enable_memory: false, enable_stack: false }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::{H160, U256};
+	use evm_tracing_events::{
+		runtime::{ExitReason, ExitSucceed},
+		Context,
+	};
+
+	fn call(input: &[u8]) -> Event {
+		Event::Evm(EvmEvent::Call {
+			code_address: H160::repeat_byte(1),
+			transfer: None,
+			input: input.to_vec(),
+			target_gas: None,
+			is_static: false,
+			context: Context {
+				address: H160::repeat_byte(1),
+				caller: H160::repeat_byte(2),
+				apparent_value: U256::zero(),
+			},
+		})
+	}
+
+	fn exit() -> Event {
+		Event::Evm(EvmEvent::Exit {
+			reason: ExitReason::Succeed(ExitSucceed::Stopped),
+			return_value: vec![],
+		})
+	}
+
+	#[test]
+	fn counts_calls_by_selector_and_size() {
+		let mut listener = Listener::default();
+		listener.event(Event::Evm(EvmEvent::TransactCall {
+			caller: H160::repeat_byte(2),
+			address: H160::repeat_byte(1),
+			value: U256::zero(),
+			data: vec![1, 2, 3, 4, 0],
+			gas_limit: 100_000,
+		}));
+		listener.event(call(&[1, 2, 3, 4, 0]));
+		listener.event(call(&[5, 6, 7, 8]));
+		listener.event(exit());
+		listener.event(call(&[5, 6]));
+		listener.event(exit());
+		listener.event(exit());
+
+		assert_eq!(
+			listener.calls,
+			BTreeMap::from([(([1, 2, 3, 4], 1), 1), (([5, 6, 7, 8], 0), 1)])
+		);
+	}
+
+	#[test]
+	fn skips_precompile_calls() {
+		let mut listener = Listener::default();
+		listener.event(call(&[1, 2, 3, 4]));
+		listener.event(call(&[5, 6, 7, 8]));
+		// A subcall of the precompile to a contract is counted.
+		listener.event(Event::Evm(EvmEvent::PrecompileSubcall {
+			code_address: H160::repeat_byte(3),
+			transfer: None,
+			input: vec![9, 9, 9, 9],
+			target_gas: None,
+			is_static: false,
+			context: Context {
+				address: H160::repeat_byte(3),
+				caller: H160::repeat_byte(1),
+				apparent_value: U256::zero(),
+			},
+		}));
+		listener.event(call(&[9, 9, 9, 9]));
+		listener.event(exit());
+		listener.event(Event::Substrate(SubstrateEvent::Precompile));
+		listener.event(exit());
+		listener.event(exit());
+
+		assert_eq!(
+			listener.calls,
+			BTreeMap::from([(([1, 2, 3, 4], 0), 1), (([9, 9, 9, 9], 0), 1)])
+		);
+	}
+}
@@ -15,7 +15,17 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 pub mod call_list;
+pub mod four_byte;
+pub mod opcount;
+pub mod prestate;
 pub mod raw;
+pub mod timeout;
+pub mod vm_trace;
 
-pub use call_list::Listener as CallList;
+pub use call_list::{CallStackOrigin, Listener as CallList};
+pub use four_byte::Listener as FourByte;
+pub use opcount::Listener as Opcount;
+pub use prestate::Listener as Prestate;
 pub use raw::Listener as Raw;
+pub use timeout::Listener as Timeout;
+pub use vm_trace::Listener as VmTrace;
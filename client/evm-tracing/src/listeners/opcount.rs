@@ -0,0 +1,29 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Listener counting the executed opcodes, used by the `opcountTracer`.
+
+use evm_tracing_events::{Event, Listener as ListenerT, RuntimeEvent, StepEventFilter};
+
+#[derive(Debug, Default)]
+pub struct Listener {
+	/// Number of executed opcodes, in all contexts.
+	pub count: u64,
+}
+
+impl Listener {
+	pub fn using<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+		evm_tracing_events::using(self, f)
+	}
+}
+
+impl ListenerT for Listener {
+	fn event(&mut self, event: Event) {
+		if let Event::Runtime(RuntimeEvent::Step { .. }) = event {
+			self.count = self.count.saturating_add(1);
+		}
+	}
+
+	fn step_event_filter(&self) -> StepEventFilter {
+		StepEventFilter { enable_memory: false, enable_stack: false }
+	}
+}
@@ -0,0 +1,148 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Listener collecting the accounts and storage slots accessed by a transaction, used by the
+//! `prestateTracer`.
+//!
+//! EVM events don't carry the balance, nonce or code of the accounts, so the listener only keeps
+//! track of what is accessed. The state of the accessed accounts is read by the caller before and
+//! after the execution and stored in `pre_state` and `post_state`.
+
+use crate::formatters::prestate::AccountState;
+use ethereum_types::{H160, H256};
+use evm_tracing_events::{
+	evm::Transfer, Event, EvmEvent, Listener as ListenerT, RuntimeEvent, StepEventFilter,
+};
+use std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
+
+#[derive(Debug, Default)]
+pub struct Listener {
+	/// Only return the fields modified by the transaction, before and after it.
+	pub diff_mode: bool,
+	/// Accessed accounts, with their accessed storage slots.
+	pub accessed: BTreeMap<H160, BTreeSet<H256>>,
+	/// State of the accessed accounts before the execution.
+	pub pre_state: BTreeMap<H160, AccountState>,
+	/// State of the accessed accounts after the execution.
+	pub post_state: BTreeMap<H160, AccountState>,
+}
+
+impl Listener {
+	pub fn new(diff_mode: bool) -> Self {
+		Self { diff_mode, ..Default::default() }
+	}
+
+	pub fn using<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+		evm_tracing_events::using(self, f)
+	}
+
+	fn access_account(&mut self, address: H160) {
+		self.accessed.entry(address).or_default();
+	}
+
+	fn access_transfer(&mut self, transfer: Option<Transfer>) {
+		if let Some(Transfer { source, target, .. }) = transfer {
+			self.access_account(source);
+			self.access_account(target);
+		}
+	}
+
+	pub fn evm_event(&mut self, event: EvmEvent) {
+		match event {
+			EvmEvent::TransactCall { caller, address, .. } |
+			EvmEvent::TransactCreate { caller, address, .. } |
+			EvmEvent::TransactCreate2 { caller, address, .. } |
+			EvmEvent::Create { caller, address, .. } => {
+				self.access_account(caller);
+				self.access_account(address);
+			},
+			EvmEvent::Call { code_address, transfer, context, .. } |
+			EvmEvent::PrecompileSubcall { code_address, transfer, context, .. } => {
+				self.access_account(code_address);
+				self.access_account(context.address);
+				self.access_transfer(transfer);
+			},
+			EvmEvent::Suicide { address, target, .. } => {
+				self.access_account(address);
+				self.access_account(target);
+			},
+			EvmEvent::Exit { .. } => (),
+		}
+	}
+
+	pub fn runtime_event(&mut self, event: RuntimeEvent) {
+		match event {
+			RuntimeEvent::SLoad { address, index, .. } |
+			RuntimeEvent::SStore { address, index, .. } => {
+				self.accessed.entry(address).or_default().insert(index);
+			},
+			// We ignore other kinds of message if any (new ones may be added in the future).
+			#[allow(unreachable_patterns)]
+			_ => (),
+		}
+	}
+}
+
+impl ListenerT for Listener {
+	fn event(&mut self, event: Event) {
+		match event {
+			Event::Evm(e) => self.evm_event(e),
+			Event::Runtime(e) => self.runtime_event(e),
+			_ => {},
+		};
+	}
+
+	fn step_event_filter(&self) -> StepEventFilter {
+		StepEventFilter { enable_memory: false, enable_stack: false }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::U256;
+	use evm_tracing_events::Context as EvmContext;
+
+	#[test]
+	fn collects_accessed_accounts_and_slots() {
+		let caller = H160::repeat_byte(1);
+		let contract = H160::repeat_byte(2);
+		let callee = H160::repeat_byte(3);
+		let slot = H256::repeat_byte(4);
+
+		let mut listener = Listener::new(false);
+		listener.event(Event::Evm(EvmEvent::TransactCall {
+			caller,
+			address: contract,
+			value: U256::zero(),
+			data: vec![],
+			gas_limit: 100_000,
+		}));
+		listener.event(Event::Runtime(RuntimeEvent::SLoad {
+			address: contract,
+			index: slot,
+			value: H256::zero(),
+		}));
+		listener.event(Event::Evm(EvmEvent::Call {
+			code_address: callee,
+			transfer: Some(Transfer { source: contract, target: callee, value: U256::one() }),
+			input: vec![],
+			target_gas: None,
+			is_static: false,
+			context: EvmContext { address: callee, caller: contract, apparent_value: U256::one() },
+		}));
+		listener.event(Event::Runtime(RuntimeEvent::SStore {
+			address: callee,
+			index: slot,
+			value: H256::repeat_byte(5),
+		}));
+
+		assert_eq!(
+			listener.accessed,
+			BTreeMap::from([
+				(caller, BTreeSet::new()),
+				(contract, BTreeSet::from([slot])),
+				(callee, BTreeSet::from([slot])),
+			])
+		);
+	}
+}
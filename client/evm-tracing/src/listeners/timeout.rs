@@ -0,0 +1,93 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Listener wrapper enforcing the `timeout` of a tracing request.
+//!
+//! The runtime execution can't be interrupted, so once the deadline is reached the events are no
+//! longer forwarded to the inner listener, and the runtime is asked not to clone the memory and
+//! stack of the steps of the following transactions. The caller must then check `expired` and
+//! discard the inner listener.
+
+use evm_tracing_events::{Event, Listener as ListenerT, StepEventFilter};
+use std::time::Instant;
+
+pub struct Listener<L> {
+	pub inner: L,
+	deadline: Instant,
+	expired: bool,
+}
+
+impl<L: ListenerT + 'static> Listener<L> {
+	pub fn new(inner: L, deadline: Instant) -> Self {
+		Self { inner, deadline, expired: false }
+	}
+
+	pub fn using<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+		evm_tracing_events::using(self, f)
+	}
+
+	/// Whether the deadline has been reached while tracing.
+	pub fn expired(&self) -> bool {
+		self.expired
+	}
+}
+
+impl<L: ListenerT> ListenerT for Listener<L> {
+	fn event(&mut self, event: Event) {
+		if self.expired {
+			return
+		}
+		if Instant::now() >= self.deadline {
+			self.expired = true;
+			return
+		}
+		self.inner.event(event);
+	}
+
+	fn step_event_filter(&self) -> StepEventFilter {
+		if self.expired || Instant::now() >= self.deadline {
+			return StepEventFilter { enable_memory: false, enable_stack: false }
+		}
+		self.inner.step_event_filter()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::listeners::Opcount;
+	use ethereum_types::{H160, U256};
+	use evm_tracing_events::{Context, RuntimeEvent};
+	use std::time::Duration;
+
+	fn step() -> Event {
+		Event::Runtime(RuntimeEvent::Step {
+			context: Context {
+				address: H160::zero(),
+				caller: H160::zero(),
+				apparent_value: U256::zero(),
+			},
+			opcode: b"Stop".to_vec(),
+			position: Ok(0),
+			stack: None,
+			memory: None,
+		})
+	}
+
+	#[test]
+	fn stops_forwarding_events_after_deadline() {
+		let mut listener =
+			Listener::new(Opcount::default(), Instant::now() + Duration::from_secs(60));
+		listener.event(step());
+		assert!(!listener.expired());
+		assert_eq!(listener.inner.count, 1);
+
+		listener.deadline = Instant::now();
+		listener.event(step());
+		assert!(listener.expired());
+		assert_eq!(listener.inner.count, 1);
+		assert_eq!(
+			listener.step_event_filter(),
+			StepEventFilter { enable_memory: false, enable_stack: false }
+		);
+	}
+}
@@ -0,0 +1,432 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Listener rebuilding the opcode level trace of a transaction, used by the `vmTrace` of Parity's
+//! `trace_replayTransaction`.
+//!
+//! EVM events don't carry the code of the called contracts, so the listener only keeps track of
+//! the addresses whose code is executed. Their code is read by the caller and stored in `codes`.
+//!
+//! The effects of an operation (pushed stack items, written memory) are read from the stack and
+//! memory of the next step of the same call, and the gas left after it from the gas left before
+//! the next step. The cost of a call or create includes the gas given to the new call, as the
+//! first cost recorded once a call is entered.
+
+use crate::formatters::vm_trace::{MemoryDiff, StorageDiff};
+use ethereum_types::{H160, H256, U256};
+use evm_tracing_events::{
+	runtime::{Capture, ExitReason},
+	Event, EvmEvent, GasometerEvent, Listener as ListenerT, RuntimeEvent, StepEventFilter,
+};
+use std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
+
+#[derive(Debug, Default)]
+pub struct Listener {
+	/// Trace of the transaction, once its outermost call exited.
+	pub trace: Option<Frame>,
+	/// Addresses of the contracts whose code is executed.
+	pub code_addresses: BTreeSet<H160>,
+	/// Code of the contracts of `code_addresses`, filled by the caller.
+	pub codes: BTreeMap<H160, Vec<u8>>,
+	/// Calls being executed, the innermost one last.
+	frames: Vec<Frame>,
+}
+
+/// Code executed by a call.
+#[derive(Debug, PartialEq, Eq)]
+pub enum FrameCode {
+	/// Init code of a create.
+	Init(Vec<u8>),
+	/// Code of the contract at the address.
+	Deployed(H160),
+}
+
+/// Operations executed by a call.
+#[derive(Debug)]
+pub struct Frame {
+	pub code: FrameCode,
+	pub ops: Vec<Operation>,
+	/// Whether the gas given to the call has been added to the cost of its caller's operation.
+	gas_given: bool,
+}
+
+impl Frame {
+	fn new(code: FrameCode) -> Self {
+		Self { code, ops: vec![], gas_given: false }
+	}
+}
+
+#[derive(Debug)]
+pub struct Operation {
+	pub pc: u64,
+	pub cost: u64,
+	/// Gas left before the operation, known from the first cost it records.
+	pub gas: Option<u64>,
+	/// Whether the operation was executed, known from the result of its step.
+	pub executed: Option<bool>,
+	pub push: Vec<U256>,
+	pub mem: Option<MemoryDiff>,
+	pub store: Option<StorageDiff>,
+	/// Call or create made by the operation.
+	pub sub: Option<Frame>,
+	/// Number of stack items pushed and memory range written by the operation, read at the next
+	/// step.
+	pushed: usize,
+	written: Option<(usize, usize)>,
+}
+
+impl Operation {
+	fn new(pc: u64, opcode: &[u8], stack: &[H256]) -> Self {
+		// The top of the stack is its last item.
+		let item = |n: usize| {
+			let index = stack.len().checked_sub(n + 1)?;
+			Some(U256::from_big_endian(stack[index].as_bytes()))
+		};
+		let range = |offset: usize, size: usize| {
+			let offset = usize::try_from(item(offset)?).ok()?;
+			let size = usize::try_from(item(size)?).ok()?;
+			(size > 0).then_some((offset, size))
+		};
+
+		let offset = || usize::try_from(item(0)?).ok();
+
+		let written = match opcode {
+			b"MStore" | b"MLoad" => offset().map(|offset| (offset, 32)),
+			b"MStore8" => offset().map(|offset| (offset, 1)),
+			b"CallDataCopy" | b"CodeCopy" | b"ReturnDataCopy" => range(0, 2),
+			b"ExtCodeCopy" => range(1, 3),
+			b"Call" | b"CallCode" => range(5, 6),
+			b"DelegateCall" | b"StaticCall" => range(4, 5),
+			_ => None,
+		};
+		let store = match opcode {
+			b"SStore" => Some(StorageDiff {
+				key: item(0).unwrap_or_default(),
+				val: item(1).unwrap_or_default(),
+			}),
+			_ => None,
+		};
+
+		Self {
+			pc,
+			cost: 0,
+			gas: None,
+			executed: None,
+			push: vec![],
+			mem: None,
+			store,
+			sub: None,
+			pushed: pushed_items(opcode),
+			written,
+		}
+	}
+
+	/// Reads the effects of the operation from the stack and memory of the next step.
+	fn complete(&mut self, stack: &[H256], memory: &[u8]) {
+		if self.executed != Some(true) {
+			return
+		}
+
+		let pushed = stack.len().saturating_sub(self.pushed);
+		self.push = stack[pushed..]
+			.iter()
+			.map(|item| U256::from_big_endian(item.as_bytes()))
+			.collect();
+		self.mem = self.written.and_then(|(offset, size)| {
+			let data = memory.get(offset..offset.checked_add(size)?)?;
+			Some(MemoryDiff { off: offset as u64, data: data.to_vec() })
+		});
+	}
+}
+
+/// Number of stack items pushed by an operation.
+fn pushed_items(opcode: &[u8]) -> usize {
+	match opcode {
+		b"Stop" | b"Pop" | b"MStore" | b"MStore8" | b"SStore" | b"Jump" | b"JumpI" |
+		b"JumpDest" | b"CallDataCopy" | b"CodeCopy" | b"ExtCodeCopy" | b"ReturnDataCopy" |
+		b"Log0" | b"Log1" | b"Log2" | b"Log3" | b"Log4" | b"Return" | b"Revert" | b"Invalid" |
+		b"SelfDestruct" => 0,
+		// `DUPn` and `SWAPn` push the `n + 1` items they reorder.
+		_ => opcode
+			.strip_prefix(b"Dup")
+			.or_else(|| opcode.strip_prefix(b"Swap"))
+			.and_then(|n| std::str::from_utf8(n).ok()?.parse::<usize>().ok())
+			.map_or(1, |n| n + 1),
+	}
+}
+
+impl Listener {
+	pub fn using<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+		evm_tracing_events::using(self, f)
+	}
+
+	pub fn gasometer_event(&mut self, event: GasometerEvent) {
+		// Snapshots are taken before the cost is recorded.
+		let (cost, snapshot) = match event {
+			GasometerEvent::RecordCost { cost, snapshot } => (cost, snapshot),
+			GasometerEvent::RecordDynamicCost { gas_cost, memory_gas, snapshot, .. } =>
+				(gas_cost.saturating_add(memory_gas.saturating_sub(snapshot.memory_gas)), snapshot),
+			_ => return,
+		};
+
+		let Some((frame, callers)) = self.frames.split_last_mut() else { return };
+		match frame.ops.last_mut() {
+			Some(op) if op.executed.is_none() => {
+				op.gas.get_or_insert(snapshot.gas());
+				op.cost = op.cost.saturating_add(cost);
+			},
+			Some(_) => (),
+			// The gas given to a call is recorded by its caller once the call is entered.
+			None if !frame.gas_given => {
+				frame.gas_given = true;
+				if let Some(op) = callers.last_mut().and_then(|caller| caller.ops.last_mut()) {
+					op.cost = op.cost.saturating_add(cost);
+				}
+			},
+			// Costs recorded by precompiles, which have no operations.
+			None => (),
+		}
+	}
+
+	pub fn runtime_event(&mut self, event: RuntimeEvent) {
+		let Some(frame) = self.frames.last_mut() else { return };
+		match event {
+			RuntimeEvent::Step { opcode, position, stack, memory, .. } => {
+				let stack = stack.map(|stack| stack.data).unwrap_or_default();
+				let memory = memory.map(|memory| memory.data).unwrap_or_default();
+				if let Some(op) = frame.ops.last_mut() {
+					op.complete(&stack, &memory);
+				}
+				frame.ops.push(Operation::new(position.unwrap_or_default(), &opcode, &stack));
+			},
+			RuntimeEvent::StepResult { result, .. } =>
+				if let Some(op) = frame.ops.last_mut() {
+					op.executed = Some(!matches!(
+						result,
+						Err(Capture::Exit(ExitReason::Error(_) | ExitReason::Fatal(_)))
+					));
+				},
+			_ => (),
+		}
+	}
+
+	pub fn evm_event(&mut self, event: EvmEvent) {
+		match event {
+			EvmEvent::Call { code_address, .. } => {
+				self.code_addresses.insert(code_address);
+				self.frames.push(Frame::new(FrameCode::Deployed(code_address)));
+			},
+			EvmEvent::Create { init_code, .. } =>
+				self.frames.push(Frame::new(FrameCode::Init(init_code))),
+			EvmEvent::Exit { .. } =>
+				if let Some(frame) = self.frames.pop() {
+					match self.frames.last_mut() {
+						Some(caller) =>
+							if let Some(op) = caller.ops.last_mut() {
+								op.sub = Some(frame);
+							},
+						None => self.trace = Some(frame),
+					}
+				},
+			_ => (),
+		}
+	}
+}
+
+impl ListenerT for Listener {
+	fn event(&mut self, event: Event) {
+		match event {
+			Event::Gasometer(e) => self.gasometer_event(e),
+			Event::Runtime(e) => self.runtime_event(e),
+			Event::Evm(e) => self.evm_event(e),
+			_ => (),
+		}
+	}
+
+	fn step_event_filter(&self) -> StepEventFilter {
+		StepEventFilter { enable_memory: true, enable_stack: true }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::formatters::{vm_trace::Formatter, ResponseFormatter};
+	use evm_tracing_events::{
+		gasometer::Snapshot,
+		runtime::{ExitError, ExitSucceed, Memory, Stack},
+		Context as EvmContext,
+	};
+
+	fn test_snapshot(used_gas: u64, memory_gas: u64) -> Snapshot {
+		Snapshot { gas_limit: 1_000, memory_gas, used_gas, refunded_gas: 0 }
+	}
+
+	fn do_call_event(listener: &mut Listener, code_address: H160) {
+		listener.evm_event(EvmEvent::Call {
+			code_address,
+			transfer: None,
+			input: vec![],
+			target_gas: None,
+			is_static: false,
+			context: EvmContext {
+				address: code_address,
+				caller: H160::default(),
+				apparent_value: U256::zero(),
+			},
+		});
+	}
+
+	fn do_step_event(
+		listener: &mut Listener,
+		pc: u64,
+		opcode: &[u8],
+		stack: &[u64],
+		memory: &[u8],
+	) {
+		listener.runtime_event(RuntimeEvent::Step {
+			context: EvmContext {
+				address: H160::default(),
+				caller: H160::default(),
+				apparent_value: U256::zero(),
+			},
+			opcode: opcode.to_vec(),
+			position: Ok(pc),
+			// The top of the stack is its last item.
+			stack: Some(Stack {
+				data: stack.iter().map(|item| H256::from_low_u64_be(*item)).collect(),
+				limit: 1024,
+			}),
+			memory: Some(Memory {
+				data: memory.to_vec(),
+				effective_len: memory.len().into(),
+				limit: u64::MAX,
+			}),
+		});
+	}
+
+	fn do_step_result_event(listener: &mut Listener, result: Result<(), ExitReason>) {
+		listener.runtime_event(RuntimeEvent::StepResult {
+			result: result.map_err(Capture::Exit),
+			return_value: vec![],
+		});
+	}
+
+	fn do_cost_event(listener: &mut Listener, cost: u64, snapshot: Snapshot) {
+		listener.gasometer_event(GasometerEvent::RecordCost { cost, snapshot });
+	}
+
+	fn do_exit_event(listener: &mut Listener, reason: ExitReason) {
+		listener.evm_event(EvmEvent::Exit { reason, return_value: vec![] });
+	}
+
+	#[test]
+	fn records_effects_of_operations() {
+		let contract = H160::repeat_byte(1);
+		let code = vec![0x60, 0x2a, 0x60, 0x00, 0x52, 0x00];
+		let mut memory = vec![0; 32];
+		memory[31] = 0x2a;
+
+		let mut listener = Listener::default();
+		do_call_event(&mut listener, contract);
+		// Gas given to the transaction's call.
+		do_cost_event(&mut listener, 1_000, test_snapshot(0, 0));
+		do_step_event(&mut listener, 0, b"Push1", &[], &[]);
+		do_cost_event(&mut listener, 3, test_snapshot(0, 0));
+		do_step_result_event(&mut listener, Ok(()));
+		do_step_event(&mut listener, 2, b"Push1", &[0x2a], &[]);
+		do_cost_event(&mut listener, 3, test_snapshot(3, 0));
+		do_step_result_event(&mut listener, Ok(()));
+		do_step_event(&mut listener, 4, b"MStore", &[0x2a, 0], &[]);
+		listener.gasometer_event(GasometerEvent::RecordDynamicCost {
+			gas_cost: 3,
+			memory_gas: 3,
+			gas_refund: 0,
+			snapshot: test_snapshot(6, 0),
+		});
+		do_step_result_event(&mut listener, Ok(()));
+		do_step_event(&mut listener, 5, b"Stop", &[], &memory);
+		do_cost_event(&mut listener, 0, test_snapshot(9, 3));
+		do_step_result_event(&mut listener, Err(ExitReason::Succeed(ExitSucceed::Stopped)));
+		do_exit_event(&mut listener, ExitReason::Succeed(ExitSucceed::Stopped));
+
+		assert_eq!(listener.code_addresses, BTreeSet::from([contract]));
+		listener.codes.insert(contract, code);
+
+		assert_eq!(
+			serde_json::to_value(Formatter::format(listener).unwrap()).unwrap(),
+			serde_json::json!({
+				"code": "0x602a60005200",
+				"ops": [
+					{
+						"pc": 0,
+						"cost": 3,
+						"ex": { "used": 997, "push": ["0x2a"], "mem": null, "store": null },
+						"sub": null,
+					},
+					{
+						"pc": 2,
+						"cost": 3,
+						"ex": { "used": 994, "push": ["0x0"], "mem": null, "store": null },
+						"sub": null,
+					},
+					{
+						"pc": 4,
+						"cost": 6,
+						"ex": {
+							"used": 988,
+							"push": [],
+							"mem": { "off": 0, "data": format!("0x{}", hex::encode(&memory)) },
+							"store": null,
+						},
+						"sub": null,
+					},
+					{
+						"pc": 5,
+						"cost": 0,
+						"ex": { "used": 988, "push": [], "mem": null, "store": null },
+						"sub": null,
+					},
+				],
+			})
+		);
+	}
+
+	#[test]
+	fn nests_calls_in_the_calling_operation() {
+		let caller = H160::repeat_byte(1);
+		let callee = H160::repeat_byte(2);
+
+		let mut listener = Listener::default();
+		do_call_event(&mut listener, caller);
+		do_cost_event(&mut listener, 1_000, test_snapshot(0, 0));
+		// Return size, return offset, arguments size and offset, value, address and gas.
+		do_step_event(&mut listener, 0, b"Call", &[0, 0, 0, 0, 0, 2, 100], &[]);
+		do_cost_event(&mut listener, 100, test_snapshot(0, 0));
+		listener.runtime_event(RuntimeEvent::StepResult {
+			result: Err(Capture::Trap(b"Call".to_vec())),
+			return_value: vec![],
+		});
+		do_call_event(&mut listener, callee);
+		// Gas given to the call, recorded by the caller.
+		do_cost_event(&mut listener, 50, test_snapshot(100, 0));
+		do_step_event(&mut listener, 0, b"Invalid", &[], &[]);
+		do_step_result_event(&mut listener, Err(ExitReason::Error(ExitError::DesignatedInvalid)));
+		do_exit_event(&mut listener, ExitReason::Error(ExitError::DesignatedInvalid));
+		do_step_event(&mut listener, 1, b"Stop", &[0], &[]);
+		do_cost_event(&mut listener, 0, test_snapshot(150, 0));
+		do_step_result_event(&mut listener, Err(ExitReason::Succeed(ExitSucceed::Stopped)));
+		do_exit_event(&mut listener, ExitReason::Succeed(ExitSucceed::Stopped));
+
+		assert_eq!(listener.code_addresses, BTreeSet::from([caller, callee]));
+
+		let trace = Formatter::format(listener).unwrap();
+		assert_eq!(trace.ops[0].cost, 150);
+		assert_eq!(trace.ops[0].ex.as_ref().unwrap().used, 850);
+		assert_eq!(trace.ops[0].ex.as_ref().unwrap().push, vec![U256::zero()]);
+		// The callee's code is unknown until filled by the caller.
+		let sub = trace.ops[0].sub.as_ref().unwrap();
+		assert_eq!(sub.code, Vec::<u8>::new());
+		assert_eq!(sub.ops.len(), 1);
+		assert_eq!(sub.ops[0].ex, None);
+	}
+}
@@ -0,0 +1,60 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Helpers shared by the RPCs replaying the extrinsics of a block to trace them.
+
+use crate::formatters::prestate::AccountState;
+use ethereum::TransactionV2 as EthereumTransaction;
+use ethereum_types::{H160, H256, U256};
+use fp_rpc::EthereumRuntimeRPCApi;
+use sp_api::ApiError;
+use sp_runtime::traits::Block as BlockT;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Index among `transactions` of the Ethereum transaction applied by each of `extrinsics`, if
+/// any. The runtime decodes each extrinsic, so only `pallet_ethereum::transact` calls match.
+pub fn ethereum_transaction_indices<B: BlockT, A: EthereumRuntimeRPCApi<B>>(
+	api: &A,
+	at: B::Hash,
+	extrinsics: &[B::Extrinsic],
+	transactions: &[EthereumTransaction],
+) -> Result<Vec<Option<usize>>, ApiError> {
+	extrinsics
+		.iter()
+		.map(|extrinsic| {
+			let applied = api.extrinsic_filter(at, vec![extrinsic.clone()])?;
+			Ok(applied.first().and_then(|tx| transactions.iter().position(|other| other == tx)))
+		})
+		.collect()
+}
+
+/// Reads the balance, nonce, code and accessed storage slots of the accessed accounts.
+pub fn account_states<B: BlockT, A: EthereumRuntimeRPCApi<B>>(
+	api: &A,
+	at: B::Hash,
+	accessed: &BTreeMap<H160, BTreeSet<H256>>,
+) -> Result<BTreeMap<H160, AccountState>, ApiError> {
+	accessed
+		.iter()
+		.map(|(address, slots)| {
+			let account = api.account_basic(at, *address)?;
+			let code = api.account_code_at(at, *address)?;
+			let storage = slots
+				.iter()
+				.map(|slot| {
+					let index = U256::from_big_endian(slot.as_bytes());
+					Ok((*slot, api.storage_at(at, *address, index)?))
+				})
+				.collect::<Result<_, ApiError>>()?;
+
+			Ok((
+				*address,
+				AccountState {
+					balance: Some(account.balance),
+					nonce: Some(account.nonce.low_u64()),
+					code: Some(code),
+					storage,
+				},
+			))
+		})
+		.collect()
+}
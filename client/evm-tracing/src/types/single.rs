@@ -38,7 +38,13 @@ pub enum TraceType {
 	/// Classic geth with no javascript based tracing.
 	Raw { disable_storage: bool, disable_memory: bool, disable_stack: bool },
 	/// List of calls and subcalls formatted with an input tracer (i.e. callTracer or Blockscout).
-	CallList,
+	/// The options are only supported by the callTracer.
+	CallList { only_top_call: bool, with_log: bool },
+	/// State of the accounts accessed by the transaction, as Geth's prestateTracer.
+	Prestate { diff_mode: bool },
+	/// Aggregate of the execution formatted with an input tracer (i.e. 4byteTracer or
+	/// opcountTracer).
+	Aggregate,
 	/// A single block trace. Use in `debug_traceTransactionByNumber` / `traceTransactionByHash`.
 	Block,
 }
@@ -61,6 +67,12 @@ pub enum TransactionTrace {
 	CallList(Vec<Call>),
 	/// Used by Geth's callTracer.
 	CallListNested(Call),
+	/// Used by Geth's prestateTracer.
+	Prestate(crate::formatters::prestate::PrestateTrace),
+	/// Used by Geth's 4byteTracer, number of calls by selector and calldata size.
+	FourByte(BTreeMap<String, u64>),
+	/// Used by the opcountTracer, number of executed opcodes.
+	Opcount(u64),
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, Serialize)]
@@ -13,11 +13,13 @@
 
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
-use ethereum_types::H256;
+use ethereum_types::{H160, H256, U256};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use peaq_client_evm_tracing::types::single;
 use peaq_rpc_core_types::RequestBlockId;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use std::time::Duration;
 
 #[derive(Clone, Eq, PartialEq, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,9 +29,109 @@ pub struct TraceParams {
 	pub disable_stack: Option<bool>,
 	/// Javascript tracer (we just check if it's Blockscout tracer string)
 	pub tracer: Option<String>,
+	pub tracer_config: Option<TracerConfig>,
 	pub timeout: Option<String>,
 }
 
+impl TraceParams {
+	/// Parses `timeout`, a Go-style duration such as "5s", "300ms" or "1m30s".
+	pub fn timeout(&self) -> Result<Option<Duration>, String> {
+		self.timeout.as_deref().map(parse_duration).transpose()
+	}
+}
+
+fn parse_duration(duration: &str) -> Result<Duration, String> {
+	let invalid = || format!("time: invalid duration {:?}", duration);
+	if duration == "0" {
+		return Ok(Duration::ZERO)
+	}
+	if duration.is_empty() {
+		return Err(invalid())
+	}
+
+	let mut nanos = 0f64;
+	let mut rest = duration;
+	while !rest.is_empty() {
+		// A unit must follow each number.
+		let number_len =
+			rest.find(|c: char| !c.is_ascii_digit() && c != '.').ok_or_else(invalid)?;
+		let (number, tail) = rest.split_at(number_len);
+		let number: f64 = number.parse().map_err(|_| invalid())?;
+
+		let unit_len = tail.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(tail.len());
+		let (unit, tail) = tail.split_at(unit_len);
+		let unit_nanos = match unit {
+			"ns" => 1e0,
+			"us" | "\u{b5}s" | "\u{3bc}s" => 1e3,
+			"ms" => 1e6,
+			"s" => 1e9,
+			"m" => 60e9,
+			"h" => 3600e9,
+			_ => return Err(invalid()),
+		};
+
+		nanos += number * unit_nanos;
+		rest = tail;
+	}
+
+	Ok(Duration::from_nanos(nanos as u64))
+}
+
+/// Options of the built-in tracers.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracerConfig {
+	/// prestateTracer: return the state modified by the transaction, before and after it.
+	pub diff_mode: Option<bool>,
+	/// callTracer: only return the top call, without its subcalls.
+	pub only_top_call: Option<bool>,
+	/// callTracer: return the logs emitted by each call.
+	pub with_log: Option<bool>,
+}
+
+/// Call traced by `debug_traceCall`, with the same fields as the request of `eth_call`.
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceCallParams {
+	/// Sender, the zero address if not provided.
+	pub from: Option<H160>,
+	/// Recipient, the call creates a contract with `data` as init code if not provided.
+	pub to: Option<H160>,
+	/// Gas price, for legacy calls.
+	pub gas_price: Option<U256>,
+	/// Max base fee per gas the sender is willing to pay.
+	pub max_fee_per_gas: Option<U256>,
+	/// Tip per gas paid to the block author.
+	pub max_priority_fee_per_gas: Option<U256>,
+	/// Gas limit, the block gas limit if not provided.
+	pub gas: Option<U256>,
+	/// Value transferred with the call.
+	pub value: Option<U256>,
+	/// Input of the call.
+	#[serde(alias = "input")]
+	pub data: Option<Bytes>,
+	/// Nonce of the sender.
+	pub nonce: Option<U256>,
+	/// EIP-2930 access list.
+	pub access_list: Option<Vec<AccessListItem>>,
+}
+
+/// Trace of a transaction of a block, streamed by `debug_subscribeTraceBlock`.
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockTransactionTrace {
+	pub tx_hash: H256,
+	pub result: single::TransactionTrace,
+}
+
+/// Storage slots of an address warmed up before the call.
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessListItem {
+	pub address: H160,
+	pub storage_keys: Vec<H256>,
+}
+
 #[rpc(server)]
 #[jsonrpsee::core::async_trait]
 pub trait Debug {
@@ -39,10 +141,57 @@ pub trait Debug {
 		transaction_hash: H256,
 		params: Option<TraceParams>,
 	) -> RpcResult<single::TransactionTrace>;
+	#[method(name = "debug_traceCall")]
+	async fn trace_call(
+		&self,
+		call_request: TraceCallParams,
+		id: RequestBlockId,
+		params: Option<TraceParams>,
+	) -> RpcResult<single::TransactionTrace>;
+	/// Traces the transactions of a block. EVM executions started by Substrate extrinsics are
+	/// traced as pseudo-transactions, whose top-level call carries their synthetic `txHash`.
 	#[method(name = "debug_traceBlockByNumber", aliases = ["debug_traceBlockByHash"])]
 	async fn trace_block(
 		&self,
 		id: RequestBlockId,
 		params: Option<TraceParams>,
 	) -> RpcResult<Vec<single::TransactionTrace>>;
+	/// Streams the trace of each transaction of the block as soon as it is traced, instead of
+	/// returning all of them at once like `debug_traceBlockByNumber`.
+	#[subscription(
+		name = "debug_subscribeTraceBlock" => "debug_traceBlockResult",
+		unsubscribe = "debug_unsubscribeTraceBlock",
+		item = BlockTransactionTrace
+	)]
+	fn subscribe_trace_block(&self, id: RequestBlockId, params: Option<TraceParams>);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_go_durations() {
+		assert_eq!(parse_duration("5s"), Ok(Duration::from_secs(5)));
+		assert_eq!(parse_duration("300ms"), Ok(Duration::from_millis(300)));
+		assert_eq!(parse_duration("1m30s"), Ok(Duration::from_secs(90)));
+		assert_eq!(parse_duration("1.5h"), Ok(Duration::from_secs(5400)));
+		assert_eq!(parse_duration("10\u{b5}s"), Ok(Duration::from_micros(10)));
+		assert_eq!(parse_duration("0"), Ok(Duration::ZERO));
+
+		for invalid in ["", "5", "s", "-5s", "5d", "1..5s"] {
+			assert!(parse_duration(invalid).is_err(), "{} should be invalid", invalid);
+		}
+	}
+	#[test]
+	fn trace_call_recipient_is_optional() {
+		let params: TraceCallParams =
+			serde_json::from_str(r#"{"from":"0x0000000000000000000000000000000000000001"}"#)
+				.unwrap();
+		assert_eq!(params.to, None);
+
+		let params: TraceCallParams =
+			serde_json::from_str(r#"{"to":"0x0000000000000000000000000000000000000002"}"#).unwrap();
+		assert_eq!(params.to, Some(H160::from_low_u64_be(2)));
+	}
 }
@@ -14,17 +14,48 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
-use ethereum_types::H160;
+use ethereum_types::{H160, H256, U64};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use peaq_client_evm_tracing::types::block::TransactionTrace;
+use peaq_client_evm_tracing::{
+	formatters::{state_diff::StateDiff, vm_trace::VmTrace},
+	types::{block::TransactionTrace, serialization::bytes_0x_serialize},
+};
 use peaq_rpc_core_types::RequestBlockId;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[rpc(server)]
 #[jsonrpsee::core::async_trait]
 pub trait Trace {
 	#[method(name = "trace_filter")]
 	async fn filter(&self, filter: FilterRequest) -> RpcResult<Vec<TransactionTrace>>;
+
+	#[method(name = "trace_block")]
+	async fn block(&self, block: RequestBlockId) -> RpcResult<Vec<TransactionTrace>>;
+
+	#[method(name = "trace_transaction")]
+	async fn transaction(&self, transaction_hash: H256)
+		-> RpcResult<Option<Vec<TransactionTrace>>>;
+
+	#[method(name = "trace_get")]
+	async fn get(
+		&self,
+		transaction_hash: H256,
+		indices: Vec<U64>,
+	) -> RpcResult<Option<TransactionTrace>>;
+
+	#[method(name = "trace_replayTransaction")]
+	async fn replay_transaction(
+		&self,
+		transaction_hash: H256,
+		trace_types: Vec<TraceResultType>,
+	) -> RpcResult<TraceResults>;
+
+	#[method(name = "trace_replayBlockTransactions")]
+	async fn replay_block_transactions(
+		&self,
+		block: RequestBlockId,
+		trace_types: Vec<TraceResultType>,
+	) -> RpcResult<Vec<TraceResults>>;
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Deserialize)]
@@ -48,3 +79,29 @@ pub struct FilterRequest {
 	/// (optional) Integer number of traces to display in a batch.
 	pub count: Option<u32>,
 }
+
+/// Kinds of results the `trace_replay*` methods can return.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TraceResultType {
+	/// Flat list of the calls of the transaction, as returned by `trace_transaction`.
+	Trace,
+	/// Changes of balance, nonce, code and storage made by the transaction.
+	StateDiff,
+	/// Operations executed by the transaction, with their cost and effects.
+	VmTrace,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResults {
+	/// Output of the transaction, or code of the created contract.
+	#[serde(serialize_with = "bytes_0x_serialize")]
+	pub output: Vec<u8>,
+	pub trace: Option<Vec<TransactionTrace>>,
+	pub state_diff: Option<StateDiff>,
+	pub vm_trace: Option<VmTrace>,
+	/// Only set by `trace_replayBlockTransactions`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub transaction_hash: Option<H256>,
+}
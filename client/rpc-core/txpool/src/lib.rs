@@ -14,18 +14,30 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
-use ethereum_types::U256;
+use ethereum_types::{H160, U256};
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use std::collections::HashMap;
 
 mod types;
 
-pub use crate::types::{Get as GetT, Summary, Transaction, TransactionMap, TxPoolResult};
+pub use crate::types::{
+	Get as GetT, NativeTransaction, Summary, Transaction, TransactionMap, TxPoolResult,
+};
 
 #[rpc(server)]
 pub trait TxPool {
 	#[method(name = "txpool_content")]
 	fn content(&self) -> RpcResult<TxPoolResult<TransactionMap<Transaction>>>;
 
+	/// Ethereum transactions sent by `address`, by nonce.
+	#[method(name = "txpool_contentFrom")]
+	fn content_from(&self, address: H160) -> RpcResult<TxPoolResult<HashMap<U256, Transaction>>>;
+
+	/// Native extrinsics signed by the account `address` is mapped to.
+	#[method(name = "txpool_nativeContentFrom")]
+	fn native_content_from(&self, address: H160)
+		-> RpcResult<TxPoolResult<Vec<NativeTransaction>>>;
+
 	#[method(name = "txpool_inspect")]
 	fn inspect(&self) -> RpcResult<TxPoolResult<TransactionMap<Summary>>>;
 
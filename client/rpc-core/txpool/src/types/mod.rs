@@ -16,13 +16,14 @@
 
 mod content;
 mod inspect;
+mod native;
 
 use ethereum::TransactionV2 as EthereumTransaction;
 use ethereum_types::{H160, H256, U256};
 use serde::Serialize;
 use std::collections::HashMap;
 
-pub use self::{content::Transaction, inspect::Summary};
+pub use self::{content::Transaction, inspect::Summary, native::NativeTransaction};
 
 pub type TransactionMap<T> = HashMap<H160, HashMap<U256, T>>;
 
@@ -0,0 +1,15 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use ethereum_types::H256;
+use fc_rpc_core::types::Bytes;
+use serde::Serialize;
+
+/// Native extrinsic waiting in the pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeTransaction {
+	/// Hash
+	pub hash: H256,
+	/// SCALE encoded extrinsic
+	pub extrinsic: Bytes,
+}
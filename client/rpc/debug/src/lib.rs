@@ -16,39 +16,64 @@
 #![feature(stmt_expr_attributes)]
 
 use futures::StreamExt;
-use jsonrpsee::core::{async_trait, RpcResult};
-pub use peaq_rpc_core_debug::{DebugServer, TraceParams};
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	types::SubscriptionResult,
+	SubscriptionSink,
+};
+pub use peaq_rpc_core_debug::{
+	BlockTransactionTrace, DebugServer, TraceCallParams, TraceParams, TracerConfig,
+};
 
 use tokio::{
 	self,
-	sync::{oneshot, Semaphore},
+	sync::{mpsc, oneshot, Semaphore},
 };
 
 use ethereum_types::H256;
+use evm_tracing_events::Listener;
 use fc_rpc::{frontier_backend_client, internal_err, OverrideHandle};
 use fp_rpc::EthereumRuntimeRPCApi;
-use peaq_client_evm_tracing::{formatters::ResponseFormatter, types::single};
+use peaq_client_evm_tracing::{
+	formatters::ResponseFormatter,
+	listeners::CallStackOrigin,
+	replay::{account_states, ethereum_transaction_indices},
+	types::single,
+};
 use peaq_rpc_core_types::{RequestBlockId, RequestBlockTag};
 use peaq_rpc_primitives_debug::{DebugRuntimeApi, TracerInput};
 use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
 use sc_utils::mpsc::TracingUnboundedSender;
-use sp_api::{ApiExt, BlockId, Core, HeaderT, ProvideRuntimeApi};
+use sp_api::{ApiError, ApiExt, ApiRef, BlockId, Core, HeaderT, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{
 	Backend as BlockchainBackend, Error as BlockChainError, HeaderBackend, HeaderMetadata,
 };
 use sp_runtime::traits::{BlakeTwo256, Block as BlockT, UniqueSaturatedInto};
-use std::{future::Future, marker::PhantomData, sync::Arc};
+use std::{
+	future::Future,
+	marker::PhantomData,
+	sync::Arc,
+	time::{Duration, Instant},
+};
 
 pub enum RequesterInput {
 	Transaction(H256),
 	Block(RequestBlockId),
+	Call {
+		params: TraceCallParams,
+		at: RequestBlockId,
+	},
+	/// Block whose transaction traces are sent one by one through the channel.
+	BlockStream(RequestBlockId, mpsc::Sender<BlockTransactionTrace>),
 }
 
 #[allow(clippy::large_enum_variant)]
 pub enum Response {
 	Single(single::TransactionTrace),
 	Block(Vec<single::TransactionTrace>),
+	/// All the traces of a `BlockStream` request have been sent.
+	Streamed,
 }
 
 pub type Responder = oneshot::Sender<RpcResult<Response>>;
@@ -93,6 +118,33 @@ impl DebugServer for Debug {
 			})
 	}
 
+	/// Handler for `debug_traceCall` request. Communicates with the service-defined task
+	/// using channels.
+	async fn trace_call(
+		&self,
+		call_request: TraceCallParams,
+		id: RequestBlockId,
+		params: Option<TraceParams>,
+	) -> RpcResult<single::TransactionTrace> {
+		let requester = self.requester.clone();
+
+		let (tx, rx) = oneshot::channel();
+		// Send a message from the rpc handler to the service level task.
+		requester
+			.unbounded_send(((RequesterInput::Call { params: call_request, at: id }, params), tx))
+			.map_err(|err| {
+				internal_err(format!("failed to send request to debug service : {:?}", err))
+			})?;
+
+		// Receive a message from the service level task and send the rpc response.
+		rx.await
+			.map_err(|err| internal_err(format!("debug service dropped the channel : {:?}", err)))?
+			.map(|res| match res {
+				Response::Single(res) => res,
+				_ => unreachable!(),
+			})
+	}
+
 	async fn trace_block(
 		&self,
 		id: RequestBlockId,
@@ -116,6 +168,47 @@ impl DebugServer for Debug {
 				_ => unreachable!(),
 			})
 	}
+
+	/// Handler for `debug_subscribeTraceBlock` subscription. The service-defined task sends the
+	/// traces through a bounded channel, so that at most one transaction trace is kept in memory
+	/// while waiting for the subscriber.
+	fn subscribe_trace_block(
+		&self,
+		mut sink: SubscriptionSink,
+		id: RequestBlockId,
+		params: Option<TraceParams>,
+	) -> SubscriptionResult {
+		let requester = self.requester.clone();
+
+		let (traces_tx, traces_rx) = mpsc::channel(1);
+		let (tx, rx) = oneshot::channel();
+		sink.accept()?;
+		// Send a message from the rpc handler to the service level task. If it fails, the
+		// response channel is dropped and the subscription is closed with an error.
+		let _ =
+			requester.unbounded_send(((RequesterInput::BlockStream(id, traces_tx), params), tx));
+
+		tokio::spawn(async move {
+			let traces = futures::stream::unfold(traces_rx, |mut traces_rx| async move {
+				traces_rx.recv().await.map(|trace| (Ok(trace), traces_rx))
+			});
+			// The traces channel is closed when the request ends, its error is then received.
+			let error = futures::stream::once(rx).filter_map(|res| async move {
+				match res {
+					Ok(Ok(_)) => None,
+					Ok(Err(err)) => Some(Err(err)),
+					Err(err) => Some(Err(internal_err(format!(
+						"debug service dropped the channel : {:?}",
+						err
+					)))),
+				}
+			});
+
+			sink.pipe_from_try_stream(traces.chain(error).boxed()).await;
+		});
+
+		Ok(())
+	}
 }
 
 pub struct DebugHandler<B: BlockT, C, BE>(PhantomData<(B, C, BE)>);
@@ -136,6 +229,7 @@ where
 {
 	/// Task spawned at service level that listens for messages on the rpc channel and spawns
 	/// blocking tasks using a permit pool.
+	#[allow(clippy::too_many_arguments)]
 	pub fn task(
 		client: Arc<C>,
 		backend: Arc<BE>,
@@ -143,6 +237,8 @@ where
 		permit_pool: Arc<Semaphore>,
 		overrides: Arc<OverrideHandle<B>>,
 		raw_max_memory_usage: usize,
+		default_timeout: Option<Duration>,
+		max_timeout: Duration,
 	) -> (impl Future<Output = ()>, DebugRequester) {
 		let (tx, mut rx): (DebugRequester, _) =
 			sc_utils::mpsc::tracing_unbounded("debug-requester", 100_000);
@@ -159,10 +255,14 @@ where
 						let frontier_backend = frontier_backend.clone();
 						let permit_pool = permit_pool.clone();
 						let overrides = overrides.clone();
+						// The timeout of the request includes its wait for a permit.
+						let deadline =
+							Self::deadline(params.as_ref(), default_timeout, max_timeout);
 
 						tokio::task::spawn(async move {
 							let _ = response_tx.send(
 								async {
+									let deadline = deadline?;
 									let _permit = permit_pool.acquire().await;
 									tokio::task::spawn_blocking(move || {
 										Self::handle_transaction_request(
@@ -173,6 +273,7 @@ where
 											params,
 											overrides.clone(),
 											raw_max_memory_usage,
+											deadline,
 										)
 									})
 									.await
@@ -193,10 +294,14 @@ where
 						let frontier_backend = frontier_backend.clone();
 						let permit_pool = permit_pool.clone();
 						let overrides = overrides.clone();
+						// The timeout of the request includes its wait for a permit.
+						let deadline =
+							Self::deadline(params.as_ref(), default_timeout, max_timeout);
 
 						tokio::task::spawn(async move {
 							let _ = response_tx.send(
 								async {
+									let deadline = deadline?;
 									let _permit = permit_pool.acquire().await;
 
 									tokio::task::spawn_blocking(move || {
@@ -207,6 +312,95 @@ where
 											request_block_id,
 											params,
 											overrides,
+											deadline,
+										)
+									})
+									.await
+									.map_err(|e| {
+										internal_err(format!(
+											"Internal error on spawned task : {:?}",
+											e
+										))
+									})?
+								}
+								.await,
+							);
+						});
+					},
+					Some((
+						(
+							RequesterInput::Call { params: call_params, at: request_block_id },
+							params,
+						),
+						response_tx,
+					)) => {
+						let client = client.clone();
+						let frontier_backend = frontier_backend.clone();
+						let permit_pool = permit_pool.clone();
+						let overrides = overrides.clone();
+						// The timeout of the request includes its wait for a permit.
+						let deadline =
+							Self::deadline(params.as_ref(), default_timeout, max_timeout);
+
+						tokio::task::spawn(async move {
+							let _ = response_tx.send(
+								async {
+									let deadline = deadline?;
+									let _permit = permit_pool.acquire().await;
+
+									tokio::task::spawn_blocking(move || {
+										Self::handle_call_request(
+											client.clone(),
+											frontier_backend.clone(),
+											call_params,
+											request_block_id,
+											params,
+											overrides,
+											raw_max_memory_usage,
+											deadline,
+										)
+									})
+									.await
+									.map_err(|e| {
+										internal_err(format!(
+											"Internal error on spawned task : {:?}",
+											e
+										))
+									})?
+								}
+								.await,
+							);
+						});
+					},
+					Some((
+						(RequesterInput::BlockStream(request_block_id, traces_tx), params),
+						response_tx,
+					)) => {
+						let client = client.clone();
+						let backend = backend.clone();
+						let frontier_backend = frontier_backend.clone();
+						let permit_pool = permit_pool.clone();
+						let overrides = overrides.clone();
+						// The timeout of the request includes its wait for a permit.
+						let deadline =
+							Self::deadline(params.as_ref(), default_timeout, max_timeout);
+
+						tokio::task::spawn(async move {
+							let _ = response_tx.send(
+								async {
+									let deadline = deadline?;
+									let _permit = permit_pool.acquire().await;
+
+									tokio::task::spawn_blocking(move || {
+										Self::handle_block_stream_request(
+											client.clone(),
+											backend.clone(),
+											frontier_backend.clone(),
+											request_block_id,
+											params,
+											overrides,
+											deadline,
+											traces_tx,
 										)
 									})
 									.await
@@ -228,10 +422,50 @@ where
 		(fut, tx)
 	}
 
+	/// Instant at which a request received now times out. The `timeout` of the request defaults
+	/// to `default_timeout`, or to `max_timeout` without one, and is capped by `max_timeout`.
+	fn deadline(
+		params: Option<&TraceParams>,
+		default_timeout: Option<Duration>,
+		max_timeout: Duration,
+	) -> RpcResult<Instant> {
+		let timeout = match params.map(TraceParams::timeout).transpose() {
+			Ok(timeout) => timeout.flatten().or(default_timeout).unwrap_or(max_timeout),
+			Err(e) => return Err(internal_err(e)),
+		};
+		Ok(Instant::now() + timeout.min(max_timeout))
+	}
+
+	/// Traces `f` with `proxy`, failing with Geth's "execution timeout" error if `deadline` is
+	/// reached before the end of the tracing. The runtime call of `f` can't be interrupted, so it
+	/// still runs to its end after the deadline.
+	fn trace_until<L, F>(proxy: L, deadline: Instant, f: F) -> RpcResult<L>
+	where
+		L: Listener + 'static,
+		F: FnOnce() -> RpcResult<peaq_rpc_primitives_debug::Response>,
+	{
+		let mut proxy = peaq_client_evm_tracing::listeners::Timeout::new(proxy, deadline);
+		proxy.using(f)?;
+		if proxy.expired() {
+			return Err(internal_err("execution timeout"))
+		}
+		Ok(proxy.inner)
+	}
+
 	fn handle_params(params: Option<TraceParams>) -> RpcResult<(TracerInput, single::TraceType)> {
 		// Set trace input and type
 		match params {
-			Some(TraceParams { tracer: Some(tracer), .. }) => {
+			Some(TraceParams { tracer: Some(tracer), tracer_config, .. })
+				if tracer == "prestateTracer" =>
+			{
+				let diff_mode = tracer_config.and_then(|config| config.diff_mode).unwrap_or(false);
+				Ok((TracerInput::None, single::TraceType::Prestate { diff_mode }))
+			},
+			Some(TraceParams { tracer: Some(tracer), .. }) if tracer == "4byteTracer" =>
+				Ok((TracerInput::FourByteTracer, single::TraceType::Aggregate)),
+			Some(TraceParams { tracer: Some(tracer), .. }) if tracer == "opcountTracer" =>
+				Ok((TracerInput::OpcountTracer, single::TraceType::Aggregate)),
+			Some(TraceParams { tracer: Some(tracer), tracer_config, .. }) => {
 				const BLOCKSCOUT_JS_CODE_HASH: [u8; 16] =
 					hex_literal::hex!("94d9f08796f91eb13a2e82a6066882f7");
 				const BLOCKSCOUT_JS_CODE_HASH_V2: [u8; 16] =
@@ -246,7 +480,14 @@ where
 						None
 					};
 				if let Some(tracer) = tracer {
-					Ok((tracer, single::TraceType::CallList))
+					let config = tracer_config.unwrap_or_default();
+					Ok((
+						tracer,
+						single::TraceType::CallList {
+							only_top_call: config.only_top_call.unwrap_or(false),
+							with_log: config.with_log.unwrap_or(false),
+						},
+					))
 				} else {
 					Err(internal_err(format!(
 						"javascript based tracing is not available (hash :{:?})",
@@ -273,17 +514,12 @@ where
 		}
 	}
 
-	fn handle_block_request(
-		client: Arc<C>,
-		backend: Arc<BE>,
-		frontier_backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
+	fn resolve_block_id(
+		client: &Arc<C>,
+		frontier_backend: &Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
 		request_block_id: RequestBlockId,
-		params: Option<TraceParams>,
-		overrides: Arc<OverrideHandle<B>>,
-	) -> RpcResult<Response> {
-		let (tracer_input, trace_type) = Self::handle_params(params)?;
-
-		let reference_id: BlockId<B> = match request_block_id {
+	) -> RpcResult<BlockId<B>> {
+		match request_block_id {
 			RequestBlockId::Number(n) => Ok(BlockId::Number(n.unique_saturated_into())),
 			RequestBlockId::Tag(RequestBlockTag::Latest) =>
 				Ok(BlockId::Number(client.info().best_number)),
@@ -302,7 +538,24 @@ where
 					Err(e) => Err(e),
 				}
 			},
-		}?;
+		}
+	}
+
+	/// Replays the extrinsics of a block, tracing its Ethereum transactions and, since version 6
+	/// of the `DebugRuntimeApi`, the EVM executions of its Substrate extrinsics.
+	fn handle_block_request(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		frontier_backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
+		request_block_id: RequestBlockId,
+		params: Option<TraceParams>,
+		overrides: Arc<OverrideHandle<B>>,
+		deadline: Instant,
+	) -> RpcResult<Response> {
+		let (tracer_input, trace_type) = Self::handle_params(params)?;
+		let listener = Self::call_list_listener(trace_type)?;
+
+		let reference_id = Self::resolve_block_id(&client, &frontier_backend, request_block_id)?;
 
 		// Get ApiRef. This handle allow to keep changes between txs in an internal buffer.
 		let api = client.runtime_api();
@@ -320,6 +573,15 @@ where
 		// Get parent blockid.
 		let parent_block_id = *header.parent_hash();
 
+		// Get DebugRuntimeApi version
+		let trace_api_version = if let Ok(Some(api_version)) =
+			api.api_version::<dyn DebugRuntimeApi<B>>(parent_block_id)
+		{
+			api_version
+		} else {
+			return Err(internal_err("Runtime api version call failed (trace)".to_string()))
+		};
+
 		let schema = fc_storage::onchain_storage_schema::<B, C, BE>(client.as_ref(), hash);
 
 		// Using storage overrides we align with `:ethereum_schema` which will result in proper
@@ -332,23 +594,19 @@ where
 		// Known ethereum transaction hashes.
 		let eth_tx_hashes: Vec<_> = statuses.iter().map(|t| t.transaction_hash).collect();
 
-		// If there are no ethereum transactions in the block return empty trace right away.
-		if eth_tx_hashes.is_empty() {
-			return Ok(Response::Block(vec![]))
-		}
-
 		// Get block extrinsics.
 		let exts = blockchain
 			.body(hash)
 			.map_err(|e| internal_err(format!("Fail to read blockchain db: {:?}", e)))?
 			.unwrap_or_default();
 
-		// Trace the block.
+		// Trace the whole block in a single runtime call. The runtime can't be interrupted, the
+		// deadline only stops the collection of its events.
 		let f = || -> RpcResult<_> {
 			api.initialize_block(parent_block_id, &header)
 				.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
 
-			api.trace_block(parent_block_id, exts, eth_tx_hashes)
+			api.trace_block(parent_block_id, exts, eth_tx_hashes.clone())
 				.map_err(|e| {
 					internal_err(format!(
 						"Blockchain error when replaying block {} : {:?}",
@@ -361,32 +619,206 @@ where
 						reference_id, e
 					))
 				})?;
+
 			Ok(peaq_rpc_primitives_debug::Response::Block)
 		};
+		let proxy = Self::trace_until(listener, deadline, f)?;
 
-		match trace_type {
-			single::TraceType::CallList => {
-				let mut proxy = peaq_client_evm_tracing::listeners::CallList::default();
-				proxy.using(f)?;
-				proxy.finish_transaction();
-				let response = match tracer_input {
-					TracerInput::CallTracer =>
-						peaq_client_evm_tracing::formatters::CallTracer::format(proxy)
-							.ok_or("Trace result is empty.")
-							.map_err(|e| internal_err(format!("{:?}", e))),
-					_ => Err(internal_err("Bug: failed to resolve the tracer format.".to_string())),
-				}?;
+		// Runtimes before version 6 only trace the Ethereum transactions.
+		let response = Self::format_call_list(proxy, tracer_input)?
+			.into_iter()
+			.map(|(origin, mut result)| {
+				if let (CallStackOrigin::Substrate(_), 6..) = (origin, trace_api_version) {
+					Self::set_pseudo_transaction_hash(
+						&mut result,
+						origin.transaction_hash(hash, &eth_tx_hashes),
+					);
+				}
+				result
+			})
+			.collect();
 
-				Ok(Response::Block(response))
-			},
-			_ => Err(internal_err(
+		Ok(Response::Block(response))
+	}
+
+	/// Listener of the block traces, which only support the `callTracer`.
+	fn call_list_listener(
+		trace_type: single::TraceType,
+	) -> RpcResult<peaq_client_evm_tracing::listeners::CallList> {
+		let single::TraceType::CallList { only_top_call, with_log } = trace_type else {
+			return Err(internal_err(
 				"debug_traceBlock functions currently only support callList mode (enabled
 				by providing `{{'tracer': 'callTracer'}}` in the request)."
 					.to_string(),
-			)),
+			))
+		};
+
+		let mut listener = peaq_client_evm_tracing::listeners::CallList::default();
+		listener.only_top_call = only_top_call;
+		listener.with_log = with_log;
+		Ok(listener)
+	}
+
+	/// Formats the call stacks traced by `proxy` with `tracer_input`, along with their origin.
+	fn format_call_list(
+		mut proxy: peaq_client_evm_tracing::listeners::CallList,
+		tracer_input: TracerInput,
+	) -> RpcResult<Vec<(CallStackOrigin, single::TransactionTrace)>> {
+		proxy.finish_transaction();
+		proxy.remove_empty_call_stacks();
+		let origins = proxy.origins.clone();
+		let response = match tracer_input {
+			TracerInput::CallTracer =>
+				peaq_client_evm_tracing::formatters::CallTracer::format(proxy)
+					.ok_or("Trace result is empty.")
+					.map_err(|e| internal_err(format!("{:?}", e))),
+			_ => Err(internal_err("Bug: failed to resolve the tracer format.".to_string())),
+		}?;
+
+		Ok(origins.into_iter().zip(response).collect())
+	}
+
+	/// EVM executions of Substrate extrinsics are given a synthetic transaction hash, carried by
+	/// their top-level call.
+	fn set_pseudo_transaction_hash(result: &mut single::TransactionTrace, tx_hash: Option<H256>) {
+		if let single::TransactionTrace::CallListNested(single::Call::CallTracer(call)) = result {
+			call.tx_hash = tx_hash;
 		}
 	}
 
+	/// Replays the extrinsics of a block one by one, tracing the same transactions as
+	/// `handle_block_request` and sending each trace through `traces_tx` before tracing the next
+	/// one.
+	#[allow(clippy::too_many_arguments)]
+	fn handle_block_stream_request(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		frontier_backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
+		request_block_id: RequestBlockId,
+		params: Option<TraceParams>,
+		overrides: Arc<OverrideHandle<B>>,
+		deadline: Instant,
+		traces_tx: mpsc::Sender<BlockTransactionTrace>,
+	) -> RpcResult<Response> {
+		let (tracer_input, trace_type) = Self::handle_params(params)?;
+		// Fails before replaying anything if the tracer isn't supported.
+		Self::call_list_listener(trace_type)?;
+
+		let reference_id = Self::resolve_block_id(&client, &frontier_backend, request_block_id)?;
+
+		// Get ApiRef. This handle allow to keep changes between txs in an internal buffer.
+		let api = client.runtime_api();
+		// Get the header I want to work with.
+		let Ok(hash) = client.expect_block_hash_from_id(&reference_id) else {
+			return Err(internal_err("Block header not found"))
+		};
+		let header = match client.header(hash) {
+			Ok(Some(h)) => h,
+			_ => return Err(internal_err("Block header not found")),
+		};
+
+		// Get parent blockid.
+		let parent_block_id = *header.parent_hash();
+
+		// Get DebugRuntimeApi version
+		let trace_api_version = if let Ok(Some(api_version)) =
+			api.api_version::<dyn DebugRuntimeApi<B>>(parent_block_id)
+		{
+			api_version
+		} else {
+			return Err(internal_err("Runtime api version call failed (trace)".to_string()))
+		};
+		if trace_api_version < 4 {
+			return Err(internal_err(format!(
+				"debug_subscribeTraceBlock is not supported by the runtime at {:?}",
+				reference_id
+			)))
+		}
+
+		let schema = fc_storage::onchain_storage_schema::<B, C, BE>(client.as_ref(), hash);
+
+		// Using storage overrides we align with `:ethereum_schema` which will result in proper
+		// SCALE decoding in case of migration.
+		let (transactions, statuses) = match overrides.schemas.get(&schema) {
+			Some(schema) => (
+				schema.current_block(hash).map(|block| block.transactions).unwrap_or_default(),
+				schema.current_transaction_statuses(hash).unwrap_or_default(),
+			),
+			_ => return Err(internal_err(format!("No storage override at {:?}", reference_id))),
+		};
+
+		// Known ethereum transaction hashes.
+		let eth_tx_hashes: Vec<_> = statuses.iter().map(|t| t.transaction_hash).collect();
+
+		// Get block extrinsics.
+		let exts = backend
+			.blockchain()
+			.body(hash)
+			.map_err(|e| internal_err(format!("Fail to read blockchain db: {:?}", e)))?
+			.unwrap_or_default();
+
+		let indices = ethereum_transaction_indices(&*api, parent_block_id, &exts, &transactions)
+			.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
+
+		api.initialize_block(parent_block_id, &header)
+			.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
+
+		for (ext_index, (ext, index)) in exts.into_iter().zip(indices).enumerate() {
+			if Instant::now() >= deadline {
+				return Err(internal_err("execution timeout"))
+			}
+
+			// Runtimes before version 6 only trace the Ethereum transactions.
+			let tx_hash = match index {
+				Some(index) => match statuses.get(index) {
+					Some(status) => Some(status.transaction_hash),
+					None => return Err(internal_err("Transaction status not found")),
+				},
+				None if trace_api_version >= 6 => CallStackOrigin::Substrate(ext_index as u32)
+					.transaction_hash(hash, &eth_tx_hashes),
+				None => None,
+			};
+			let Some(tx_hash) = tx_hash else {
+				let _ = api.apply_extrinsic(parent_block_id, ext);
+				continue
+			};
+
+			// Traces the extrinsic alone, applying it. The runtime call can't be interrupted, the
+			// deadline only stops the collection of its events.
+			let f = || -> RpcResult<_> {
+				api.trace_block(parent_block_id, vec![ext], eth_tx_hashes.clone())
+					.map_err(|e| {
+						internal_err(format!(
+							"Blockchain error when replaying block {} : {:?}",
+							reference_id, e
+						))
+					})?
+					.map_err(|e| {
+						internal_err(format!(
+							"Internal runtime error when replaying block {} : {:?}",
+							reference_id, e
+						))
+					})?;
+
+				Ok(peaq_rpc_primitives_debug::Response::Block)
+			};
+			let proxy = Self::trace_until(Self::call_list_listener(trace_type)?, deadline, f)?;
+
+			// Like in `handle_block_request`, an extrinsic without EVM execution has no trace.
+			for (origin, mut result) in Self::format_call_list(proxy, tracer_input)? {
+				if let CallStackOrigin::Substrate(_) = origin {
+					Self::set_pseudo_transaction_hash(&mut result, Some(tx_hash));
+				}
+				// The subscriber is gone, no need to trace the remaining transactions.
+				if traces_tx.blocking_send(BlockTransactionTrace { tx_hash, result }).is_err() {
+					return Ok(Response::Streamed)
+				}
+			}
+		}
+
+		Ok(Response::Streamed)
+	}
+
 	/// Replays a transaction in the Runtime at a given block height.
 	///
 	/// In order to succesfully reproduce the result of the original transaction we need a correct
@@ -394,6 +826,10 @@ where
 	///
 	/// Substrate allows to apply extrinsics in the Runtime and thus creating an overlayed state.
 	/// This overlayed changes will live in-memory for the lifetime of the ApiRef.
+	///
+	/// The transaction is traced by a single runtime call, which can't be interrupted: past the
+	/// deadline, its events are no longer collected but the request only fails once it returns.
+	#[allow(clippy::too_many_arguments)]
 	fn handle_transaction_request(
 		client: Arc<C>,
 		backend: Arc<BE>,
@@ -402,6 +838,7 @@ where
 		params: Option<TraceParams>,
 		overrides: Arc<OverrideHandle<B>>,
 		raw_max_memory_usage: usize,
+		deadline: Instant,
 	) -> RpcResult<Response> {
 		let (tracer_input, trace_type) = Self::handle_params(params)?;
 
@@ -467,6 +904,13 @@ where
 		if let Some(block) = reference_block {
 			let transactions = block.transactions;
 			if let Some(transaction) = transactions.get(index) {
+				// The prestate tracer needs the state the transaction is executed on.
+				let pre_api = match trace_type {
+					single::TraceType::Prestate { .. } =>
+						Some(Self::replay_until_transaction(&client, &header, &exts, transaction)?),
+					_ => None,
+				};
+
 				let f = || -> RpcResult<_> {
 					api.initialize_block(parent_block_id, &header)
 						.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
@@ -504,55 +948,291 @@ where
 					Ok(peaq_rpc_primitives_debug::Response::Single)
 				};
 
-				return match trace_type {
-					single::TraceType::Raw { disable_storage, disable_memory, disable_stack } => {
-						let mut proxy = peaq_client_evm_tracing::listeners::Raw::new(
-							disable_storage,
-							disable_memory,
-							disable_stack,
-							raw_max_memory_usage,
-						);
-						proxy.using(f)?;
-						Ok(Response::Single(
-							peaq_client_evm_tracing::formatters::Raw::format(proxy).ok_or_else(
-								|| {
-									internal_err(
-										"replayed transaction generated too much data. \
-								try disabling memory or storage?",
-									)
-								},
-							)?,
-						))
-					},
-					single::TraceType::CallList => {
-						let mut proxy = peaq_client_evm_tracing::listeners::CallList::default();
-						proxy.using(f)?;
-						proxy.finish_transaction();
-						let response = match tracer_input {
-							TracerInput::Blockscout =>
-								peaq_client_evm_tracing::formatters::Blockscout::format(proxy)
-									.ok_or("Trace result is empty.")
-									.map_err(|e| internal_err(format!("{:?}", e))),
-							TracerInput::CallTracer => {
-								let mut res =
-									peaq_client_evm_tracing::formatters::CallTracer::format(proxy)
-										.ok_or("Trace result is empty.")
-										.map_err(|e| internal_err(format!("{:?}", e)))?;
-								Ok(res.pop().expect("Trace result is empty."))
-							},
-							_ => Err(internal_err(
-								"Bug: failed to resolve the tracer format.".to_string(),
-							)),
-						}?;
-						Ok(Response::Single(response))
-					},
-					not_supported => Err(internal_err(format!(
-						"Bug: `handle_transaction_request` does not support {:?}.",
-						not_supported
-					))),
+				return match (trace_type, pre_api) {
+					(single::TraceType::Prestate { diff_mode }, Some(pre_api)) =>
+						Self::format_prestate_trace(
+							f,
+							diff_mode,
+							(&*pre_api, parent_block_id),
+							(&*api, parent_block_id),
+							deadline,
+						),
+					_ => Self::format_single_trace(
+						f,
+						tracer_input,
+						trace_type,
+						raw_max_memory_usage,
+						deadline,
+					),
 				}
 			}
 		}
 		Err(internal_err("Runtime block call failed".to_string()))
 	}
+
+	/// Traces a call which is not part of any block on top of the state of the requested block,
+	/// like `eth_call` does. Like a transaction, the call can't be interrupted.
+	#[allow(clippy::too_many_arguments)]
+	fn handle_call_request(
+		client: Arc<C>,
+		frontier_backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
+		call_params: TraceCallParams,
+		request_block_id: RequestBlockId,
+		params: Option<TraceParams>,
+		overrides: Arc<OverrideHandle<B>>,
+		raw_max_memory_usage: usize,
+		deadline: Instant,
+	) -> RpcResult<Response> {
+		let (tracer_input, trace_type) = Self::handle_params(params)?;
+
+		let reference_id = Self::resolve_block_id(&client, &frontier_backend, request_block_id)?;
+		let Ok(hash) = client.expect_block_hash_from_id(&reference_id) else {
+			return Err(internal_err("Block header not found"))
+		};
+
+		let api = client.runtime_api();
+
+		// Get DebugRuntimeApi version
+		let trace_api_version =
+			if let Ok(Some(api_version)) = api.api_version::<dyn DebugRuntimeApi<B>>(hash) {
+				api_version
+			} else {
+				return Err(internal_err("Runtime api version call failed (trace)".to_string()))
+			};
+		if trace_api_version < 5 {
+			return Err(internal_err(format!(
+				"debug_traceCall is not supported by the runtime at {:?}",
+				reference_id
+			)))
+		}
+
+		let TraceCallParams {
+			from,
+			to,
+			gas_price,
+			max_fee_per_gas,
+			max_priority_fee_per_gas,
+			gas,
+			value,
+			data,
+			nonce,
+			access_list,
+		} = call_params;
+
+		// A legacy gas price is both the max fee and the tip, as for `eth_call`.
+		let (max_fee_per_gas, max_priority_fee_per_gas) =
+			match (gas_price, max_fee_per_gas, max_priority_fee_per_gas) {
+				(Some(gas_price), None, None) => (Some(gas_price), Some(gas_price)),
+				(None, max_fee, max_priority) => {
+					if let (Some(max_fee), Some(max_priority)) = (max_fee, max_priority) {
+						if max_priority > max_fee {
+							return Err(internal_err(
+								"Invalid input: `max_priority_fee_per_gas` greater than \
+								`max_fee_per_gas`",
+							))
+						}
+					}
+					(max_fee, max_priority)
+				},
+				_ =>
+					return Err(internal_err(
+						"Invalid input: both legacy and EIP-1559 fee fields provided",
+					)),
+			};
+
+		// Default to the gas limit of the block, as for `eth_call`.
+		let gas_limit = match gas {
+			Some(gas) => gas,
+			None => {
+				let schema = fc_storage::onchain_storage_schema::<B, C, BE>(client.as_ref(), hash);
+				match overrides.schemas.get(&schema).and_then(|schema| schema.current_block(hash)) {
+					Some(block) => block.header.gas_limit,
+					None =>
+						return Err(internal_err(format!("Block not found at {:?}", reference_id))),
+				}
+			},
+		};
+
+		let f = || -> RpcResult<_> {
+			api.trace_call(
+				hash,
+				from.unwrap_or_default(),
+				to,
+				data.map(|d| d.0).unwrap_or_default(),
+				value.unwrap_or_default(),
+				gas_limit,
+				max_fee_per_gas,
+				max_priority_fee_per_gas,
+				nonce,
+				access_list.map(|list| {
+					list.into_iter().map(|item| (item.address, item.storage_keys)).collect()
+				}),
+			)
+			.map_err(|e| {
+				internal_err(format!(
+					"Runtime api access error (version {:?}): {:?}",
+					trace_api_version, e
+				))
+			})?
+			.map_err(|e| internal_err(format!("DispatchError: {:?}", e)))?;
+
+			Ok(peaq_rpc_primitives_debug::Response::Single)
+		};
+
+		match trace_type {
+			single::TraceType::Prestate { diff_mode } => Self::format_prestate_trace(
+				f,
+				diff_mode,
+				(&*client.runtime_api(), hash),
+				(&*api, hash),
+				deadline,
+			),
+			_ => Self::format_single_trace(
+				f,
+				tracer_input,
+				trace_type,
+				raw_max_memory_usage,
+				deadline,
+			),
+		}
+	}
+
+	/// Initializes the block of `header` and applies the extrinsics preceding `transaction`, so
+	/// that the returned api reads the state `transaction` is executed on.
+	fn replay_until_transaction<'a>(
+		client: &'a Arc<C>,
+		header: &B::Header,
+		exts: &[B::Extrinsic],
+		transaction: &ethereum::TransactionV2,
+	) -> RpcResult<ApiRef<'a, C::Api>> {
+		let api = client.runtime_api();
+		let parent_block_id = *header.parent_hash();
+
+		let transactions = core::slice::from_ref(transaction);
+		let indices = ethereum_transaction_indices(&*api, parent_block_id, exts, transactions)
+			.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
+		let Some(index) = indices.iter().position(Option::is_some) else {
+			return Err(internal_err("Transaction not found in the extrinsics of its block"))
+		};
+
+		api.initialize_block(parent_block_id, header)
+			.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
+
+		for ext in &exts[..index] {
+			let _ = api.apply_extrinsic(parent_block_id, ext.clone());
+		}
+
+		Ok(api)
+	}
+
+	/// Traces `f` with the prestate listener and reads the state of the accessed accounts from
+	/// `pre` and `post`.
+	fn format_prestate_trace<F>(
+		f: F,
+		diff_mode: bool,
+		pre: (&C::Api, B::Hash),
+		post: (&C::Api, B::Hash),
+		deadline: Instant,
+	) -> RpcResult<Response>
+	where
+		F: FnOnce() -> RpcResult<peaq_rpc_primitives_debug::Response>,
+	{
+		let mut proxy = Self::trace_until(
+			peaq_client_evm_tracing::listeners::Prestate::new(diff_mode),
+			deadline,
+			f,
+		)?;
+		let map_err = |e: ApiError| internal_err(format!("Runtime api access error: {:?}", e));
+		proxy.pre_state = account_states(pre.0, pre.1, &proxy.accessed).map_err(map_err)?;
+		proxy.post_state = account_states(post.0, post.1, &proxy.accessed).map_err(map_err)?;
+
+		peaq_client_evm_tracing::formatters::Prestate::format(proxy)
+			.map(Response::Single)
+			.ok_or_else(|| internal_err("Trace result is empty."))
+	}
+
+	/// Traces `f` with the listener matching `trace_type` and formats the single resulting trace.
+	fn format_single_trace<F>(
+		f: F,
+		tracer_input: TracerInput,
+		trace_type: single::TraceType,
+		raw_max_memory_usage: usize,
+		deadline: Instant,
+	) -> RpcResult<Response>
+	where
+		F: FnOnce() -> RpcResult<peaq_rpc_primitives_debug::Response>,
+	{
+		match trace_type {
+			single::TraceType::Raw { disable_storage, disable_memory, disable_stack } => {
+				let proxy = peaq_client_evm_tracing::listeners::Raw::new(
+					disable_storage,
+					disable_memory,
+					disable_stack,
+					raw_max_memory_usage,
+				);
+				let proxy = Self::trace_until(proxy, deadline, f)?;
+				Ok(Response::Single(
+					peaq_client_evm_tracing::formatters::Raw::format(proxy).ok_or_else(|| {
+						internal_err(
+							"replayed transaction generated too much data. \
+						try disabling memory or storage?",
+						)
+					})?,
+				))
+			},
+			single::TraceType::CallList { only_top_call, with_log } => {
+				let mut proxy = peaq_client_evm_tracing::listeners::CallList::default();
+				proxy.only_top_call = only_top_call;
+				proxy.with_log = with_log;
+				let mut proxy = Self::trace_until(proxy, deadline, f)?;
+				proxy.finish_transaction();
+				let response = match tracer_input {
+					TracerInput::Blockscout =>
+						peaq_client_evm_tracing::formatters::Blockscout::format(proxy)
+							.ok_or("Trace result is empty.")
+							.map_err(|e| internal_err(format!("{:?}", e))),
+					TracerInput::CallTracer => {
+						let mut res =
+							peaq_client_evm_tracing::formatters::CallTracer::format(proxy)
+								.ok_or("Trace result is empty.")
+								.map_err(|e| internal_err(format!("{:?}", e)))?;
+						Ok(res.pop().expect("Trace result is empty."))
+					},
+					_ => Err(internal_err("Bug: failed to resolve the tracer format.".to_string())),
+				}?;
+				Ok(Response::Single(response))
+			},
+			single::TraceType::Aggregate => {
+				let response = match tracer_input {
+					TracerInput::FourByteTracer => {
+						let proxy = Self::trace_until(
+							peaq_client_evm_tracing::listeners::FourByte::default(),
+							deadline,
+							f,
+						)?;
+						peaq_client_evm_tracing::formatters::FourByte::format(proxy)
+					},
+					TracerInput::OpcountTracer => {
+						let proxy = Self::trace_until(
+							peaq_client_evm_tracing::listeners::Opcount::default(),
+							deadline,
+							f,
+						)?;
+						peaq_client_evm_tracing::formatters::Opcount::format(proxy)
+					},
+					_ =>
+						return Err(internal_err(
+							"Bug: failed to resolve the tracer format.".to_string(),
+						)),
+				};
+				response
+					.map(Response::Single)
+					.ok_or_else(|| internal_err("Trace result is empty."))
+			},
+			not_supported => Err(internal_err(format!(
+				"Bug: `format_single_trace` does not support {:?}.",
+				not_supported
+			))),
+		}
+	}
 }
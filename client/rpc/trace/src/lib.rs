@@ -14,15 +14,23 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
-//! `trace_filter` RPC handler and its associated service task.
-//! The RPC handler rely on `CacheTask` which provides a future that must be run inside a tokio
+//! `trace_*` RPC handlers and their associated service task.
+//! The RPC handlers rely on `CacheTask` which provides a future that must be run inside a tokio
 //! executor.
 //!
+//! `trace_filter`, `trace_block`, `trace_transaction` and `trace_get` read the traces of the
+//! cached blocks. `trace_replayTransaction` and `trace_replayBlockTransactions` additionally ask
+//! the `CacheTask` to replay the block to compute the state diffs and opcode level traces, which
+//! are not cached.
+//!
 //! The implementation is composed of multiple tasks :
-//! - Many calls the the RPC handler `Trace::filter`, communicating with the main task.
+//! - Many calls the the RPC handlers of `Trace`, communicating with the main task.
 //! - A main `CacheTask` managing the cache and the communication between tasks.
 //! - For each traced block an async task responsible to wait for a permit, spawn a blocking task
 //!   and waiting for the result, then send it to the main `CacheTask`.
+//!
+//! Traces can also be persisted on disk in a `TraceStore` (see the `store` module), which is
+//! looked up before replaying a block.
 
 use futures::{select, stream::FuturesUnordered, FutureExt, StreamExt};
 use std::{collections::BTreeMap, future::Future, marker::PhantomData, sync::Arc, time::Duration};
@@ -32,42 +40,54 @@ use tokio::{
 };
 use tracing::{instrument, Instrument};
 
+mod store;
+pub use store::{fill_trace_store, TraceStore};
+
 use sc_client_api::backend::{Backend, StateBackend, StorageProvider};
 use sc_utils::mpsc::TracingUnboundedSender;
-use sp_api::{ApiExt, Core, HeaderT, ProvideRuntimeApi};
+use sp_api::{ApiError, ApiExt, Core, HeaderT, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{
 	Backend as BlockchainBackend, Error as BlockChainError, HeaderBackend, HeaderMetadata,
 };
-use sp_runtime::traits::{BlakeTwo256, Block as BlockT};
+use sp_runtime::{
+	traits::{BlakeTwo256, Block as BlockT},
+	TransactionOutcome,
+};
 
-use ethereum_types::H256;
-use fc_rpc::OverrideHandle;
+use ethereum_types::{H256, U64};
+use fc_rpc::{frontier_backend_client, OverrideHandle};
 use fp_rpc::EthereumRuntimeRPCApi;
 
 use peaq_client_evm_tracing::{
-	formatters::ResponseFormatter,
+	formatters::{state_diff::StateDiff, vm_trace::VmTrace, ResponseFormatter},
+	listeners::CallStackOrigin,
+	replay::{account_states, ethereum_transaction_indices},
 	types::block::{self, TransactionTrace},
 };
-pub use peaq_rpc_core_trace::{FilterRequest, TraceServer};
+pub use peaq_rpc_core_trace::{FilterRequest, TraceResultType, TraceResults, TraceServer};
 use peaq_rpc_core_types::{RequestBlockId, RequestBlockTag};
 use peaq_rpc_primitives_debug::DebugRuntimeApi;
 
 type TxsTraceRes = Result<Vec<TransactionTrace>, String>;
+type StateDiffsRes = Result<BTreeMap<H256, StateDiff>, String>;
+type VmTracesRes = Result<BTreeMap<H256, VmTrace>, String>;
 
 /// RPC handler. Will communicate with a `CacheTask` through a `CacheRequester`.
-pub struct Trace<B, C> {
+pub struct Trace<B: BlockT, C> {
 	_phantom: PhantomData<B>,
 	client: Arc<C>,
+	frontier_backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
 	requester: CacheRequester,
 	max_count: u32,
 }
 
-impl<B, C> Clone for Trace<B, C> {
+impl<B: BlockT, C> Clone for Trace<B, C> {
 	fn clone(&self) -> Self {
 		Self {
 			_phantom: PhantomData,
 			client: Arc::clone(&self.client),
+			frontier_backend: Arc::clone(&self.frontier_backend),
 			requester: self.requester.clone(),
 			max_count: self.max_count,
 		}
@@ -82,8 +102,13 @@ where
 	C: Send + Sync + 'static,
 {
 	/// Create a new RPC handler.
-	pub fn new(client: Arc<C>, requester: CacheRequester, max_count: u32) -> Self {
-		Self { client, requester, max_count, _phantom: PhantomData }
+	pub fn new(
+		client: Arc<C>,
+		frontier_backend: Arc<dyn fc_db::BackendReader<B> + Send + Sync>,
+		requester: CacheRequester,
+		max_count: u32,
+	) -> Self {
+		Self { client, frontier_backend, requester, max_count, _phantom: PhantomData }
 	}
 
 	/// Convert an optional block ID (number or tag) to a block height.
@@ -211,6 +236,212 @@ where
 
 		Ok(traces)
 	}
+
+	/// Convert a block ID to the hash of the Substrate block, or `None` for the genesis block
+	/// which has no traces.
+	async fn substrate_block_hash(&self, id: RequestBlockId) -> Result<Option<H256>, String> {
+		if let RequestBlockId::Hash(eth_block_hash) = id {
+			return frontier_backend_client::load_hash::<B, C>(
+				self.client.as_ref(),
+				self.frontier_backend.as_ref(),
+				eth_block_hash,
+			)
+			.await
+			.map_err(|e| e.to_string())?
+			.map(Some)
+			.ok_or_else(|| format!("Block with hash {:?} don't exist", eth_block_hash))
+		}
+
+		let block_height = self.block_id(Some(id))?;
+		if block_height == 0 {
+			return Ok(None)
+		}
+		self.client
+			.hash(block_height)
+			.map_err(|e| format!("Error when fetching block {} header : {:?}", block_height, e))?
+			.map(Some)
+			.ok_or_else(|| format!("Block with height {} don't exist", block_height))
+	}
+
+	/// Find the Substrate block containing an Ethereum transaction and its position in it.
+	async fn transaction_location(
+		&self,
+		transaction_hash: H256,
+	) -> Result<Option<(H256, u32)>, String> {
+		let Some((eth_block_hash, position)) = frontier_backend_client::load_transactions::<B, C>(
+			self.client.as_ref(),
+			self.frontier_backend.as_ref(),
+			transaction_hash,
+			true,
+		)
+		.await
+		.map_err(|e| e.to_string())?
+		else {
+			return Ok(None)
+		};
+
+		let block_hash = frontier_backend_client::load_hash::<B, C>(
+			self.client.as_ref(),
+			self.frontier_backend.as_ref(),
+			eth_block_hash,
+		)
+		.await
+		.map_err(|e| e.to_string())?
+		.ok_or_else(|| format!("Block with hash {:?} don't exist", eth_block_hash))?;
+
+		Ok(Some((block_hash, position)))
+	}
+
+	/// Fetch the traces of a single block through the cache task.
+	async fn block_traces(&self, block_hash: H256) -> TxsTraceRes {
+		let batch_id = self.requester.start_batch(vec![block_hash]).await?;
+		let res = self.requester.get_traces(block_hash).await;
+		self.requester.stop_batch(batch_id).await;
+
+		res
+	}
+
+	/// `trace_block` endpoint.
+	async fn block(self, block: RequestBlockId) -> TxsTraceRes {
+		match self.substrate_block_hash(block).await? {
+			Some(block_hash) => self.block_traces(block_hash).await,
+			None => Ok(vec![]),
+		}
+	}
+
+	/// `trace_transaction` endpoint.
+	async fn transaction(
+		self,
+		transaction_hash: H256,
+	) -> Result<Option<Vec<TransactionTrace>>, String> {
+		let Some((block_hash, _)) = self.transaction_location(transaction_hash).await? else {
+			return Ok(None)
+		};
+
+		// Positions of Ethereum transactions and Substrate extrinsics can be the same.
+		let traces = self.block_traces(block_hash).await?;
+		Ok(Some(
+			traces
+				.into_iter()
+				.filter(|trace| trace.transaction_hash == transaction_hash)
+				.collect(),
+		))
+	}
+
+	/// `trace_get` endpoint.
+	async fn get(
+		self,
+		transaction_hash: H256,
+		indices: Vec<U64>,
+	) -> Result<Option<TransactionTrace>, String> {
+		let trace_address: Vec<u32> = indices.iter().map(|index| index.low_u32()).collect();
+		Ok(self
+			.transaction(transaction_hash)
+			.await?
+			.unwrap_or_default()
+			.into_iter()
+			.find(|trace| trace.trace_address == trace_address))
+	}
+
+	/// Replays the transactions of a block, keeping the result of the transaction
+	/// `transaction_hash` (all of them if `None`).
+	async fn replay(
+		&self,
+		block_hash: H256,
+		transaction_hash: Option<H256>,
+		trace_types: &[TraceResultType],
+	) -> Result<Vec<TraceResults>, String> {
+		// The traces are always needed for the output of the transactions.
+		let traces_by_transaction = traces_by_transaction(self.block_traces(block_hash).await?);
+
+		let mut state_diffs = if trace_types.contains(&TraceResultType::StateDiff) {
+			self.requester.get_state_diffs(block_hash).await?
+		} else {
+			BTreeMap::new()
+		};
+
+		let mut vm_traces = if trace_types.contains(&TraceResultType::VmTrace) {
+			self.requester.get_vm_traces(block_hash).await?
+		} else {
+			BTreeMap::new()
+		};
+
+		// Substrate extrinsics have no state diff nor opcode level trace.
+		Ok(traces_by_transaction
+			.into_iter()
+			.filter(|(hash, _)| {
+				transaction_hash.map_or(true, |transaction_hash| transaction_hash == *hash)
+			})
+			.map(|(hash, traces)| TraceResults {
+				output: transaction_output(&traces),
+				transaction_hash: Some(hash),
+				trace: trace_types.contains(&TraceResultType::Trace).then_some(traces),
+				state_diff: state_diffs.remove(&hash),
+				vm_trace: vm_traces.remove(&hash),
+			})
+			.collect())
+	}
+
+	/// `trace_replayTransaction` endpoint.
+	async fn replay_transaction(
+		self,
+		transaction_hash: H256,
+		trace_types: Vec<TraceResultType>,
+	) -> Result<TraceResults, String> {
+		let (block_hash, _) = self
+			.transaction_location(transaction_hash)
+			.await?
+			.ok_or_else(|| format!("Transaction {:?} not found", transaction_hash))?;
+
+		let result = self.replay(block_hash, Some(transaction_hash), &trace_types).await?.pop();
+		result
+			.map(|result| TraceResults { transaction_hash: None, ..result })
+			.ok_or_else(|| format!("Transaction {:?} has not been replayed", transaction_hash))
+	}
+
+	/// `trace_replayBlockTransactions` endpoint.
+	async fn replay_block_transactions(
+		self,
+		block: RequestBlockId,
+		trace_types: Vec<TraceResultType>,
+	) -> Result<Vec<TraceResults>, String> {
+		match self.substrate_block_hash(block).await? {
+			Some(block_hash) => self.replay(block_hash, None, &trace_types).await,
+			None => Ok(vec![]),
+		}
+	}
+}
+
+/// Output of a transaction, or code of the contract it created, taken from its root trace.
+/// Traces of a block grouped by transaction, in block order. The traces of a transaction are
+/// contiguous.
+fn traces_by_transaction(traces: Vec<TransactionTrace>) -> Vec<(H256, Vec<TransactionTrace>)> {
+	let mut traces_by_transaction: Vec<(H256, Vec<TransactionTrace>)> = vec![];
+	for trace in traces {
+		match traces_by_transaction.last_mut() {
+			Some((hash, traces)) if *hash == trace.transaction_hash => traces.push(trace),
+			_ => traces_by_transaction.push((trace.transaction_hash, vec![trace])),
+		}
+	}
+	traces_by_transaction
+}
+
+fn transaction_output(traces: &[TransactionTrace]) -> Vec<u8> {
+	match traces
+		.iter()
+		.find(|trace| trace.trace_address.is_empty())
+		.map(|trace| &trace.output)
+	{
+		Some(block::TransactionTraceOutput::Result(block::TransactionTraceResult::Call {
+			output,
+			..
+		})) => output.clone(),
+		Some(block::TransactionTraceOutput::Result(block::TransactionTraceResult::Create {
+			code,
+			..
+		})) => code.clone(),
+		_ => vec![],
+	}
 }
 
 #[jsonrpsee::core::async_trait]
@@ -227,6 +458,50 @@ where
 	) -> jsonrpsee::core::RpcResult<Vec<TransactionTrace>> {
 		self.clone().filter(filter).await.map_err(fc_rpc::internal_err)
 	}
+
+	async fn block(
+		&self,
+		block: RequestBlockId,
+	) -> jsonrpsee::core::RpcResult<Vec<TransactionTrace>> {
+		self.clone().block(block).await.map_err(fc_rpc::internal_err)
+	}
+
+	async fn transaction(
+		&self,
+		transaction_hash: H256,
+	) -> jsonrpsee::core::RpcResult<Option<Vec<TransactionTrace>>> {
+		self.clone().transaction(transaction_hash).await.map_err(fc_rpc::internal_err)
+	}
+
+	async fn get(
+		&self,
+		transaction_hash: H256,
+		indices: Vec<U64>,
+	) -> jsonrpsee::core::RpcResult<Option<TransactionTrace>> {
+		self.clone().get(transaction_hash, indices).await.map_err(fc_rpc::internal_err)
+	}
+
+	async fn replay_transaction(
+		&self,
+		transaction_hash: H256,
+		trace_types: Vec<TraceResultType>,
+	) -> jsonrpsee::core::RpcResult<TraceResults> {
+		self.clone()
+			.replay_transaction(transaction_hash, trace_types)
+			.await
+			.map_err(fc_rpc::internal_err)
+	}
+
+	async fn replay_block_transactions(
+		&self,
+		block: RequestBlockId,
+		trace_types: Vec<TraceResultType>,
+	) -> jsonrpsee::core::RpcResult<Vec<TraceResults>> {
+		self.clone()
+			.replay_block_transactions(block, trace_types)
+			.await
+			.map_err(fc_rpc::internal_err)
+	}
 }
 
 /// An opaque batch ID.
@@ -254,6 +529,22 @@ enum CacheRequest {
 	/// Notify the cache that it can stop the batch with that ID. Any block contained only in
 	/// this batch and still not started will be discarded.
 	StopBatch { batch_id: CacheBatchId },
+	/// Replay the given block hash to compute the state diff of each of its Ethereum
+	/// transactions. State diffs are not cached.
+	GetStateDiffs {
+		/// Returns the array of state diffs or an error.
+		sender: oneshot::Sender<StateDiffsRes>,
+		/// Hash of the block.
+		block: H256,
+	},
+	/// Replay the given block hash to compute the opcode level trace of each of its Ethereum
+	/// transactions. Opcode level traces are not cached.
+	GetVmTraces {
+		/// Returns the array of opcode level traces or an error.
+		sender: oneshot::Sender<VmTracesRes>,
+		/// Hash of the block.
+		block: H256,
+	},
 }
 
 /// Allows to interact with the cache task.
@@ -300,6 +591,44 @@ impl CacheRequester {
 			.map_err(|e| format!("Failed to replay block. Error : {:?}", e))
 	}
 
+	/// Replay the given block hash to compute the state diff of each of its Ethereum
+	/// transactions, by transaction hash.
+	#[instrument(skip(self))]
+	pub async fn get_state_diffs(&self, block: H256) -> StateDiffsRes {
+		let (response_tx, response_rx) = oneshot::channel();
+		let sender = self.0.clone();
+
+		sender
+			.unbounded_send(CacheRequest::GetStateDiffs { sender: response_tx, block })
+			.map_err(|e| {
+				format!("Failed to send request to the trace cache task. Error : {:?}", e)
+			})?;
+
+		response_rx
+			.await
+			.map_err(|e| format!("Trace cache task closed the response channel. Error : {:?}", e))?
+			.map_err(|e| format!("Failed to replay block. Error : {:?}", e))
+	}
+
+	/// Replay the given block hash to compute the opcode level trace of each of its Ethereum
+	/// transactions, by transaction hash.
+	#[instrument(skip(self))]
+	pub async fn get_vm_traces(&self, block: H256) -> VmTracesRes {
+		let (response_tx, response_rx) = oneshot::channel();
+		let sender = self.0.clone();
+
+		sender
+			.unbounded_send(CacheRequest::GetVmTraces { sender: response_tx, block })
+			.map_err(|e| {
+				format!("Failed to send request to the trace cache task. Error : {:?}", e)
+			})?;
+
+		response_rx
+			.await
+			.map_err(|e| format!("Trace cache task closed the response channel. Error : {:?}", e))?
+			.map_err(|e| format!("Failed to replay block. Error : {:?}", e))
+	}
+
 	/// Notify the cache that it can stop the batch with that ID. Any block contained only in
 	/// this batch and still in the waiting pool will be discarded.
 	#[instrument(skip(self))]
@@ -363,6 +692,7 @@ enum BlockingTaskMessage {
 pub struct CacheTask<B, C, BE> {
 	client: Arc<C>,
 	backend: Arc<BE>,
+	store: Option<Arc<TraceStore>>,
 	blocking_permits: Arc<Semaphore>,
 	cached_blocks: BTreeMap<H256, CacheBlock>,
 	batches: BTreeMap<u64, Vec<H256>>,
@@ -395,6 +725,7 @@ where
 		cache_duration: Duration,
 		blocking_permits: Arc<Semaphore>,
 		overrides: Arc<OverrideHandle<B>>,
+		store: Option<Arc<TraceStore>>,
 	) -> (impl Future<Output = ()>, CacheRequester) {
 		// Communication with the outside world :
 		let (requester_tx, mut requester_rx) =
@@ -414,6 +745,7 @@ where
 			let mut inner = Self {
 				client,
 				backend,
+				store,
 				blocking_permits,
 				cached_blocks: BTreeMap::new(),
 				batches: BTreeMap::new(),
@@ -432,6 +764,14 @@ where
 								=> inner.request_start_batch(&blocking_tx, sender, blocks, overrides.clone()),
 							Some(CacheRequest::GetTraces {sender, block})
 								=> inner.request_get_traces(sender, block),
+							Some(CacheRequest::GetStateDiffs {sender, block})
+								=> inner.request_replay_block(
+									sender, block, overrides.clone(), Self::state_diffs_block,
+								),
+							Some(CacheRequest::GetVmTraces {sender, block})
+								=> inner.request_replay_block(
+									sender, block, overrides.clone(), Self::vm_traces_block,
+								),
 							Some(CacheRequest::StopBatch {batch_id}) => {
 								// Cannot be refactored inside `request_stop_batch` because
 								// it has an unnamable type :C
@@ -498,6 +838,7 @@ where
 				let (unqueue_sender, unqueue_receiver) = oneshot::channel();
 				let client = Arc::clone(&self.client);
 				let backend = Arc::clone(&self.backend);
+				let store = self.store.clone();
 				let blocking_tx = blocking_tx.clone();
 				let overrides = overrides.clone();
 
@@ -527,7 +868,7 @@ where
 						// Perform block tracing in a tokio blocking task.
 						let result = async {
 							tokio::task::spawn_blocking(move || {
-								Self::cache_block(client, backend, block, overrides)
+								Self::cache_block(client, backend, block, overrides, store)
 							})
 							.await
 							.map_err(|e| {
@@ -606,6 +947,42 @@ where
 		}
 	}
 
+	/// Handle a request to replay the provided block with `replay`, to get the state diffs or
+	/// opcode level traces of its transactions.
+	/// They are computed in a blocking task once a permit is obtained and sent directly to the
+	/// requester, without going through the cache.
+	#[instrument(skip(self, sender, overrides, replay))]
+	fn request_replay_block<T: Send + 'static>(
+		&mut self,
+		sender: oneshot::Sender<Result<T, String>>,
+		block: H256,
+		overrides: Arc<OverrideHandle<B>>,
+		replay: fn(Arc<C>, Arc<BE>, H256, Arc<OverrideHandle<B>>) -> Result<T, String>,
+	) {
+		let blocking_permits = Arc::clone(&self.blocking_permits);
+		let client = Arc::clone(&self.client);
+		let backend = Arc::clone(&self.backend);
+
+		tokio::spawn(
+			async move {
+				tracing::trace!("Waiting for blocking permit");
+				let _permit = blocking_permits.acquire().await;
+
+				let result = async {
+					tokio::task::spawn_blocking(move || replay(client, backend, block, overrides))
+						.await
+						.map_err(|e| {
+							format!("Replaying Substrate block {} panicked : {:?}", block, e)
+						})?
+				}
+				.await;
+
+				let _ = sender.send(result);
+			}
+			.instrument(tracing::trace_span!("Block replay", block = %block)),
+		);
+	}
+
 	/// Handle a request to stop a batch.
 	/// For all blocks that needed to be traced, are only in this batch and not yet started, their
 	/// tracing is cancelled to save CPU-time and avoid attacks requesting large amount of blocks.
@@ -704,14 +1081,28 @@ where
 		}
 	}
 
-	/// (In blocking task) Use the Runtime API to trace the block.
-	#[instrument(skip(client, backend, overrides))]
+	/// (In blocking task) Use the Runtime API to trace the block, unless its traces are in the
+	/// store. Traces of blocks within the retention window of the store are added to it.
+	#[instrument(skip(client, backend, overrides, store))]
 	fn cache_block(
 		client: Arc<C>,
 		backend: Arc<BE>,
 		substrate_hash: H256,
 		overrides: Arc<OverrideHandle<B>>,
+		store: Option<Arc<TraceStore>>,
 	) -> TxsTraceRes {
+		if let Some(store) = &store {
+			match store.get(substrate_hash) {
+				Ok(Some(traces)) => return Ok(traces),
+				Ok(None) => (),
+				Err(e) => tracing::warn!(
+					"Failed to read block {} from trace store : {}",
+					substrate_hash,
+					e
+				),
+			}
+		}
+
 		// Get Subtrate block data.
 		let api = client.runtime_api();
 		let block_header = client
@@ -744,7 +1135,7 @@ where
 		};
 
 		let eth_block_hash = eth_block.header.hash();
-		let eth_tx_hashes = eth_transactions.iter().map(|t| t.transaction_hash).collect();
+		let eth_tx_hashes: Vec<_> = eth_transactions.iter().map(|t| t.transaction_hash).collect();
 
 		// Get extrinsics (containing Ethereum ones)
 		let extrinsics = backend
@@ -760,7 +1151,7 @@ where
 			api.initialize_block(substrate_parent_id, &block_header)
 				.map_err(|e| format!("Runtime api access error: {:?}", e))?;
 
-			api.trace_block(substrate_parent_id, extrinsics, eth_tx_hashes)
+			api.trace_block(substrate_parent_id, extrinsics, eth_tx_hashes.clone())
 				.map_err(|e| format!("Blockchain error when replaying block {} : {:?}", height, e))?
 				.map_err(|e| {
 					tracing::warn!(
@@ -775,15 +1166,25 @@ where
 
 		let mut proxy = peaq_client_evm_tracing::listeners::CallList::default();
 		proxy.using(f)?;
+		proxy.finish_transaction();
+		proxy.remove_empty_call_stacks();
+		let origins = proxy.origins.clone();
 		let mut traces: Vec<_> = peaq_client_evm_tracing::formatters::TraceFilter::format(proxy)
 			.ok_or("Fail to format proxy")?;
 		// Fill missing data.
 		for trace in traces.iter_mut() {
 			trace.block_hash = eth_block_hash;
 			trace.block_number = height;
-			trace.transaction_hash = eth_transactions
+			// EVM executions of Substrate extrinsics are given a synthetic transaction hash, and
+			// their extrinsic index as position.
+			let origin = origins
 				.get(trace.transaction_position as usize)
-				.ok_or_else(|| {
+				.copied()
+				.unwrap_or(CallStackOrigin::Ethereum(trace.transaction_position));
+			let (CallStackOrigin::Ethereum(index) | CallStackOrigin::Substrate(index)) = origin;
+			trace.transaction_position = index;
+			trace.transaction_hash =
+				origin.transaction_hash(substrate_hash, &eth_tx_hashes).ok_or_else(|| {
 					tracing::warn!(
 						"Bug: A transaction has been replayed while it shouldn't (in block {}).",
 						height
@@ -793,8 +1194,7 @@ where
 						"Bug: A transaction has been replayed while it shouldn't (in block {}).",
 						height
 					)
-				})?
-				.transaction_hash;
+				})?;
 
 			// Reformat error messages.
 			if let block::TransactionTraceOutput::Error(ref mut error) = trace.output {
@@ -803,6 +1203,264 @@ where
 				}
 			}
 		}
+
+		if let Some(store) = store {
+			if store.is_retained(height, client.info().best_number) {
+				if let Err(e) = store.insert(substrate_hash, height, &traces) {
+					tracing::warn!("Failed to write block {} to trace store : {}", height, e);
+				}
+			}
+		}
 		Ok(traces)
 	}
+
+	/// (In blocking task) Use the Runtime API to replay the extrinsics of the block, letting
+	/// `replay` apply the Ethereum ones and collecting its results by transaction hash.
+	fn replay_ethereum_transactions<T>(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		substrate_hash: H256,
+		overrides: Arc<OverrideHandle<B>>,
+		mut replay: impl FnMut(
+			&C::Api,
+			H256,
+			B::Extrinsic,
+			&ethereum::TransactionV2,
+		) -> Result<T, String>,
+	) -> Result<BTreeMap<H256, T>, String> {
+		// Get Subtrate block data.
+		let api = client.runtime_api();
+		let block_header = client
+			.header(substrate_hash)
+			.map_err(|e| {
+				format!("Error when fetching substrate block {} header : {:?}", substrate_hash, e)
+			})?
+			.ok_or_else(|| format!("Subtrate block {} don't exist", substrate_hash))?;
+
+		let height = *block_header.number();
+		let substrate_parent_id = *block_header.parent_hash();
+
+		let schema =
+			fc_storage::onchain_storage_schema::<B, C, BE>(client.as_ref(), substrate_hash);
+
+		// Get Ethereum transactions.
+		let eth_transactions = match overrides.schemas.get(&schema) {
+			Some(schema) => match schema.current_block(substrate_hash) {
+				Some(block) => block.transactions,
+				_ =>
+					return Err(format!(
+						"Failed to get Ethereum block data for Substrate block {}",
+						substrate_hash
+					)),
+			},
+			_ => return Err(format!("No storage override at {:?}", substrate_hash)),
+		};
+
+		// Get extrinsics (containing Ethereum ones)
+		let extrinsics = backend
+			.blockchain()
+			.body(substrate_hash)
+			.map_err(|e| {
+				format!("Blockchain error when fetching extrinsics of block {} : {:?}", height, e)
+			})?
+			.ok_or_else(|| format!("Could not find block {} when fetching extrinsics.", height))?;
+
+		let indices = ethereum_transaction_indices(
+			&*api,
+			substrate_parent_id,
+			&extrinsics,
+			&eth_transactions,
+		)
+		.map_err(|e| format!("Runtime api access error: {:?}", e))?;
+
+		api.initialize_block(substrate_parent_id, &block_header)
+			.map_err(|e| format!("Runtime api access error: {:?}", e))?;
+
+		let mut results = BTreeMap::new();
+		for (ext, index) in extrinsics.into_iter().zip(indices) {
+			match index {
+				Some(index) => {
+					let transaction = &eth_transactions[index];
+					let result = replay(&*api, substrate_parent_id, ext, transaction)?;
+					results.insert(transaction.hash(), result);
+				},
+				None => {
+					let _ = api.apply_extrinsic(substrate_parent_id, ext);
+				},
+			}
+		}
+		Ok(results)
+	}
+
+	/// (In blocking task) Use the Runtime API to replay the block and compute the state diff of
+	/// each Ethereum transaction.
+	///
+	/// Each Ethereum transaction is first traced in a storage transaction which is rolled back,
+	/// to learn which accounts it accesses. Their state is read before and after applying the
+	/// transaction for real.
+	#[instrument(skip(client, backend, overrides))]
+	fn state_diffs_block(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		substrate_hash: H256,
+		overrides: Arc<OverrideHandle<B>>,
+	) -> StateDiffsRes {
+		Self::replay_ethereum_transactions(
+			client,
+			backend,
+			substrate_hash,
+			overrides,
+			|api, parent_hash, ext, transaction| {
+				let mut proxy = peaq_client_evm_tracing::listeners::Prestate::new(false);
+				proxy
+					.using(|| {
+						api.execute_in_transaction(|api| {
+							TransactionOutcome::Rollback(api.trace_transaction(
+								parent_hash,
+								vec![ext.clone()],
+								transaction,
+							))
+						})
+					})
+					.map_err(|e| {
+						format!(
+							"Blockchain error when replaying block {} : {:?}",
+							substrate_hash, e
+						)
+					})?
+					.map_err(|e| {
+						format!(
+							"Internal runtime error when replaying block {} : {:?}",
+							substrate_hash, e
+						)
+					})?;
+
+				let map_err = |e: ApiError| format!("Runtime api access error: {:?}", e);
+				proxy.pre_state =
+					account_states(api, parent_hash, &proxy.accessed).map_err(map_err)?;
+				let _ = api.apply_extrinsic(parent_hash, ext);
+				proxy.post_state =
+					account_states(api, parent_hash, &proxy.accessed).map_err(map_err)?;
+
+				Ok(peaq_client_evm_tracing::formatters::StateDiff::format(proxy)
+					.ok_or("Fail to format proxy")?)
+			},
+		)
+	}
+
+	/// (In blocking task) Use the Runtime API to replay the block and compute the opcode level
+	/// trace of each Ethereum transaction.
+	///
+	/// Each Ethereum transaction is first traced in a storage transaction which is rolled back.
+	/// The code of the executed contracts is read before applying the transaction for real, or
+	/// after for the contracts it creates.
+	#[instrument(skip(client, backend, overrides))]
+	fn vm_traces_block(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		substrate_hash: H256,
+		overrides: Arc<OverrideHandle<B>>,
+	) -> VmTracesRes {
+		Self::replay_ethereum_transactions(
+			client,
+			backend,
+			substrate_hash,
+			overrides,
+			|api, parent_hash, ext, transaction| {
+				let mut proxy = peaq_client_evm_tracing::listeners::VmTrace::default();
+				proxy
+					.using(|| {
+						api.execute_in_transaction(|api| {
+							TransactionOutcome::Rollback(api.trace_transaction(
+								parent_hash,
+								vec![ext.clone()],
+								transaction,
+							))
+						})
+					})
+					.map_err(|e| {
+						format!(
+							"Blockchain error when replaying block {} : {:?}",
+							substrate_hash, e
+						)
+					})?
+					.map_err(|e| {
+						format!(
+							"Internal runtime error when replaying block {} : {:?}",
+							substrate_hash, e
+						)
+					})?;
+
+				let map_err = |e: ApiError| format!("Runtime api access error: {:?}", e);
+				for address in proxy.code_addresses.iter() {
+					let code = api.account_code_at(parent_hash, *address).map_err(map_err)?;
+					proxy.codes.insert(*address, code);
+				}
+				let _ = api.apply_extrinsic(parent_hash, ext);
+				for (address, code) in proxy.codes.iter_mut().filter(|(_, code)| code.is_empty()) {
+					*code = api.account_code_at(parent_hash, *address).map_err(map_err)?;
+				}
+
+				Ok(peaq_client_evm_tracing::formatters::VmTrace::format(proxy)
+					.ok_or("Fail to format proxy")?)
+			},
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::{H160, U256};
+	use peaq_client_evm_tracing::types::{block::TransactionTraceAction, CallType};
+
+	fn test_trace(
+		transaction_hash: H256,
+		position: u32,
+		trace_address: Vec<u32>,
+	) -> TransactionTrace {
+		TransactionTrace {
+			action: TransactionTraceAction::Call {
+				call_type: CallType::Call,
+				from: H160::repeat_byte(1),
+				gas: U256::from(21_000),
+				input: vec![],
+				to: H160::repeat_byte(2),
+				value: U256::zero(),
+			},
+			block_hash: H256::repeat_byte(0xbb),
+			block_number: 1,
+			output: block::TransactionTraceOutput::Result(block::TransactionTraceResult::Call {
+				gas_used: U256::zero(),
+				output: transaction_hash.as_bytes().to_vec(),
+			}),
+			subtraces: 0,
+			trace_address,
+			transaction_hash,
+			transaction_position: position,
+		}
+	}
+
+	#[test]
+	fn pseudo_transactions_are_grouped_by_hash() {
+		// The Substrate extrinsic at index 0 and the first Ethereum transaction share their
+		// position.
+		let substrate = H256::repeat_byte(1);
+		let ethereum = H256::repeat_byte(2);
+		let traces = vec![
+			test_trace(substrate, 0, vec![]),
+			test_trace(substrate, 0, vec![0]),
+			test_trace(ethereum, 0, vec![]),
+		];
+
+		let grouped = traces_by_transaction(traces.clone());
+		assert_eq!(
+			grouped,
+			vec![
+				(substrate, vec![traces[0].clone(), traces[1].clone()]),
+				(ethereum, vec![traces[2].clone()]),
+			]
+		);
+		assert_eq!(transaction_output(&grouped[1].1), ethereum.as_bytes().to_vec());
+	}
 }
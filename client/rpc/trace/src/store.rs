@@ -0,0 +1,440 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Persistent storage of the traces of the recent blocks, used by the `CacheTask` to avoid
+//! replaying blocks whose in-memory cache has expired.
+//!
+//! Only the blocks within `retention` blocks of the best block are kept. The store is filled in
+//! the background (see `fill_trace_store`) and when an older block of the window is traced on
+//! demand. The traces of the blocks retracted by a reorg are removed.
+//!
+//! Import notifications are not sent during the initial sync, so the background task doesn't
+//! only trace the notified blocks: on start and on each new best block, it traces every block of
+//! the window missing from the store. It traces one block at a time and doesn't take the permits
+//! of the RPC requests, so a long backfill doesn't delay them.
+//!
+//! The store is a cache: when its schema version doesn't match `SCHEMA_VERSION`, its traces are
+//! dropped and traced again.
+
+use futures::StreamExt;
+use kvdb::{DBTransaction, KeyValueDB};
+use kvdb_rocksdb::{Database, DatabaseConfig};
+use parity_scale_codec::{Decode, Encode};
+use std::{future::Future, path::Path, sync::Arc};
+use tracing::Instrument;
+
+use sc_client_api::{
+	backend::{Backend, StateBackend, StorageProvider},
+	BlockchainEvents,
+};
+use sp_api::{ApiExt, HeaderT, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder;
+use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_runtime::traits::{BlakeTwo256, Block as BlockT};
+
+use ethereum_types::H256;
+use fc_rpc::OverrideHandle;
+use fp_rpc::EthereumRuntimeRPCApi;
+
+use peaq_client_evm_tracing::types::block::TransactionTrace;
+use peaq_rpc_primitives_debug::DebugRuntimeApi;
+
+use crate::CacheTask;
+
+/// Block hash => SCALE encoded `(block number, traces)`.
+const COLUMN_TRACES: u32 = 0;
+/// Big endian block number ++ block hash => empty, to prune the blocks by number.
+const COLUMN_NUMBERS: u32 = 1;
+/// `VERSION_KEY` => SCALE encoded schema version of the stored traces.
+const COLUMN_META: u32 = 2;
+const NUM_COLUMNS: u32 = 3;
+
+const VERSION_KEY: &[u8] = b"version";
+/// Version of the encoding of the stored traces, to bump when `TransactionTrace` changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// On-disk store of the traces of the recent blocks.
+pub struct TraceStore {
+	db: Database,
+	retention: u32,
+}
+
+impl TraceStore {
+	/// Open (or create) the store at `path`, keeping the traces of the last `retention` blocks.
+	pub fn open(path: &Path, retention: u32) -> Result<Self, String> {
+		let db = Database::open(&DatabaseConfig::with_columns(NUM_COLUMNS), path)
+			.map_err(|e| format!("Failed to open trace store at {:?} : {:?}", path, e))?;
+
+		let store = Self { db, retention };
+		store.check_schema_version()?;
+		Ok(store)
+	}
+
+	/// Drop the stored traces if they were stored with another schema version, or before the
+	/// version was stored.
+	fn check_schema_version(&self) -> Result<(), String> {
+		let version = self
+			.db
+			.get(COLUMN_META, VERSION_KEY)
+			.map_err(db_err)?
+			.map(|value| u32::decode(&mut &value[..]))
+			.transpose()
+			.map_err(|e| format!("Failed to decode trace store version : {:?}", e))?;
+		if version == Some(SCHEMA_VERSION) {
+			return Ok(())
+		}
+
+		if let Some(version) = version {
+			tracing::info!(
+				"Dropping the traces of schema version {} for version {}",
+				version,
+				SCHEMA_VERSION
+			);
+		}
+		let mut transaction = DBTransaction::new();
+		transaction.delete_prefix(COLUMN_TRACES, &[]);
+		transaction.delete_prefix(COLUMN_NUMBERS, &[]);
+		transaction.put(COLUMN_META, VERSION_KEY, &SCHEMA_VERSION.encode());
+
+		self.db.write(transaction).map_err(db_err)
+	}
+
+	/// Whether the traces of block `number` are kept while `best_number` is the best block.
+	pub fn is_retained(&self, number: u32, best_number: u32) -> bool {
+		number.saturating_add(self.retention) > best_number
+	}
+
+	/// First block number whose traces are kept while `best_number` is the best block.
+	pub fn first_retained(&self, best_number: u32) -> u32 {
+		best_number.saturating_add(1).saturating_sub(self.retention)
+	}
+
+	/// Whether the traces of the given block hash are stored.
+	pub fn contains(&self, block: H256) -> Result<bool, String> {
+		self.db.has_key(COLUMN_TRACES, block.as_bytes()).map_err(db_err)
+	}
+
+	/// Traces of the given block hash, if stored.
+	pub fn get(&self, block: H256) -> Result<Option<Vec<TransactionTrace>>, String> {
+		let Some(value) = self.db.get(COLUMN_TRACES, block.as_bytes()).map_err(db_err)? else {
+			return Ok(None)
+		};
+
+		let (_, traces) = <(u32, Vec<TransactionTrace>)>::decode(&mut &value[..])
+			.map_err(|e| format!("Failed to decode stored traces of block {} : {:?}", block, e))?;
+		Ok(Some(traces))
+	}
+
+	/// Store the traces of the given block.
+	pub fn insert(
+		&self,
+		block: H256,
+		number: u32,
+		traces: &[TransactionTrace],
+	) -> Result<(), String> {
+		let mut transaction = DBTransaction::new();
+		transaction.put(COLUMN_TRACES, block.as_bytes(), &(number, traces).encode());
+		transaction.put(COLUMN_NUMBERS, &number_key(number, block), &[]);
+
+		self.db.write(transaction).map_err(db_err)
+	}
+
+	/// Remove the traces of the given block, if stored.
+	pub fn remove(&self, block: H256) -> Result<(), String> {
+		let Some(value) = self.db.get(COLUMN_TRACES, block.as_bytes()).map_err(db_err)? else {
+			return Ok(())
+		};
+		let number = u32::decode(&mut &value[..])
+			.map_err(|e| format!("Failed to decode stored traces of block {} : {:?}", block, e))?;
+
+		let mut transaction = DBTransaction::new();
+		transaction.delete(COLUMN_TRACES, block.as_bytes());
+		transaction.delete(COLUMN_NUMBERS, &number_key(number, block));
+
+		self.db.write(transaction).map_err(db_err)
+	}
+
+	/// Remove the traces of the blocks which are no longer retained.
+	pub fn prune(&self, best_number: u32) -> Result<(), String> {
+		let mut transaction = DBTransaction::new();
+		// Keys are ordered by block number, so we stop at the first retained block.
+		for entry in self.db.iter(COLUMN_NUMBERS) {
+			let (key, _) = entry.map_err(db_err)?;
+			let mut number = [0u8; 4];
+			number.copy_from_slice(&key[..4]);
+			if self.is_retained(u32::from_be_bytes(number), best_number) {
+				break
+			}
+
+			transaction.delete(COLUMN_TRACES, &key[4..]);
+			transaction.delete(COLUMN_NUMBERS, &key);
+		}
+
+		self.db.write(transaction).map_err(db_err)
+	}
+}
+
+fn number_key(number: u32, block: H256) -> Vec<u8> {
+	let mut key = number.to_be_bytes().to_vec();
+	key.extend_from_slice(block.as_bytes());
+	key
+}
+
+fn db_err(e: std::io::Error) -> String {
+	format!("Trace store error : {:?}", e)
+}
+
+/// Task tracing the blocks of the retention window into the store, and removing the blocks
+/// retracted by reorgs. It must be run inside a tokio executor.
+pub fn fill_trace_store<B, C, BE>(
+	store: Arc<TraceStore>,
+	client: Arc<C>,
+	backend: Arc<BE>,
+	overrides: Arc<OverrideHandle<B>>,
+) -> impl Future<Output = ()>
+where
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+	C: ProvideRuntimeApi<B>,
+	C: StorageProvider<B, BE>,
+	C: HeaderMetadata<B, Error = BlockChainError> + HeaderBackend<B>,
+	C: BlockchainEvents<B>,
+	C: Send + Sync + 'static,
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	B::Header: HeaderT<Number = u32>,
+	C::Api: BlockBuilder<B>,
+	C::Api: DebugRuntimeApi<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	C::Api: ApiExt<B>,
+{
+	async move {
+		// Subscribe first so the blocks imported while backfilling are notified.
+		let mut import_notifications = client.import_notification_stream();
+
+		// Backfill the window of the current best block, which also prunes the blocks no longer
+		// retained if the retention window has been reduced since the last run.
+		let best_number = client.info().best_number;
+		let from = store.first_retained(best_number);
+		fill_blocks(&store, &client, &backend, &overrides, from, best_number).await;
+		// First block number not filled yet.
+		let mut next_number = best_number.saturating_add(1);
+
+		while let Some(notification) = import_notifications.next().await {
+			// Blocks enacted by a reorg replace blocks which might already be filled.
+			let mut enacted_number = next_number;
+			if let Some(tree_route) = &notification.tree_route {
+				for retracted in tree_route.retracted() {
+					if let Err(e) = store.remove(retracted.hash) {
+						tracing::warn!(
+							"Failed to remove retracted block {} : {}",
+							retracted.hash,
+							e
+						);
+					}
+				}
+				for enacted in tree_route.enacted() {
+					enacted_number = enacted_number.min(enacted.number);
+				}
+			}
+
+			if !notification.is_new_best {
+				continue
+			}
+
+			// Also fill the blocks imported without notification since the last new best block.
+			let number = *notification.header.number();
+			let from = store.first_retained(number).max(enacted_number.min(number));
+			fill_blocks(&store, &client, &backend, &overrides, from, number).await;
+			next_number = next_number.max(number.saturating_add(1));
+		}
+	}
+	.instrument(tracing::debug_span!("trace_store"))
+}
+
+/// Trace the canonical blocks `from..=to` missing from the store into it, then prune the blocks
+/// no longer retained once `to` is the best block.
+async fn fill_blocks<B, C, BE>(
+	store: &Arc<TraceStore>,
+	client: &Arc<C>,
+	backend: &Arc<BE>,
+	overrides: &Arc<OverrideHandle<B>>,
+	from: u32,
+	to: u32,
+) where
+	BE: Backend<B> + 'static,
+	BE::State: StateBackend<BlakeTwo256>,
+	C: ProvideRuntimeApi<B>,
+	C: StorageProvider<B, BE>,
+	C: HeaderMetadata<B, Error = BlockChainError> + HeaderBackend<B>,
+	C: Send + Sync + 'static,
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	B::Header: HeaderT<Number = u32>,
+	C::Api: BlockBuilder<B>,
+	C::Api: DebugRuntimeApi<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	C::Api: ApiExt<B>,
+{
+	for number in from..=to {
+		let block = match client.hash(number) {
+			Ok(Some(block)) => block,
+			Ok(None) => continue,
+			Err(e) => {
+				tracing::warn!("Failed to get hash of block {} : {:?}", number, e);
+				continue
+			},
+		};
+		match store.contains(block) {
+			Ok(false) => (),
+			Ok(true) => continue,
+			Err(e) => {
+				tracing::warn!("Failed to read trace store : {}", e);
+				continue
+			},
+		}
+
+		let client = Arc::clone(client);
+		let backend = Arc::clone(backend);
+		let overrides = Arc::clone(overrides);
+		let block_store = Arc::clone(store);
+		// Tracing the block stores its traces.
+		match tokio::task::spawn_blocking(move || {
+			CacheTask::<B, C, BE>::cache_block(client, backend, block, overrides, Some(block_store))
+		})
+		.await
+		{
+			Ok(Ok(_)) => (),
+			Ok(Err(e)) => tracing::warn!("Failed to trace block {} : {}", block, e),
+			Err(e) => tracing::warn!("Tracing Substrate block {} panicked : {:?}", block, e),
+		}
+	}
+
+	if let Err(e) = store.prune(to) {
+		tracing::warn!("Failed to prune trace store : {}", e);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::{H160, U256};
+	use peaq_client_evm_tracing::types::{
+		block::{TransactionTraceAction, TransactionTraceOutput},
+		CallType,
+	};
+
+	fn test_store(retention: u32) -> (tempfile::TempDir, TraceStore) {
+		let dir = tempfile::tempdir().unwrap();
+		let store = TraceStore::open(dir.path(), retention).unwrap();
+		(dir, store)
+	}
+
+	fn test_traces(block: H256, number: u32) -> Vec<TransactionTrace> {
+		vec![TransactionTrace {
+			action: TransactionTraceAction::Call {
+				call_type: CallType::Call,
+				from: H160::repeat_byte(1),
+				gas: U256::from(21_000),
+				input: vec![],
+				to: H160::repeat_byte(2),
+				value: U256::zero(),
+			},
+			block_hash: block,
+			block_number: number,
+			output: TransactionTraceOutput::Error(b"Reverted".to_vec()),
+			subtraces: 0,
+			trace_address: vec![],
+			transaction_hash: H256::repeat_byte(3),
+			transaction_position: 0,
+		}]
+	}
+
+	#[test]
+	fn insert_get_and_remove() {
+		let (_dir, store) = test_store(10);
+		let block = H256::repeat_byte(1);
+		let traces = test_traces(block, 5);
+
+		assert_eq!(store.get(block).unwrap(), None);
+		assert!(!store.contains(block).unwrap());
+
+		store.insert(block, 5, &traces).unwrap();
+		assert_eq!(store.get(block).unwrap(), Some(traces));
+		assert!(store.contains(block).unwrap());
+
+		store.remove(block).unwrap();
+		assert_eq!(store.get(block).unwrap(), None);
+		assert!(!store.contains(block).unwrap());
+		// Removing a missing block is a no-op.
+		store.remove(block).unwrap();
+	}
+
+	#[test]
+	fn traces_are_kept_across_reopening() {
+		let dir = tempfile::tempdir().unwrap();
+		let block = H256::repeat_byte(1);
+		let traces = test_traces(block, 5);
+
+		TraceStore::open(dir.path(), 10).unwrap().insert(block, 5, &traces).unwrap();
+		assert_eq!(TraceStore::open(dir.path(), 10).unwrap().get(block).unwrap(), Some(traces));
+	}
+
+	#[test]
+	fn traces_of_another_schema_version_are_dropped() {
+		let dir = tempfile::tempdir().unwrap();
+		let block = H256::repeat_byte(1);
+		let store = TraceStore::open(dir.path(), 10).unwrap();
+		store.insert(block, 5, &test_traces(block, 5)).unwrap();
+
+		let mut transaction = DBTransaction::new();
+		transaction.put(COLUMN_META, VERSION_KEY, &(SCHEMA_VERSION + 1).encode());
+		store.db.write(transaction).unwrap();
+		drop(store);
+
+		let store = TraceStore::open(dir.path(), 10).unwrap();
+		assert!(!store.contains(block).unwrap());
+		assert_eq!(store.db.get(COLUMN_META, VERSION_KEY).unwrap(), Some(SCHEMA_VERSION.encode()));
+	}
+
+	#[test]
+	fn retention_window_boundaries() {
+		let (_dir, store) = test_store(10);
+
+		// Blocks 11 to 20 are retained while 20 is the best block.
+		assert_eq!(store.first_retained(20), 11);
+		assert!(!store.is_retained(10, 20));
+		assert!(store.is_retained(11, 20));
+		assert!(store.is_retained(20, 20));
+
+		// The whole chain is retained while it is shorter than the window.
+		assert_eq!(store.first_retained(5), 0);
+		assert!(store.is_retained(0, 5));
+
+		let (_dir, store) = test_store(0);
+		assert_eq!(store.first_retained(20), 21);
+		assert!(!store.is_retained(20, 20));
+	}
+
+	#[test]
+	fn prune_removes_blocks_out_of_the_window() {
+		let (_dir, store) = test_store(10);
+		let blocks: Vec<_> = (0..=20u8).map(H256::repeat_byte).collect();
+		for (number, block) in blocks.iter().enumerate() {
+			store
+				.insert(*block, number as u32, &test_traces(*block, number as u32))
+				.unwrap();
+		}
+		// A block of a fork at the same height as a pruned one.
+		let fork = H256::repeat_byte(0xff);
+		store.insert(fork, 10, &test_traces(fork, 10)).unwrap();
+
+		store.prune(20).unwrap();
+
+		for (number, block) in blocks.iter().enumerate() {
+			assert_eq!(store.contains(*block).unwrap(), number >= 11, "block {}", number);
+		}
+		assert!(!store.contains(fork).unwrap());
+
+		// Pruning again with the same best block is a no-op.
+		store.prune(20).unwrap();
+		assert!(store.contains(blocks[11]).unwrap());
+	}
+}
@@ -17,8 +17,9 @@
 use ethereum_types::{H160, H256, U256};
 use fc_rpc::{internal_err, public_key};
 use jsonrpsee::core::RpcResult;
+use parity_scale_codec::Encode;
 pub use peaq_rpc_core_txpool::{
-	GetT, Summary, Transaction, TransactionMap, TxPoolResult, TxPoolServer,
+	GetT, NativeTransaction, Summary, Transaction, TransactionMap, TxPoolResult, TxPoolServer,
 };
 use sc_transaction_pool::{ChainApi, Pool};
 use sc_transaction_pool_api::InPoolTransaction;
@@ -27,7 +28,7 @@ use sha3::{Digest, Keccak256};
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_runtime::traits::Block as BlockT;
-use std::{marker::PhantomData, sync::Arc};
+use std::{collections::HashMap, marker::PhantomData, sync::Arc};
 
 use peaq_rpc_primitives_txpool::{Transaction as TransactionV2, TxPoolResponse, TxPoolRuntimeApi};
 
@@ -52,6 +53,28 @@ where
 	where
 		T: GetT + Serialize,
 	{
+		let (txs_ready, txs_future) = self.pool_extrinsics();
+		let ethereum_txns = self.ethereum_transactions(txs_ready, txs_future)?;
+
+		// Build the T response.
+		let by_sender = |txns: Vec<TransactionV2>| {
+			let mut map = TransactionMap::<T>::new();
+			for txn in txns.iter() {
+				let from_address = sender(txn);
+				map.entry(from_address)
+					.or_default()
+					.insert(nonce(txn), T::get(txn.hash(), from_address, txn));
+			}
+			map
+		};
+		Ok(TxPoolResult {
+			pending: by_sender(ethereum_txns.ready),
+			queued: by_sender(ethereum_txns.future),
+		})
+	}
+
+	/// Extrinsics currently in the ready and future queues.
+	fn pool_extrinsics(&self) -> (Vec<B::Extrinsic>, Vec<B::Extrinsic>) {
 		// Collect transactions in the ready validated pool.
 		let txs_ready = self
 			.graph
@@ -65,73 +88,150 @@ where
 			.graph
 			.validated_pool()
 			.futures()
-			.iter()
-			.map(|(_hash, extrinsic)| extrinsic.clone())
+			.into_iter()
+			.map(|(_hash, extrinsic)| extrinsic)
+			.collect();
+
+		(txs_ready, txs_future)
+	}
+
+	/// Extrinsics of the ready and future queues providing a tag matched by `is_sender_tag`.
+	///
+	/// Each signed extrinsic provides a tag starting with its sender, so the pool indexes the
+	/// extrinsics of a sender without decoding nor sending them to the runtime.
+	fn sender_extrinsics(
+		&self,
+		is_sender_tag: impl Fn(&[u8]) -> bool,
+	) -> (Vec<B::Extrinsic>, Vec<B::Extrinsic>) {
+		let provided_by_sender = |tags: &[Vec<u8>]| tags.iter().any(|tag| is_sender_tag(tag));
+		let validated_pool = self.graph.validated_pool();
+
+		let txs_ready = validated_pool
+			.ready()
+			.filter(|in_pool_tx| provided_by_sender(in_pool_tx.provides()))
+			.map(|in_pool_tx| in_pool_tx.data().clone())
+			.collect();
+
+		// The future queue only exposes the tags of its extrinsics by hash.
+		let (hashes, txs_future): (Vec<_>, Vec<_>) = validated_pool.futures().into_iter().unzip();
+		let txs_future = validated_pool
+			.extrinsics_tags(&hashes)
+			.into_iter()
+			.zip(txs_future)
+			.filter(|(tags, _)| tags.as_deref().map_or(false, provided_by_sender))
+			.map(|(_, extrinsic)| extrinsic)
 			.collect();
 
-		// Use the runtime to match the (here) opaque extrinsics against ethereum transactions.
+		(txs_ready, txs_future)
+	}
+
+	/// Use the runtime to match the (here) opaque extrinsics against ethereum transactions.
+	fn ethereum_transactions(
+		&self,
+		txs_ready: Vec<B::Extrinsic>,
+		txs_future: Vec<B::Extrinsic>,
+	) -> RpcResult<TxPoolResponse> {
 		let best_block = self.client.info().best_hash;
 		let api = self.client.runtime_api();
-		let api_version =
-			if let Ok(Some(api_version)) = api.api_version::<dyn TxPoolRuntimeApi<B>>(best_block) {
-				api_version
-			} else {
-				return Err(internal_err("failed to retrieve Runtime Api version".to_string()))
-			};
-		let ethereum_txns: TxPoolResponse = if api_version == 1 {
+		let api_version = self.api_version(best_block)?;
+		if api_version == 1 {
 			#[allow(deprecated)]
 			let res = api.extrinsic_filter_before_version_2(best_block, txs_ready, txs_future)
 				.map_err(|err| {
 					internal_err(format!("fetch runtime extrinsic filter failed: {:?}", err))
 				})?;
-			TxPoolResponse {
+			Ok(TxPoolResponse {
 				ready: res.ready.iter().map(|t| TransactionV2::Legacy(t.clone())).collect(),
 				future: res.future.iter().map(|t| TransactionV2::Legacy(t.clone())).collect(),
-			}
+			})
 		} else {
 			api.extrinsic_filter(best_block, txs_ready, txs_future).map_err(|err| {
 				internal_err(format!("fetch runtime extrinsic filter failed: {:?}", err))
-			})?
-		};
-		// Build the T response.
-		let mut pending = TransactionMap::<T>::new();
-		for txn in ethereum_txns.ready.iter() {
-			let hash = txn.hash();
-			let nonce = match txn {
-				TransactionV2::Legacy(t) => t.nonce,
-				TransactionV2::EIP2930(t) => t.nonce,
-				TransactionV2::EIP1559(t) => t.nonce,
-			};
-			let from_address = match public_key(txn) {
-				Ok(pk) => H160::from(H256::from_slice(Keccak256::digest(&pk).as_slice())),
-				Err(_e) => H160::default(),
-			};
-			pending
-				.entry(from_address)
-				.or_default()
-				.insert(nonce, T::get(hash, from_address, txn));
+			})
 		}
-		let mut queued = TransactionMap::<T>::new();
-		for txn in ethereum_txns.future.iter() {
-			let hash = txn.hash();
-			let nonce = match txn {
-				TransactionV2::Legacy(t) => t.nonce,
-				TransactionV2::EIP2930(t) => t.nonce,
-				TransactionV2::EIP1559(t) => t.nonce,
-			};
-			let from_address = match public_key(txn) {
-				Ok(pk) => H160::from(H256::from_slice(Keccak256::digest(&pk).as_slice())),
-				Err(_e) => H160::default(),
-			};
-			queued
-				.entry(from_address)
-				.or_default()
-				.insert(nonce, T::get(hash, from_address, txn));
+	}
+
+	fn api_version(&self, at: B::Hash) -> RpcResult<u32> {
+		match self.client.runtime_api().api_version::<dyn TxPoolRuntimeApi<B>>(at) {
+			Ok(Some(api_version)) => Ok(api_version),
+			_ => Err(internal_err("failed to retrieve Runtime Api version".to_string())),
 		}
-		Ok(TxPoolResult { pending, queued })
+	}
+
+	/// Ethereum transactions of `from`, which provide a `(sender, nonce)` tag.
+	fn sender_build(&self, from: H160) -> RpcResult<TxPoolResult<HashMap<U256, Transaction>>> {
+		let (txs_ready, txs_future) = self.sender_extrinsics(|tag| is_sender_tag(tag, from));
+		let ethereum_txns = self.ethereum_transactions(txs_ready, txs_future)?;
+
+		let by_nonce = |txns: Vec<TransactionV2>| {
+			txns.iter()
+				.map(|txn| (nonce(txn), Transaction::get(txn.hash(), from, txn)))
+				.collect()
+		};
+		Ok(TxPoolResult {
+			pending: by_nonce(ethereum_txns.ready),
+			queued: by_nonce(ethereum_txns.future),
+		})
+	}
+
+	/// Native extrinsics signed by the account `from` is mapped to, which provide an
+	/// `(account, nonce)` tag starting with the prefix given by the runtime.
+	fn native_build(&self, from: H160) -> RpcResult<TxPoolResult<Vec<NativeTransaction>>> {
+		let best_block = self.client.info().best_hash;
+		if self.api_version(best_block)? < 3 {
+			return Err(internal_err(
+				"txpool_nativeContentFrom is not supported by the runtime".to_string(),
+			))
+		}
+
+		let tag_prefix =
+			self.client.runtime_api().native_tag_prefix(best_block, from).map_err(|err| {
+				internal_err(format!("fetch runtime native tag prefix failed: {:?}", err))
+			})?;
+		let (txs_ready, txs_future) =
+			self.sender_extrinsics(|tag| is_native_sender_tag(tag, &tag_prefix));
+
+		let native = |txs: Vec<B::Extrinsic>| {
+			txs.into_iter()
+				.map(|xt| {
+					let extrinsic = xt.encode();
+					NativeTransaction {
+						hash: H256::from(sp_io::hashing::blake2_256(&extrinsic)),
+						extrinsic: extrinsic.into(),
+					}
+				})
+				.collect()
+		};
+		Ok(TxPoolResult { pending: native(txs_ready), queued: native(txs_future) })
+	}
+}
+
+fn nonce(txn: &TransactionV2) -> U256 {
+	match txn {
+		TransactionV2::Legacy(t) => t.nonce,
+		TransactionV2::EIP2930(t) => t.nonce,
+		TransactionV2::EIP1559(t) => t.nonce,
+	}
+}
+
+/// Recovers the sender of `txn`, defaulting to the zero address.
+fn sender(txn: &TransactionV2) -> H160 {
+	match public_key(txn) {
+		Ok(pk) => H160::from(H256::from_slice(Keccak256::digest(&pk).as_slice())),
+		Err(_e) => H160::default(),
 	}
 }
 
+/// Whether `tag` is the `(sender, nonce)` tag provided by an Ethereum transaction of `from`.
+fn is_sender_tag(tag: &[u8], from: H160) -> bool {
+	tag.len() == (from, U256::zero()).encoded_size() && tag.starts_with(from.as_bytes())
+}
+
+/// Whether `tag` is an `(account, nonce)` tag starting with the encoded account `tag_prefix`.
+fn is_native_sender_tag(tag: &[u8], tag_prefix: &[u8]) -> bool {
+	tag.len() > tag_prefix.len() && tag.starts_with(tag_prefix)
+}
+
 impl<B: BlockT, C, A: ChainApi> TxPool<B, C, A> {
 	pub fn new(client: Arc<C>, graph: Arc<Pool<A>>) -> Self {
 		Self { client, graph, _marker: PhantomData }
@@ -151,6 +251,17 @@ where
 		self.map_build::<Transaction>()
 	}
 
+	fn content_from(&self, address: H160) -> RpcResult<TxPoolResult<HashMap<U256, Transaction>>> {
+		self.sender_build(address)
+	}
+
+	fn native_content_from(
+		&self,
+		address: H160,
+	) -> RpcResult<TxPoolResult<Vec<NativeTransaction>>> {
+		self.native_build(address)
+	}
+
 	fn inspect(&self) -> RpcResult<TxPoolResult<TransactionMap<Summary>>> {
 		self.map_build::<Summary>()
 	}
@@ -160,3 +271,27 @@ where
 		Ok(TxPoolResult { pending: U256::from(status.ready), queued: U256::from(status.future) })
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn matches_the_tags_of_the_sender() {
+		let from = H160::repeat_byte(1);
+
+		assert!(is_sender_tag(&(from, U256::from(7)).encode(), from));
+		assert!(!is_sender_tag(&(H160::repeat_byte(2), U256::from(7)).encode(), from));
+		// Tags provided by native extrinsics, such as `frame_system`'s `(account, nonce)`.
+		assert!(!is_sender_tag(&([1u8; 32], 7u32).encode(), from));
+	}
+
+	#[test]
+	fn matches_the_native_tags_of_the_account() {
+		let account = [1u8; 32];
+
+		assert!(is_native_sender_tag(&(account, 7u32).encode(), &account));
+		assert!(!is_native_sender_tag(&([2u8; 32], 7u32).encode(), &account));
+		assert!(!is_native_sender_tag(&account, &account));
+	}
+}
@@ -0,0 +1,40 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Calls the precompiles of the runtime with the fuzzer input, see
+//! `precompile_utils::testing::fuzz`.
+
+#![no_main]
+
+use frame_support::traits::Get;
+use libfuzzer_sys::fuzz_target;
+use peaq_dev_runtime::{BuildStorage, GenesisConfig, PrecompilesValue};
+use precompile_utils::testing::fuzz::{check_call, FuzzCall};
+use sp_core::H160;
+
+fn precompile_addresses() -> Vec<H160> {
+	let asset = |id: u8| {
+		let mut address = [0u8; 20];
+		address[..4].copy_from_slice(&[255u8; 4]);
+		address[19] = id;
+		H160(address)
+	};
+
+	(1..=9)
+		.chain(1024..=1026)
+		.chain(2048..=2053)
+		.map(H160::from_low_u64_be)
+		.chain([asset(0), asset(1)])
+		.collect()
+}
+
+fuzz_target!(|data: &[u8]| {
+	let callers = [H160::repeat_byte(0xaa), H160::repeat_byte(0xbb)];
+	let Some(call) = FuzzCall::from_bytes(data, &precompile_addresses(), &callers) else { return };
+
+	let storage = GenesisConfig::default().build_storage().expect("Default genesis builds");
+	sp_io::TestExternalities::new(storage).execute_with(|| {
+		if let Err(failure) = check_call(&PrecompilesValue::get(), call) {
+			panic!("{failure}");
+		}
+	});
+});
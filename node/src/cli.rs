@@ -25,6 +25,18 @@ pub struct RunCmd {
 	#[clap(long, default_value = "20000000")]
 	pub tracing_raw_max_memory_usage: usize,
 
+	/// Duration (in seconds) after which a debug tracing request not providing a `timeout`
+	/// fails with an "execution timeout" error. Such requests are only bounded by
+	/// `--tracing-max-timeout` by default.
+	#[clap(long)]
+	pub tracing_timeout: Option<u64>,
+
+	/// Maximum duration (in seconds) a debug tracing request is allowed to run, whatever its
+	/// `timeout`, counted from its receipt. The deadline is checked between runtime calls: a
+	/// transaction or call being traced can't be interrupted and always runs to its end.
+	#[clap(long, default_value = "300")]
+	pub tracing_max_timeout: u64,
+
 	/// Maximum number of trace entries a single request of `trace_filter` is allowed to return.
 	/// A request asking for more or an unbounded one going over this limit will both return an
 	/// error.
@@ -36,6 +48,11 @@ pub struct RunCmd {
 	#[clap(long, default_value = "300")]
 	pub ethapi_trace_cache_duration: u64,
 
+	/// Number of most recent blocks whose `trace_filter` traces are persisted on disk, next to
+	/// the frontier database. Traces are only kept in memory if not set.
+	#[clap(long)]
+	pub ethapi_trace_store_retention: Option<u32>,
+
 	/// Size of the LRU cache for block data and their transaction statuses.
 	#[clap(long, default_value = "3000")]
 	pub eth_log_block_cache: usize,
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{path::PathBuf, str::FromStr};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum EthApi {
@@ -25,10 +25,14 @@ pub struct RpcConfig {
 	pub ethapi_max_permits: u32,
 	pub ethapi_trace_max_count: u32,
 	pub ethapi_trace_cache_duration: u64,
+	pub ethapi_trace_store_retention: Option<u32>,
+	pub ethapi_trace_store_path: PathBuf,
 	pub eth_log_block_cache: usize,
 	pub eth_statuses_cache: usize,
 	pub fee_history_limit: u64,
 	pub max_past_logs: u32,
 	pub relay_chain_rpc_urls: Vec<url::Url>,
 	pub tracing_raw_max_memory_usage: usize,
+	pub tracing_timeout: Option<u64>,
+	pub tracing_max_timeout: u64,
 }
@@ -447,12 +447,16 @@ pub fn run() -> sc_cli::Result<()> {
 					ethapi_max_permits: cli.run.ethapi_max_permits,
 					ethapi_trace_max_count: cli.run.ethapi_trace_max_count,
 					ethapi_trace_cache_duration: cli.run.ethapi_trace_cache_duration,
+					ethapi_trace_store_retention: cli.run.ethapi_trace_store_retention,
+					ethapi_trace_store_path: frontier_database_dir(&config, "traces"),
 					eth_log_block_cache: cli.run.eth_log_block_cache,
 					eth_statuses_cache: cli.run.eth_statuses_cache,
 					fee_history_limit: cli.run.fee_history_limit,
 					max_past_logs: cli.run.max_past_logs,
 					relay_chain_rpc_urls: cli.run.base.relay_chain_rpc_urls,
 					tracing_raw_max_memory_usage: cli.run.tracing_raw_max_memory_usage,
+					tracing_timeout: cli.run.tracing_timeout,
+					tracing_max_timeout: cli.run.tracing_max_timeout,
 				};
 
 				let polkadot_cli = RelayChainCli::new(
@@ -195,7 +195,7 @@ where
 		io.merge(
 			EthFilter::new(
 				client.clone(),
-				frontier_backend,
+				Arc::clone(&frontier_backend),
 				fc_rpc::TxPool::new(client.clone(), graph.clone()),
 				filter_pool,
 				500_usize, // max stored filters
@@ -244,8 +244,13 @@ where
 	if let Some(tracing_config) = maybe_tracing_config {
 		if let Some(trace_filter_requester) = tracing_config.tracing_requesters.trace {
 			io.merge(
-				Trace::new(client, trace_filter_requester, tracing_config.trace_filter_max_count)
-					.into_rpc(),
+				Trace::new(
+					client,
+					frontier_backend,
+					trace_filter_requester,
+					tracing_config.trace_filter_max_count,
+				)
+				.into_rpc(),
 			)?;
 		}
 
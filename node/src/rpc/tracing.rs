@@ -17,7 +17,9 @@
 use super::*;
 
 use peaq_rpc_debug::{DebugHandler, DebugRequester};
-use peaq_rpc_trace::{CacheRequester as TraceFilterCacheRequester, CacheTask};
+use peaq_rpc_trace::{
+	fill_trace_store, CacheRequester as TraceFilterCacheRequester, CacheTask, TraceStore,
+};
 use tokio::sync::Semaphore;
 
 use crate::cli_opt::EthApi as EthApiCmd;
@@ -54,6 +56,18 @@ where
 {
 	let permit_pool = Arc::new(Semaphore::new(rpc_config.ethapi_max_permits as usize));
 
+	let trace_store = match rpc_config.ethapi_trace_store_retention {
+		Some(retention) if rpc_config.ethapi.contains(&EthApiCmd::Trace) =>
+			match TraceStore::open(&rpc_config.ethapi_trace_store_path, retention) {
+				Ok(store) => Some(Arc::new(store)),
+				Err(e) => {
+					log::warn!("Traces will not be persisted: {}", e);
+					None
+				},
+			},
+		_ => None,
+	};
+
 	let (trace_filter_task, trace_filter_requester) =
 		if rpc_config.ethapi.contains(&EthApiCmd::Trace) {
 			let (trace_filter_task, trace_filter_requester) = CacheTask::create(
@@ -62,6 +76,7 @@ where
 				Duration::from_secs(rpc_config.ethapi_trace_cache_duration),
 				Arc::clone(&permit_pool),
 				Arc::clone(&params.overrides),
+				trace_store.clone(),
 			);
 			(Some(trace_filter_task), Some(trace_filter_requester))
 		} else {
@@ -78,6 +93,8 @@ where
 			Arc::clone(&permit_pool),
 			Arc::clone(&params.overrides),
 			rpc_config.tracing_raw_max_memory_usage,
+			rpc_config.tracing_timeout.map(Duration::from_secs),
+			Duration::from_secs(rpc_config.tracing_max_timeout),
 		);
 		(Some(debug_task), Some(debug_requester))
 	} else {
@@ -94,6 +111,20 @@ where
 		);
 	}
 
+	// Trace store filling task if enabled. Not essential, traces are then computed on demand.
+	if let Some(trace_store) = trace_store {
+		params.task_manager.spawn_handle().spawn(
+			"trace-store",
+			Some("eth-tracing"),
+			fill_trace_store(
+				trace_store,
+				Arc::clone(&params.client),
+				Arc::clone(&params.substrate_backend),
+				Arc::clone(&params.overrides),
+			),
+		);
+	}
+
 	// `debug` task if enabled. Essential.
 	// Proxies rpc requests to it's handler.
 	if let Some(debug_task) = debug_task {
@@ -0,0 +1,23 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Runtime API listing the keys indexed by the key index pallet, which the DID and storage
+//! runtime APIs can only read one by one.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// These clippy lints are disabled because the macro-generated code triggers them.
+#![allow(clippy::unnecessary_mut_passed)]
+
+use parity_scale_codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait KeyIndexApi<AccountId> where AccountId: Codec {
+		/// Names of `limit` attributes of `did_account` from the `offset`-th one, which can be
+		/// read with `PeaqDIDApi::read`.
+		fn did_attribute_names(did_account: AccountId, offset: u32, limit: u32) -> Vec<Vec<u8>>;
+
+		/// Types of `limit` items of `account` from the `offset`-th one, which can be read with
+		/// `PeaqStorageApi::read`.
+		fn storage_item_types(account: AccountId, offset: u32, limit: u32) -> Vec<Vec<u8>>;
+	}
+}
@@ -0,0 +1,139 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! # Key Index Pallet
+//!
+//! ## Overview
+//!
+//! The DID pallet stores the attributes of a DID under a hash of the DID and the attribute name,
+//! and the storage pallet stores the items of an account under a hash of the account and the item
+//! type, so neither can be listed from their storage. This pallet keeps the attribute names of
+//! each DID and the item types of each account, so they can be listed page by page.
+//!
+//! The index follows the events of the runtime: at the end of each block, the events deposited
+//! during the block are mapped to key changes by [`Config::KeyChanges`] and applied to the index.
+//! Keys written before the pallet was added to the runtime are not indexed.
+//!
+//! ## Interface
+//!
+//! The pallet has no dispatchable functions. [`Pallet::keys`] iterates over the keys of an
+//! account and [`Pallet::page`] returns a page of them.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::pallet;
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[pallet]
+pub mod pallet {
+	use crate::weights::WeightInfo;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_std::vec::Vec;
+
+	/// Kind of the indexed keys, each kind being a separate index.
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	pub enum KeyKind {
+		/// Names of the attributes of a DID.
+		DidAttribute,
+		/// Types of the items of an account in the storage pallet.
+		StorageItem,
+	}
+
+	/// Change of an indexed key carried by an event.
+	#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+	pub enum KeyChange<AccountId> {
+		Inserted { kind: KeyKind, account: AccountId, key: Vec<u8> },
+		Removed { kind: KeyKind, account: AccountId, key: Vec<u8> },
+	}
+
+	/// Maps the events of the runtime to the changes of the indexed keys.
+	pub trait KeyChanges<Event, AccountId> {
+		/// The key changed by `event`, if any.
+		fn key_change(event: &Event) -> Option<KeyChange<AccountId>>;
+	}
+
+	#[impl_trait_for_tuples::impl_for_tuples(8)]
+	impl<Event, AccountId> KeyChanges<Event, AccountId> for Tuple {
+		fn key_change(event: &Event) -> Option<KeyChange<AccountId>> {
+			for_tuples!( #(
+				if let Some(change) = Tuple::key_change(event) {
+					return Some(change)
+				}
+			)* );
+			None
+		}
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Key changes carried by the events of the runtime.
+		type KeyChanges: KeyChanges<<Self as frame_system::Config>::RuntimeEvent, Self::AccountId>;
+
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Indexed keys of each kind and account.
+	#[pallet::storage]
+	#[pallet::unbounded]
+	pub type Keys<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(KeyKind, T::AccountId),
+		Blake2_128Concat,
+		Vec<u8>,
+		(),
+	>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_finalize(_n: BlockNumberFor<T>) {
+			// Events are cleared when the block is initialized, so reading them only reads what
+			// was deposited during the block and adds nothing to the proof.
+			let (mut events, mut changes) = (0u32, 0u32);
+			for record in frame_system::Pallet::<T>::read_events_no_consensus() {
+				events = events.saturating_add(1);
+				if let Some(change) = T::KeyChanges::key_change(&record.event) {
+					changes = changes.saturating_add(1);
+					Self::apply(change);
+				}
+			}
+
+			frame_system::Pallet::<T>::register_extra_weight_unchecked(
+				T::WeightInfo::on_finalize(events, changes),
+				DispatchClass::Mandatory,
+			);
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Indexed keys of `account`, in the order of their storage hashes.
+		pub fn keys(kind: KeyKind, account: T::AccountId) -> impl Iterator<Item = Vec<u8>> {
+			Keys::<T>::iter_key_prefix((kind, account))
+		}
+
+		/// `limit` indexed keys of `account` from the `offset`-th one.
+		pub fn page(kind: KeyKind, account: T::AccountId, offset: u32, limit: u32) -> Vec<Vec<u8>> {
+			Self::keys(kind, account).skip(offset as usize).take(limit as usize).collect()
+		}
+
+		fn apply(change: KeyChange<T::AccountId>) {
+			match change {
+				KeyChange::Inserted { kind, account, key } =>
+					Keys::<T>::insert((kind, account), key, ()),
+				KeyChange::Removed { kind, account, key } =>
+					Keys::<T>::remove((kind, account), key),
+			}
+		}
+	}
+}
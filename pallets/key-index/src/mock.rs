@@ -0,0 +1,125 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Mocks for the key index pallet.
+
+use crate::{self as key_index, KeyChange, KeyChanges, KeyKind};
+use frame_support::{
+	construct_runtime, parameter_types,
+	traits::{ConstU32, Everything},
+};
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, IdentityLookup};
+
+pub type AccountId = u64;
+pub type BlockNumber = u64;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+pub const ALICE: AccountId = 1;
+pub const BOB: AccountId = 2;
+
+/// Pallet depositing the events of key changes, as the DID pallet does.
+#[frame_support::pallet]
+pub mod attributes {
+	use frame_support::pallet_prelude::*;
+	use sp_std::vec::Vec;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub fn deposit_event)]
+	pub enum Event<T: Config> {
+		Added { account: T::AccountId, name: Vec<u8> },
+		Removed { account: T::AccountId, name: Vec<u8> },
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Test {
+	type BaseCallFilter = Everything;
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type RuntimeOrigin = RuntimeOrigin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type RuntimeCall = RuntimeCall;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = sp_runtime::generic::Header<BlockNumber, BlakeTwo256>;
+	type RuntimeEvent = RuntimeEvent;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = ConstU32<16>;
+}
+
+impl attributes::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+}
+
+pub struct AttributeChanges;
+
+impl KeyChanges<RuntimeEvent, AccountId> for AttributeChanges {
+	fn key_change(event: &RuntimeEvent) -> Option<KeyChange<AccountId>> {
+		match event {
+			RuntimeEvent::Attributes(attributes::Event::Added { account, name }) =>
+				Some(KeyChange::Inserted {
+					kind: KeyKind::DidAttribute,
+					account: *account,
+					key: name.clone(),
+				}),
+			RuntimeEvent::Attributes(attributes::Event::Removed { account, name }) =>
+				Some(KeyChange::Removed {
+					kind: KeyKind::DidAttribute,
+					account: *account,
+					key: name.clone(),
+				}),
+			_ => None,
+		}
+	}
+}
+
+impl key_index::Config for Test {
+	type KeyChanges = AttributeChanges;
+	type WeightInfo = ();
+}
+
+construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system,
+		Attributes: attributes,
+		KeyIndex: key_index,
+	}
+);
+
+pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.expect("Frame system builds valid default genesis config");
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,102 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Unit tests for the key index pallet.
+
+use crate::{mock::*, *};
+use frame_support::traits::Hooks;
+use sp_std::collections::btree_set::BTreeSet;
+
+fn added(account: AccountId, name: &[u8]) {
+	Attributes::deposit_event(attributes::Event::Added { account, name: name.to_vec() });
+}
+
+fn removed(account: AccountId, name: &[u8]) {
+	Attributes::deposit_event(attributes::Event::Removed { account, name: name.to_vec() });
+}
+
+fn finalize_and_start_next_block() {
+	let now = System::block_number();
+	KeyIndex::on_finalize(now);
+	System::reset_events();
+	System::set_block_number(now + 1);
+}
+
+fn keys(account: AccountId) -> BTreeSet<Vec<u8>> {
+	KeyIndex::keys(KeyKind::DidAttribute, account).collect()
+}
+
+#[test]
+fn indexes_the_keys_of_the_events() {
+	new_test_ext().execute_with(|| {
+		added(ALICE, b"name");
+		added(ALICE, b"email");
+		added(BOB, b"name");
+		finalize_and_start_next_block();
+
+		assert_eq!(keys(ALICE), BTreeSet::from([b"name".to_vec(), b"email".to_vec()]));
+		assert_eq!(keys(BOB), BTreeSet::from([b"name".to_vec()]));
+
+		removed(ALICE, b"name");
+		finalize_and_start_next_block();
+
+		assert_eq!(keys(ALICE), BTreeSet::from([b"email".to_vec()]));
+		assert_eq!(keys(BOB), BTreeSet::from([b"name".to_vec()]));
+	});
+}
+
+#[test]
+fn keys_are_indexed_once() {
+	new_test_ext().execute_with(|| {
+		added(ALICE, b"name");
+		added(ALICE, b"name");
+		finalize_and_start_next_block();
+		added(ALICE, b"name");
+		finalize_and_start_next_block();
+
+		assert_eq!(KeyIndex::page(KeyKind::DidAttribute, ALICE, 0, 10), vec![b"name".to_vec()]);
+	});
+}
+
+#[test]
+fn later_events_of_a_block_win() {
+	new_test_ext().execute_with(|| {
+		added(ALICE, b"name");
+		removed(ALICE, b"name");
+		added(ALICE, b"email");
+		finalize_and_start_next_block();
+		removed(ALICE, b"email");
+		added(ALICE, b"email");
+		finalize_and_start_next_block();
+
+		assert_eq!(keys(ALICE), BTreeSet::from([b"email".to_vec()]));
+	});
+}
+
+#[test]
+fn kinds_are_indexed_separately() {
+	new_test_ext().execute_with(|| {
+		Keys::<Test>::insert((KeyKind::StorageItem, ALICE), b"item".to_vec(), ());
+		added(ALICE, b"name");
+		finalize_and_start_next_block();
+
+		assert_eq!(keys(ALICE), BTreeSet::from([b"name".to_vec()]));
+		assert_eq!(KeyIndex::page(KeyKind::StorageItem, ALICE, 0, 10), vec![b"item".to_vec()]);
+	});
+}
+
+#[test]
+fn pages_cover_all_keys() {
+	new_test_ext().execute_with(|| {
+		let names: Vec<Vec<u8>> = (0u8..10).map(|i| vec![i]).collect();
+		names.iter().for_each(|name| added(ALICE, name));
+		finalize_and_start_next_block();
+
+		let pages: Vec<Vec<u8>> = (0..4)
+			.flat_map(|page| KeyIndex::page(KeyKind::DidAttribute, ALICE, page * 3, 3))
+			.collect();
+
+		assert_eq!(pages.len(), names.len());
+		assert_eq!(pages.into_iter().collect::<BTreeSet<_>>(), names.into_iter().collect());
+		assert!(KeyIndex::page(KeyKind::DidAttribute, ALICE, 10, 3).is_empty());
+	});
+}
@@ -0,0 +1,42 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Weights for key_index
+//!
+//! Hand-written estimates of decoding the events of a block and writing one key per change.
+//! They are not benchmark output.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for key_index.
+pub trait WeightInfo {
+	fn on_finalize(e: u32, c: u32) -> Weight;
+}
+
+/// Hand-written weights for key_index.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: System Events (r:1 w:0)
+	// Storage: KeyIndex Keys (r:0 w:1)
+	// Proof Skipped: KeyIndex Keys (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `e` is `[0, 10000]`.
+	/// The range of component `c` is `[0, 10000]`.
+	fn on_finalize(e: u32, c: u32, ) -> Weight {
+		Weight::from_parts(3_000_000, 0)
+			.saturating_add(Weight::from_parts(2_000_000, 0).saturating_mul(e.into()))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(c.into())))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn on_finalize(e: u32, c: u32, ) -> Weight {
+		Weight::from_parts(3_000_000, 0)
+			.saturating_add(Weight::from_parts(2_000_000, 0).saturating_mul(e.into()))
+			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(c.into())))
+	}
+}
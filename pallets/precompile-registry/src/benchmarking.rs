@@ -0,0 +1,30 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Benchmarking
+use super::*;
+use frame_benchmarking::v1::{benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::EnsureOrigin;
+use sp_core::H160;
+
+benchmarks! {
+	disable_precompile {
+		let origin = T::ControlOrigin::try_successful_origin()
+			.map_err(|_| frame_benchmarking::BenchmarkError::Weightless)?;
+		let address = H160::from_low_u64_be(2048);
+	}: _<T::RuntimeOrigin>(origin, address)
+	verify {
+		assert!(DisabledPrecompiles::<T>::contains_key(address));
+	}
+
+	enable_precompile {
+		let origin = T::ControlOrigin::try_successful_origin()
+			.map_err(|_| frame_benchmarking::BenchmarkError::Weightless)?;
+		let address = H160::from_low_u64_be(2048);
+		DisabledPrecompiles::<T>::insert(address, ());
+	}: _<T::RuntimeOrigin>(origin, address)
+	verify {
+		assert!(!DisabledPrecompiles::<T>::contains_key(address));
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
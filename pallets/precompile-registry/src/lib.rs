@@ -0,0 +1,116 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! # Precompile Registry Pallet
+//!
+//! ## Overview
+//!
+//! The precompiles of a runtime are fixed at compile time. This pallet keeps the set of
+//! precompiles disabled by governance, so a faulty precompile can be switched off without a
+//! runtime upgrade. Precompiles wrapped in
+//! `precompile_utils::precompile_set::DisableablePrecompiles<Pallet<T>, _>` revert while they
+//! are in the set.
+//!
+//! ## Interface
+//!
+//! ### Dispatchable Functions
+//!
+//! - `disable_precompile` - disables the precompile at the given address
+//! - `enable_precompile` - enables again a disabled precompile
+//!
+//! ### Other
+//!
+//! `Contains<H160>` is implemented by the pallet to check if a precompile is disabled.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::pallet;
+pub use pallet::*;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[pallet]
+pub mod pallet {
+	use crate::weights::WeightInfo;
+	use frame_support::{pallet_prelude::*, traits::Contains};
+	use frame_system::pallet_prelude::*;
+	use sp_core::H160;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The origin allowed to disable and enable precompiles.
+		///
+		/// Should most likely be root or the council.
+		type ControlOrigin: EnsureOrigin<<Self as frame_system::Config>::RuntimeOrigin>;
+
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The precompile is already disabled.
+		AlreadyDisabled,
+		/// The precompile is not disabled.
+		NotDisabled,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The precompile at this address now reverts.
+		PrecompileDisabled { address: H160 },
+		/// The precompile at this address is enabled again.
+		PrecompileEnabled { address: H160 },
+	}
+
+	/// Addresses of the disabled precompiles.
+	#[pallet::storage]
+	pub type DisabledPrecompiles<T: Config> = StorageMap<_, Blake2_128Concat, H160, ()>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Disable the precompile at `address`, calls to it revert until it is enabled again.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::disable_precompile())]
+		pub fn disable_precompile(origin: OriginFor<T>, address: H160) -> DispatchResult {
+			T::ControlOrigin::ensure_origin(origin)?;
+
+			ensure!(!DisabledPrecompiles::<T>::contains_key(address), Error::<T>::AlreadyDisabled);
+			DisabledPrecompiles::<T>::insert(address, ());
+
+			Self::deposit_event(Event::PrecompileDisabled { address });
+			Ok(())
+		}
+
+		/// Enable the precompile at `address` which was disabled.
+		#[pallet::call_index(1)]
+		#[pallet::weight(T::WeightInfo::enable_precompile())]
+		pub fn enable_precompile(origin: OriginFor<T>, address: H160) -> DispatchResult {
+			T::ControlOrigin::ensure_origin(origin)?;
+
+			ensure!(DisabledPrecompiles::<T>::contains_key(address), Error::<T>::NotDisabled);
+			DisabledPrecompiles::<T>::remove(address);
+
+			Self::deposit_event(Event::PrecompileEnabled { address });
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Contains<H160> for Pallet<T> {
+		fn contains(address: &H160) -> bool {
+			DisabledPrecompiles::<T>::contains_key(address)
+		}
+	}
+}
@@ -0,0 +1,72 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Unit tests for the precompile registry pallet.
+
+use crate::{mock::*, *};
+use frame_support::{assert_noop, assert_ok, traits::Contains};
+use sp_core::H160;
+use sp_runtime::DispatchError;
+
+fn precompile() -> H160 {
+	H160::from_low_u64_be(2048)
+}
+
+#[test]
+fn disable_precompile_works() {
+	new_test_ext().execute_with(|| {
+		assert!(!PrecompileRegistry::contains(&precompile()));
+
+		assert_ok!(PrecompileRegistry::disable_precompile(RuntimeOrigin::root(), precompile()));
+
+		assert!(PrecompileRegistry::contains(&precompile()));
+		assert!(!PrecompileRegistry::contains(&H160::from_low_u64_be(2049)));
+		System::assert_last_event(RuntimeEvent::PrecompileRegistry(Event::PrecompileDisabled {
+			address: precompile(),
+		}));
+	});
+}
+
+#[test]
+fn enable_precompile_works() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(PrecompileRegistry::disable_precompile(RuntimeOrigin::root(), precompile()));
+		assert_ok!(PrecompileRegistry::enable_precompile(RuntimeOrigin::root(), precompile()));
+
+		assert!(!PrecompileRegistry::contains(&precompile()));
+		System::assert_last_event(RuntimeEvent::PrecompileRegistry(Event::PrecompileEnabled {
+			address: precompile(),
+		}));
+	});
+}
+
+#[test]
+fn only_control_origin_can_switch_precompiles() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PrecompileRegistry::disable_precompile(RuntimeOrigin::signed(ALICE), precompile()),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(PrecompileRegistry::disable_precompile(RuntimeOrigin::root(), precompile()));
+		assert_noop!(
+			PrecompileRegistry::enable_precompile(RuntimeOrigin::signed(ALICE), precompile()),
+			DispatchError::BadOrigin
+		);
+	});
+}
+
+#[test]
+fn cannot_disable_twice_nor_enable_an_enabled_precompile() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PrecompileRegistry::enable_precompile(RuntimeOrigin::root(), precompile()),
+			Error::<Test>::NotDisabled
+		);
+
+		assert_ok!(PrecompileRegistry::disable_precompile(RuntimeOrigin::root(), precompile()));
+		assert_noop!(
+			PrecompileRegistry::disable_precompile(RuntimeOrigin::root(), precompile()),
+			Error::<Test>::AlreadyDisabled
+		);
+	});
+}
@@ -0,0 +1,57 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Weights for precompile_registry
+//!
+//! Hand-written estimates of one storage read and write. They are not benchmark output and
+//! should be regenerated from `benchmarking.rs` with `benchmark pallet` once reference hardware
+//! results are available.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for precompile_registry.
+pub trait WeightInfo {
+	fn disable_precompile() -> Weight;
+	fn enable_precompile() -> Weight;
+}
+
+/// Hand-written weights for precompile_registry.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	// Storage: PrecompileRegistry DisabledPrecompiles (r:1 w:1)
+	// Proof Skipped: PrecompileRegistry DisabledPrecompiles (max_values: None, max_size: None, mode: Measured)
+	fn disable_precompile() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 2_511))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	// Storage: PrecompileRegistry DisabledPrecompiles (r:1 w:1)
+	// Proof Skipped: PrecompileRegistry DisabledPrecompiles (max_values: None, max_size: None, mode: Measured)
+	fn enable_precompile() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 2_511))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn disable_precompile() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 2_511))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn enable_precompile() -> Weight {
+		Weight::from_parts(12_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 2_511))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
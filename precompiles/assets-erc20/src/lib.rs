@@ -39,7 +39,7 @@
 
 use fp_evm::{ExitError, PrecompileHandle};
 use frame_support::{
-	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	dispatch::{Dispatchable, GetCallMetadata, GetDispatchInfo, PostDispatchInfo},
 	sp_runtime::traits::StaticLookup,
 	traits::{
 		fungibles::{
@@ -50,27 +50,11 @@ use frame_support::{
 };
 use pallet_evm::AddressMapping;
 use precompile_utils::{
-	evm::logs::LogsBuilder,
 	prelude::{
-		Address, DiscriminantResult, InjectBacktrace, LogExt, MayRevert, PrecompileHandleExt,
-		RevertReason, RuntimeHelper, UnboundedBytes, SYSTEM_ACCOUNT_SIZE,
+		precompile, Address, DiscriminantResult, InjectBacktrace, LogExt, MayRevert,
+		PrecompileHandleExt, RevertReason, RuntimeHelper, UnboundedString, SYSTEM_ACCOUNT_SIZE,
 	},
-	solidity,
-};
-use precompile_utils::{
-	keccak256,
-	// succeed,
-	// Address,
-	//Bytes,
-	// EvmData,
-	// EvmDataWriter,
-	EvmResult,
-	// DiscriminantResult,
-	//FunctionModifier,
-	// LogExt,
-	// LogsBuilder,
-	// PrecompileHandleExt,
-	// RuntimeHelper,
+	solidity, EvmResult,
 };
 use sp_runtime::traits::Bounded;
 
@@ -86,10 +70,24 @@ mod mock;
 mod tests;
 
 /// Solidity selector of the Transfer log, which is the Keccak of the Log signature.
-pub const SELECTOR_LOG_TRANSFER: [u8; 32] = keccak256!("Transfer(address,address,uint256)");
+pub const SELECTOR_LOG_TRANSFER: [u8; 32] = Transfer::TOPIC;
 
 /// Solidity selector of the Approval log, which is the Keccak of the Log signature.
-pub const SELECTOR_LOG_APPROVAL: [u8; 32] = keccak256!("Approval(address,address,uint256)");
+pub const SELECTOR_LOG_APPROVAL: [u8; 32] = Approval::TOPIC;
+
+#[precompile::event("Transfer(address indexed,address indexed,uint256)")]
+pub struct Transfer {
+	pub from: Address,
+	pub to: Address,
+	pub value: U256,
+}
+
+#[precompile::event("Approval(address indexed,address indexed,uint256)")]
+pub struct Approval {
+	pub owner: Address,
+	pub spender: Address,
+	pub value: U256,
+}
 
 /// Alias for the Balance type for the provided Runtime and Instance.
 pub type BalanceOf<Runtime, Instance = ()> = <Runtime as pallet_assets::Config<Instance>>::Balance;
@@ -139,11 +137,13 @@ impl<Runtime, Instance> Default for Erc20AssetsPrecompileSet<Runtime, Instance>
 
 #[precompile_utils::precompile]
 #[precompile::precompile_set]
+#[precompile::events(Transfer, Approval)]
 impl<Runtime, Instance> Erc20AssetsPrecompileSet<Runtime, Instance>
 where
 	Instance: 'static,
 	Runtime: pallet_assets::Config<Instance> + pallet_evm::Config + frame_system::Config,
-	Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	Runtime::RuntimeCall:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
 	Runtime::RuntimeCall: From<pallet_assets::Call<Runtime, Instance>>,
 	<Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<Runtime::AccountId>>,
 	BalanceOf<Runtime, Instance>: TryFrom<U256> + Into<U256> + solidity::Codec,
@@ -282,14 +282,13 @@ where
 			)?;
 		}
 
-		LogsBuilder::new(handle.context().address)
-			.log3(
-				SELECTOR_LOG_APPROVAL,
-				handle.context().caller,
-				spender,
-				solidity::encode_event_data(amount),
-			)
-			.record(handle)?;
+		Approval {
+			owner: Address::from(handle.context().caller),
+			spender: Address::from(spender),
+			value: amount,
+		}
+		.into_log(handle.context().address)
+		.record(handle)?;
 
 		Ok(true)
 	}
@@ -324,14 +323,13 @@ where
 			)?;
 		}
 
-		LogsBuilder::new(handle.context().address)
-			.log3(
-				SELECTOR_LOG_TRANSFER,
-				handle.context().caller,
-				to,
-				solidity::encode_event_data(amount),
-			)
-			.record(handle)?;
+		Transfer {
+			from: Address::from(handle.context().caller),
+			to: Address::from(to),
+			value: amount.into(),
+		}
+		.into_log(handle.context().address)
+		.record(handle)?;
 
 		Ok(true)
 	}
@@ -385,8 +383,8 @@ where
 			}
 		}
 
-		LogsBuilder::new(handle.context().address)
-			.log3(SELECTOR_LOG_TRANSFER, from, to, solidity::encode_event_data(amount))
+		Transfer { from: Address::from(from), to: Address::from(to), value: amount.into() }
+			.into_log(handle.context().address)
 			.record(handle)?;
 
 		// Build output.
@@ -398,7 +396,7 @@ where
 	fn name(
 		asset_id: AssetIdOf<Runtime, Instance>,
 		handle: &mut impl PrecompileHandle,
-	) -> EvmResult<UnboundedBytes> {
+	) -> EvmResult<UnboundedString> {
 		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
 
 		let name = pallet_assets::Pallet::<Runtime, Instance>::name(asset_id).as_slice().into();
@@ -411,7 +409,7 @@ where
 	fn symbol(
 		asset_id: AssetIdOf<Runtime, Instance>,
 		handle: &mut impl PrecompileHandle,
-	) -> EvmResult<UnboundedBytes> {
+	) -> EvmResult<UnboundedString> {
 		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
 
 		let symbol = pallet_assets::Pallet::<Runtime, Instance>::symbol(asset_id).as_slice().into();
@@ -468,8 +466,8 @@ where
 			SYSTEM_ACCOUNT_SIZE,
 		)?;
 
-		LogsBuilder::new(handle.context().address)
-			.log3(SELECTOR_LOG_TRANSFER, H160::default(), addr, solidity::encode_event_data(amount))
+		Transfer { from: Address::default(), to: Address::from(addr), value: amount.into() }
+			.into_log(handle.context().address)
 			.record(handle)?;
 
 		Ok(true)
@@ -502,8 +500,8 @@ where
 			0,
 		)?;
 
-		LogsBuilder::new(handle.context().address)
-			.log3(SELECTOR_LOG_TRANSFER, addr, H160::default(), solidity::encode_event_data(amount))
+		Transfer { from: Address::from(addr), to: Address::default(), value: amount.into() }
+			.into_log(handle.context().address)
 			.record(handle)?;
 
 		Ok(true)
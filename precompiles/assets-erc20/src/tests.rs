@@ -955,3 +955,14 @@ fn burn_non_admin_is_not_ok() {
 			.execute_reverts(|output| from_utf8(&output).unwrap().contains("NoPermission"));
 	});
 }
+
+#[test]
+fn events_match_their_signatures() {
+	assert_eq!(Transfer::solidity_event().signature(), Transfer::SIGNATURE);
+	assert_eq!(Approval::solidity_event().signature(), Approval::SIGNATURE);
+}
+
+#[test]
+fn test_solidity_interface_matches_precompile() {
+	check_solidity_interface_matches("ERC20.sol", &PCall::solidity_interface())
+}
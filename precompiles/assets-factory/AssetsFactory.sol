@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pragma solidity >=0.8.3;
+
+address constant PRECOMPILE_ADDR = address(0x0000000000000000000000000000000000000802);
+
+AssetsFactory constant ASSETS_FACTORY_CONTRACT = AssetsFactory(PRECOMPILE_ADDR);
+
+interface AssetsFactory {
+
+    /// @dev Returns the ERC20 precompile address of the asset with the given id.
+    /// @custom:selector d7ad6236
+    function convertAssetIdToAddress(
+        uint32 id
+    ) external view returns (address);
+
+    /// @dev Creates a new asset owned by the caller and returns its ERC20 precompile address.
+    /// @custom:selector 8af44993
+    function create(
+        uint32 id,
+        address admin,
+        uint256 min_balance
+    ) external returns (address);
+
+    /// @dev Sets the metadata of an asset owned by the caller.
+    /// @custom:selector dc331b70
+    function setMetadata(
+        uint32 id,
+        string memory name,
+        string memory symbol,
+        uint8 decimals
+    ) external returns (bool);
+
+    event AssetCreated(
+        address indexed creator,
+        address indexed asset,
+        uint32 id,
+        address admin,
+        uint256 min_balance
+    );
+    event MetadataSet(
+        address indexed asset,
+        string name,
+        string symbol,
+        uint8 decimals
+    );
+}
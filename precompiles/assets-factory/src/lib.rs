@@ -0,0 +1,185 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use fp_evm::PrecompileHandle;
+use frame_support::{
+	dispatch::{Dispatchable, GetCallMetadata, GetDispatchInfo, PostDispatchInfo},
+	sp_runtime::traits::StaticLookup,
+	traits::ConstU32,
+};
+use pallet_evm::AddressMapping;
+use pallet_evm_precompile_assets_erc20::EVMAddressToAssetId;
+use peaq_primitives_xcm::AssetId;
+use precompile_utils::prelude::*;
+use sp_core::{H160, U256};
+use sp_std::{convert::TryFrom, marker::PhantomData};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Solidity selector of the AssetCreated log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_ASSET_CREATED: [u8; 32] = AssetCreated::TOPIC;
+
+/// Solidity selector of the MetadataSet log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_METADATA_SET: [u8; 32] = MetadataSet::TOPIC;
+
+/// Alias for the Balance type for the provided Runtime.
+pub type BalanceOf<Runtime> = <Runtime as pallet_assets::Config>::Balance;
+
+type AccountIdOf<Runtime> = <Runtime as frame_system::Config>::AccountId;
+
+type GetStringLimit = ConstU32<{ 2u32.pow(8) }>;
+
+#[precompile::event("AssetCreated(address indexed,address indexed,uint32,address,uint256)")]
+pub struct AssetCreated {
+	pub creator: Address,
+	pub asset: Address,
+	pub id: u32,
+	pub admin: Address,
+	pub min_balance: U256,
+}
+
+#[precompile::event("MetadataSet(address indexed,string,string,uint8)")]
+pub struct MetadataSet {
+	pub asset: Address,
+	pub name: BoundedString<GetStringLimit>,
+	pub symbol: BoundedString<GetStringLimit>,
+	pub decimals: u8,
+}
+
+/// Just a rough estimation
+/// Asset size in bytes = Pallet_Name_Hash (16) + Storage_name_hash (16) +
+/// Blake2_128Concat (16) + AssetId (9) + AssetDetails (4 * AccountId (32) + 3 * Balance (16) +
+/// 3 * u32 (4) + AssetStatus (1)) + EVM revert code of the asset precompile (16 + 16 + 20 + 5)
+const ASSET_DETAILS_SIZE: u64 = 307;
+
+/// Just a rough estimation
+/// Metadata size in bytes = Pallet_Name_Hash (16) + Storage_name_hash (16) +
+/// Blake2_128Concat (16) + AssetId (9) + Deposit (16) + Decimals (1) + IsFrozen (1)
+/// but without name and symbol length
+const ASSET_METADATA_KEY_SIZE: u64 = 75;
+
+/// Precompile allowing EVM accounts to create `pallet_assets` assets and to set their metadata.
+/// Created assets are reachable through `Erc20AssetsPrecompileSet` at the address returned by
+/// `create`.
+pub struct AssetsFactoryPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+#[precompile::events(AssetCreated, MetadataSet)]
+impl<Runtime> AssetsFactoryPrecompile<Runtime>
+where
+	Runtime: pallet_assets::Config<AssetId = AssetId> + pallet_evm::Config + frame_system::Config,
+	Runtime: EVMAddressToAssetId<AssetId>,
+	Runtime::RuntimeCall:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	Runtime::RuntimeCall: From<pallet_assets::Call<Runtime>>,
+	<Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<AccountIdOf<Runtime>>>,
+	BalanceOf<Runtime>: TryFrom<U256> + Into<U256>,
+{
+	#[precompile::public("convertAssetIdToAddress(uint32)")]
+	#[precompile::view]
+	fn convert_asset_id_to_address(
+		_handle: &mut impl PrecompileHandle,
+		id: u32,
+	) -> EvmResult<Address> {
+		Ok(Address(Runtime::asset_id_to_address(AssetId::Token(id))))
+	}
+
+	#[precompile::public("create(uint32,address,uint256)")]
+	fn create(
+		handle: &mut impl PrecompileHandle,
+		id: u32,
+		admin: Address,
+		min_balance: U256,
+	) -> EvmResult<Address> {
+		handle.record_log_costs_manual(3, 32 * 3)?;
+
+		let asset_id = Self::allowed_asset_id(id).in_field("id")?;
+		let amount = Self::u256_to_amount(min_balance).in_field("minBalance")?;
+
+		let admin: H160 = admin.into();
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		// Dispatch call (if enough gas).
+		RuntimeHelper::<Runtime>::try_dispatch(
+			handle,
+			Some(origin).into(),
+			pallet_assets::Call::<Runtime>::create {
+				id: asset_id.into(),
+				admin: Runtime::Lookup::unlookup(Runtime::AddressMapping::into_account_id(admin)),
+				min_balance: amount,
+			},
+			ASSET_DETAILS_SIZE,
+		)?;
+
+		let asset_address = Runtime::asset_id_to_address(asset_id);
+
+		AssetCreated {
+			creator: Address::from(handle.context().caller),
+			asset: Address(asset_address),
+			id,
+			admin: Address(admin),
+			min_balance,
+		}
+		.into_log(handle.context().address)
+		.record(handle)?;
+
+		Ok(Address(asset_address))
+	}
+
+	#[precompile::public("setMetadata(uint32,string,string,uint8)")]
+	fn set_metadata(
+		handle: &mut impl PrecompileHandle,
+		id: u32,
+		name: BoundedString<GetStringLimit>,
+		symbol: BoundedString<GetStringLimit>,
+		decimals: u8,
+	) -> EvmResult<bool> {
+		let asset_id = Self::allowed_asset_id(id).in_field("id")?;
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let storage_growth =
+			ASSET_METADATA_KEY_SIZE + name.as_bytes().len() as u64 + symbol.as_bytes().len() as u64;
+
+		// Dispatch call (if enough gas).
+		RuntimeHelper::<Runtime>::try_dispatch(
+			handle,
+			Some(origin).into(),
+			pallet_assets::Call::<Runtime>::set_metadata {
+				id: asset_id.into(),
+				name: name.as_bytes().to_vec(),
+				symbol: symbol.as_bytes().to_vec(),
+				decimals,
+			},
+			storage_growth,
+		)?;
+
+		MetadataSet {
+			asset: Address(Runtime::asset_id_to_address(asset_id)),
+			name,
+			symbol,
+			decimals,
+		}
+		.record(handle)?;
+
+		Ok(true)
+	}
+
+	/// Only plain tokens that `AssetId::is_allow_to_create` accepts can be created, the same
+	/// rule the runtime `BaseFilter` applies to `pallet_assets::create`.
+	fn allowed_asset_id(id: u32) -> MayRevert<AssetId> {
+		let asset_id = AssetId::Token(id);
+		if !asset_id.is_allow_to_create() {
+			return Err(RevertReason::custom("Asset id is not allowed to be created").into())
+		}
+		Ok(asset_id)
+	}
+
+	fn u256_to_amount(value: U256) -> MayRevert<BalanceOf<Runtime>> {
+		value
+			.try_into()
+			.map_err(|_| RevertReason::value_is_too_large("balance type").into())
+	}
+}
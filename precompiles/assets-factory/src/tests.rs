@@ -0,0 +1,269 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use frame_support::assert_ok;
+use precompile_utils::testing::*;
+use sha3::{Digest, Keccak256};
+
+use crate::{mock::*, *};
+
+fn precompile_address() -> H160 {
+	H160::from_low_u64_be(1)
+}
+
+fn asset_address(id: u32) -> H160 {
+	Runtime::asset_id_to_address(AssetId::Token(id))
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::convert_asset_id_to_address_selectors().contains(&0xd7ad6236));
+	assert!(PCall::create_selectors().contains(&0x8af44993));
+	assert!(PCall::set_metadata_selectors().contains(&0xdc331b70));
+
+	assert_eq!(
+		crate::SELECTOR_LOG_ASSET_CREATED,
+		&Keccak256::digest(b"AssetCreated(address,address,uint32,address,uint256)")[..]
+	);
+	assert_eq!(
+		crate::SELECTOR_LOG_METADATA_SET,
+		&Keccak256::digest(b"MetadataSet(address,string,string,uint8)")[..]
+	);
+}
+
+#[test]
+fn modifiers() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			let mut tester =
+				PrecompilesModifierTester::new(precompiles(), Alice, precompile_address());
+
+			tester.test_view_modifier(PCall::convert_asset_id_to_address_selectors());
+			tester.test_default_modifier(PCall::create_selectors());
+			tester.test_default_modifier(PCall::set_metadata_selectors());
+		});
+}
+
+#[test]
+fn convert_asset_id_to_address() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(Alice, precompile_address(), PCall::convert_asset_id_to_address { id: 5 })
+			.expect_cost(0)
+			.expect_no_logs()
+			.execute_returns(Address(asset_address(5)));
+	});
+}
+
+#[test]
+fn create_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			precompiles()
+				.prepare_test(
+					Alice,
+					precompile_address(),
+					PCall::create {
+						id: 5,
+						admin: Address(Bob.into()),
+						min_balance: U256::from(10),
+					},
+				)
+				.expect_log(log3(
+					precompile_address(),
+					SELECTOR_LOG_ASSET_CREATED,
+					H160::from(Alice),
+					asset_address(5),
+					solidity::encode_event_data((5u32, Address(Bob.into()), U256::from(10))),
+				))
+				.execute_returns(Address(asset_address(5)));
+
+			assert_eq!(
+				pallet_assets::Pallet::<Runtime>::maybe_total_supply(AssetId::Token(5)),
+				Some(0)
+			);
+			assert_eq!(
+				<pallet_assets::Pallet<Runtime> as frame_support::traits::fungibles::Inspect<
+					AccountId,
+				>>::minimum_balance(AssetId::Token(5)),
+				10
+			);
+		});
+}
+
+#[test]
+fn create_rejects_native_asset_id() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			precompiles()
+				.prepare_test(
+					Alice,
+					precompile_address(),
+					PCall::create { id: 0, admin: Address(Alice.into()), min_balance: U256::one() },
+				)
+				.execute_reverts(|output| output == b"id: Asset id is not allowed to be created");
+		});
+}
+
+#[test]
+fn create_rejects_asset_id_outside_token_range() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			precompiles()
+				.prepare_test(
+					Alice,
+					precompile_address(),
+					PCall::create {
+						id: 0x0FFF_FFFF,
+						admin: Address(Alice.into()),
+						min_balance: U256::one(),
+					},
+				)
+				.execute_reverts(|output| output == b"id: Asset id is not allowed to be created");
+		});
+}
+
+#[test]
+fn create_rejects_too_large_min_balance() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			precompiles()
+				.prepare_test(
+					Alice,
+					precompile_address(),
+					PCall::create { id: 5, admin: Address(Alice.into()), min_balance: U256::MAX },
+				)
+				.execute_reverts(|output| {
+					output == b"minBalance: Value is too large for balance type"
+				});
+		});
+}
+
+#[test]
+fn create_fails_for_existing_asset() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Assets::force_create(
+				RuntimeOrigin::root(),
+				AssetId::Token(5),
+				Bob.into(),
+				true,
+				1
+			));
+
+			precompiles()
+				.prepare_test(
+					Alice,
+					precompile_address(),
+					PCall::create { id: 5, admin: Address(Alice.into()), min_balance: U256::one() },
+				)
+				.execute_reverts(|output| {
+					output.starts_with(b"Dispatched call failed with error:")
+				});
+		});
+}
+
+#[test]
+fn set_metadata_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			precompiles()
+				.prepare_test(
+					Alice,
+					precompile_address(),
+					PCall::create { id: 5, admin: Address(Alice.into()), min_balance: U256::one() },
+				)
+				.execute_some();
+
+			precompiles()
+				.prepare_test(
+					Alice,
+					precompile_address(),
+					PCall::set_metadata {
+						id: 5,
+						name: "Test token".into(),
+						symbol: "TEST".into(),
+						decimals: 12,
+					},
+				)
+				.expect_log(log2(
+					precompile_address(),
+					SELECTOR_LOG_METADATA_SET,
+					asset_address(5),
+					solidity::encode_event_data((
+						BoundedString::<GetStringLimit>::from("Test token"),
+						BoundedString::<GetStringLimit>::from("TEST"),
+						12u8,
+					)),
+				))
+				.execute_returns(true);
+
+			let metadata = pallet_assets::Metadata::<Runtime>::get(AssetId::Token(5));
+			assert_eq!(metadata.name.to_vec(), b"Test token".to_vec());
+			assert_eq!(metadata.symbol.to_vec(), b"TEST".to_vec());
+			assert_eq!(metadata.decimals, 12);
+		});
+}
+
+#[test]
+fn set_metadata_fails_for_non_owner() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000), (Bob.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			precompiles()
+				.prepare_test(
+					Alice,
+					precompile_address(),
+					PCall::create { id: 5, admin: Address(Alice.into()), min_balance: U256::one() },
+				)
+				.execute_some();
+
+			precompiles()
+				.prepare_test(
+					Bob,
+					precompile_address(),
+					PCall::set_metadata {
+						id: 5,
+						name: "Test token".into(),
+						symbol: "TEST".into(),
+						decimals: 12,
+					},
+				)
+				.execute_reverts(|output| {
+					output.starts_with(b"Dispatched call failed with error:")
+				});
+		});
+}
+
+#[test]
+fn events_match_their_signatures() {
+	assert_eq!(AssetCreated::solidity_event().signature(), AssetCreated::SIGNATURE);
+	assert_eq!(MetadataSet::solidity_event().signature(), MetadataSet::SIGNATURE);
+}
+
+#[test]
+fn test_solidity_interface_has_all_function_selectors_documented_and_implemented() {
+	check_precompile_implements_solidity_interfaces(
+		&["AssetsFactory.sol"],
+		PCall::supports_selector,
+	)
+}
+
+#[test]
+fn test_solidity_interface_matches_precompile() {
+	check_solidity_interface_matches("AssetsFactory.sol", &PCall::solidity_interface())
+}
@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pragma solidity >=0.8.3;
+
+address constant PRECOMPILE_ADDR = address(0x0000000000000000000000000000000000000401);
+
+/// The precompile has no functions: the input of a call is a SCALE encoded runtime call, which is
+/// dispatched with the caller as signed origin if it is allowed by the runtime.
+interface Dispatch {
+
+    /// @dev Emitted when a call was dispatched, callHash is the blake2_256 hash of the input.
+    event Dispatched(
+        address indexed caller,
+        bytes32 callHash
+    );
+}
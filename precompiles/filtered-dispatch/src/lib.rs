@@ -0,0 +1,83 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Precompile dispatching a SCALE encoded runtime call, given as raw input, with the caller as
+//! signed origin.
+//!
+//! Only calls allowed by `CallFilter` can be dispatched. The filter is also added to the origin,
+//! so calls dispatched by the call itself (e.g. by `utility.batch`) must be allowed as well.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use fp_evm::PrecompileHandle;
+use frame_support::{
+	dispatch::{DispatchClass, Dispatchable, GetCallMetadata, GetDispatchInfo, PostDispatchInfo},
+	traits::{Contains, OriginTrait},
+};
+use pallet_evm::AddressMapping;
+use parity_scale_codec::{Decode, DecodeLimit};
+use precompile_utils::prelude::*;
+use sp_core::H256;
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+type AccountIdOf<Runtime> = <Runtime as frame_system::Config>::AccountId;
+type OriginOf<Runtime> =
+	<<Runtime as frame_system::Config>::RuntimeCall as Dispatchable>::RuntimeOrigin;
+
+/// Maximum depth of nested calls when decoding the input.
+pub const CALL_DECODE_DEPTH_LIMIT: u32 = 8;
+
+pub(crate) const SELECTOR_LOG_DISPATCHED: [u8; 32] = keccak256!("Dispatched(address,bytes32)");
+
+pub struct FilteredDispatchPrecompile<Runtime, CallFilter>(PhantomData<(Runtime, CallFilter)>);
+
+#[precompile_utils::precompile]
+impl<Runtime, CallFilter> FilteredDispatchPrecompile<Runtime, CallFilter>
+where
+	Runtime: pallet_evm::Config + frame_system::pallet::Config,
+	Runtime::RuntimeCall:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode + GetCallMetadata,
+	OriginOf<Runtime>:
+		From<Option<AccountIdOf<Runtime>>> + OriginTrait<Call = Runtime::RuntimeCall>,
+	CallFilter: Contains<Runtime::RuntimeCall> + 'static,
+{
+	#[precompile::fallback]
+	fn dispatch(handle: &mut impl PrecompileHandle) -> EvmResult {
+		let input = handle.input().to_vec();
+		let call = Runtime::RuntimeCall::decode_all_with_depth_limit(
+			CALL_DECODE_DEPTH_LIMIT,
+			&mut input.as_slice(),
+		)
+		.map_err(|_| revert("Failed decoding call"))?;
+
+		if call.get_dispatch_info().class != DispatchClass::Normal {
+			return Err(revert("Only normal calls can be dispatched"))
+		}
+		if !CallFilter::contains(&call) {
+			return Err(revert("Call is not allowed"))
+		}
+
+		let caller = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let mut origin: OriginOf<Runtime> = Some(caller).into();
+		origin.add_filter(CallFilter::contains);
+
+		// The weight of the call is charged before dispatching it and the unused part is refunded.
+		// The allowed calls store at most the data they are given, which bounds the storage growth.
+		RuntimeHelper::<Runtime>::try_dispatch(handle, origin, call, input.len() as u64)?;
+
+		let event = log2(
+			handle.context().address,
+			SELECTOR_LOG_DISPATCHED,
+			handle.context().caller,
+			solidity::encode_event_data(H256(sp_io::hashing::blake2_256(&input))),
+		);
+		handle.record_log_costs(&[&event])?;
+		event.record(handle)?;
+
+		Ok(())
+	}
+}
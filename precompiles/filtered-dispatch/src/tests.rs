@@ -0,0 +1,179 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use fp_evm::Log;
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo},
+	traits::OriginTrait,
+	weights::Weight,
+};
+use pallet_evm::GasWeightMapping;
+use parity_scale_codec::Encode;
+use precompile_utils::{evm::costs::log_costs, testing::*};
+use sha3::{Digest, Keccak256};
+use sp_core::H160;
+
+use crate::{mock::*, *};
+
+fn precompile_address() -> H160 {
+	H160::from_low_u64_be(1)
+}
+
+fn remark(remark: &[u8]) -> RuntimeCall {
+	RuntimeCall::System(frame_system::Call::remark_with_event { remark: remark.to_vec() })
+}
+
+fn transfer_to_bob() -> RuntimeCall {
+	RuntimeCall::Balances(pallet_balances::Call::transfer_keep_alive {
+		dest: account_of(Bob),
+		value: 100,
+	})
+}
+
+fn batch_all(calls: Vec<RuntimeCall>) -> RuntimeCall {
+	RuntimeCall::Utility(pallet_utility::Call::batch_all { calls })
+}
+
+/// Gas charged for a dispatched call using `weight`, including the `Dispatched` log.
+fn dispatch_cost(weight: Weight) -> u64 {
+	<Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(weight) +
+		log_costs(2, 32).expect("log cost doesn't overflow")
+}
+
+fn dispatched_log(caller: impl Into<H160>, call: &RuntimeCall) -> Log {
+	log2(
+		precompile_address(),
+		SELECTOR_LOG_DISPATCHED,
+		caller.into(),
+		solidity::encode_event_data(H256(sp_io::hashing::blake2_256(&call.encode()))),
+	)
+}
+
+fn remarks_by(account: AccountId) -> usize {
+	System::events()
+		.iter()
+		.filter(|record| {
+			matches!(
+				&record.event,
+				RuntimeEvent::System(frame_system::Event::Remarked { sender, .. })
+					if *sender == account
+			)
+		})
+		.count()
+}
+
+fn ext_with_funded_alice() -> sp_io::TestExternalities {
+	ExtBuilder::default().with_balances(vec![(account_of(Alice), 1_000)]).build()
+}
+
+#[test]
+fn log_selectors() {
+	assert_eq!(
+		crate::SELECTOR_LOG_DISPATCHED,
+		&Keccak256::digest(b"Dispatched(address,bytes32)")[..]
+	);
+}
+
+#[test]
+fn dispatches_allowed_call_with_caller_origin() {
+	ext_with_funded_alice().execute_with(|| {
+		let call = remark(b"peaq");
+
+		precompiles()
+			.prepare_test(Alice, precompile_address(), call.encode())
+			.expect_cost(dispatch_cost(call.get_dispatch_info().weight))
+			.expect_log(dispatched_log(Alice, &call))
+			.execute_returns(());
+
+		assert_eq!(remarks_by(account_of(Alice)), 1);
+	});
+}
+
+#[test]
+fn denies_call_not_allowed_by_filter() {
+	ext_with_funded_alice().execute_with(|| {
+		precompiles()
+			.prepare_test(Alice, precompile_address(), transfer_to_bob().encode())
+			.execute_reverts(|output| output == b"Call is not allowed");
+
+		assert_eq!(Balances::free_balance(account_of(Bob)), 0);
+	});
+}
+
+#[test]
+fn reverts_for_invalid_call() {
+	ext_with_funded_alice().execute_with(|| {
+		precompiles()
+			.prepare_test(Alice, precompile_address(), vec![0xff; 4])
+			.execute_reverts(|output| output == b"Failed decoding call");
+	});
+}
+
+#[test]
+fn reverts_in_static_context() {
+	ext_with_funded_alice().execute_with(|| {
+		precompiles()
+			.prepare_test(Alice, precompile_address(), remark(b"peaq").encode())
+			.with_static_call(true)
+			.execute_reverts(|output| {
+				output == b"Can't call non-static function in static context"
+			});
+	});
+}
+
+#[test]
+fn dispatches_nested_allowed_calls() {
+	ext_with_funded_alice().execute_with(|| {
+		let call = batch_all(vec![remark(b"first"), remark(b"second")]);
+
+		precompiles()
+			.prepare_test(Alice, precompile_address(), call.encode())
+			.expect_log(dispatched_log(Alice, &call))
+			.execute_returns(());
+
+		assert_eq!(remarks_by(account_of(Alice)), 2);
+	});
+}
+
+#[test]
+fn denies_nested_call_not_allowed_by_filter() {
+	ext_with_funded_alice().execute_with(|| {
+		let call = batch_all(vec![remark(b"first"), transfer_to_bob()]);
+
+		precompiles()
+			.prepare_test(Alice, precompile_address(), call.encode())
+			.execute_reverts(|output| output.starts_with(b"Dispatched call failed with error:"));
+
+		assert_eq!(remarks_by(account_of(Alice)), 0);
+		assert_eq!(Balances::free_balance(account_of(Bob)), 0);
+	});
+}
+
+#[test]
+fn refunds_unused_weight() {
+	// The batch is interrupted by the filtered transfer, so the last remark is not dispatched.
+	let call = RuntimeCall::Utility(pallet_utility::Call::batch {
+		calls: vec![remark(b"first"), transfer_to_bob(), remark(b"second")],
+	});
+
+	let actual_weight = ext_with_funded_alice().execute_with(|| {
+		let mut origin = RuntimeOrigin::signed(account_of(Alice));
+		origin.add_filter(CallFilter::contains);
+		call.clone()
+			.dispatch(origin)
+			.expect("interrupted batch succeeds")
+			.actual_weight
+			.expect("batch returns its actual weight")
+	});
+	assert!(actual_weight.ref_time() < call.get_dispatch_info().weight.ref_time());
+
+	ext_with_funded_alice().execute_with(|| {
+		precompiles()
+			.prepare_test(Alice, precompile_address(), call.encode())
+			.expect_cost(dispatch_cost(actual_weight))
+			.expect_log(dispatched_log(Alice, &call))
+			.execute_returns(());
+
+		assert_eq!(remarks_by(account_of(Alice)), 1);
+		assert_eq!(Balances::free_balance(account_of(Bob)), 0);
+	});
+}
@@ -15,11 +15,36 @@ interface DID {
         uint256 created;
     }
 
+    /// @custom:selector a4319234
     function read_attribute(
         bytes32 did_account,
         bytes memory name
     ) external view returns (Attribute memory);
 
+    /// @dev Returns the name, value, validity and creation time of an attribute.
+    /// @custom:selector a5be7016
+    function getAttribute(
+        bytes32 did_account,
+        bytes memory name
+    ) external view returns (bytes memory, bytes memory, uint32, uint256);
+
+    /// @dev Returns whether the attribute exists and has not expired at the current block.
+    /// @custom:selector 4f309cb8
+    function isValid(
+        bytes32 did_account,
+        bytes memory name
+    ) external view returns (bool);
+
+    /// @dev Returns `limit` attributes of a DID from the `offset`-th one, in no particular order.
+    /// Attributes added before the attributes were indexed are not listed.
+    /// @custom:selector c7a4d63b
+    function getAttributes(
+        bytes32 did_account,
+        uint32 offset,
+        uint32 limit
+    ) external view returns (Attribute[] memory);
+
+    /// @custom:selector a4c1165f
     function add_attribute(
         bytes32 did_account,
         bytes memory name,
@@ -27,6 +52,7 @@ interface DID {
         uint32 validity_for
     ) external returns (bool);
 
+    /// @custom:selector 4e8119d4
     function update_attribute(
         bytes32 did_account,
         bytes memory name,
@@ -34,6 +60,7 @@ interface DID {
         uint32 validity_for
     ) external returns (bool);
 
+    /// @custom:selector 463c105d
     function remove_attribute(
         bytes32 did_account,
         bytes memory name
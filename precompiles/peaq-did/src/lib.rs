@@ -4,7 +4,7 @@
 
 // primitives and utils imports
 use frame_support::{
-	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	dispatch::{Dispatchable, GetCallMetadata, GetDispatchInfo, PostDispatchInfo},
 	traits::ConstU32,
 };
 use precompile_utils::prelude::*;
@@ -15,21 +15,48 @@ use fp_evm::PrecompileHandle;
 
 use pallet_evm::AddressMapping;
 
+use key_index::KeyKind;
 use peaq_pallet_did::did::Did as PeaqDidT;
 
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
 type AccountIdOf<Runtime> = <Runtime as frame_system::Config>::AccountId;
 type BlockNumberOf<Runtime> = <Runtime as frame_system::Config>::BlockNumber;
 type MomentOf<Runtime> = <Runtime as pallet_timestamp::Config>::Moment;
 
 type GetBytesLimit = ConstU32<{ 2u32.pow(16) }>;
-pub(crate) const SELECTOR_LOG_ADD_ATTRIBUTE: [u8; 32] =
-	keccak256!("AddAttribute(address,bytes32,bytes,bytes,uint32)");
+pub(crate) const SELECTOR_LOG_ADD_ATTRIBUTE: [u8; 32] = AddAttribute::TOPIC;
+
+pub(crate) const SELECTOR_LOG_UPDATE_ATTRIBUTE: [u8; 32] = UpdateAttribute::TOPIC;
+
+pub(crate) const SELECTOR_LOG_REMOVE_ATTRIBUTE: [u8; 32] = RemoveAttribte::TOPIC;
+
+#[precompile::event("AddAttribute(address,bytes32,bytes,bytes,uint32)")]
+pub struct AddAttribute {
+	pub sender: Address,
+	pub did_account: H256,
+	pub name: BoundedBytes<GetBytesLimit>,
+	pub value: BoundedBytes<GetBytesLimit>,
+	pub validity: u32,
+}
 
-pub(crate) const SELECTOR_LOG_UPDATE_ATTRIBUTE: [u8; 32] =
-	keccak256!("UpdateAttribute(address,bytes32,bytes,bytes,uint32)");
+#[precompile::event("UpdateAttribute(address,bytes32,bytes,bytes,uint32)")]
+pub struct UpdateAttribute {
+	pub sender: Address,
+	pub did_account: H256,
+	pub name: BoundedBytes<GetBytesLimit>,
+	pub value: BoundedBytes<GetBytesLimit>,
+	pub validity: u32,
+}
 
-pub(crate) const SELECTOR_LOG_REMOVE_ATTRIBUTE: [u8; 32] =
-	keccak256!("RemoveAttribte(bytes32,bytes)");
+#[precompile::event("RemoveAttribte(bytes32,bytes)")]
+pub struct RemoveAttribte {
+	pub did_account: H256,
+	pub name: BoundedBytes<GetBytesLimit>,
+}
 
 pub struct PeaqDIDPrecompile<Runtime>(PhantomData<Runtime>);
 
@@ -49,15 +76,18 @@ pub struct EVMAttribute {
 }
 
 #[precompile_utils::precompile]
+#[precompile::events(AddAttribute, UpdateAttribute, RemoveAttribte)]
 impl<Runtime> PeaqDIDPrecompile<Runtime>
 where
 	Runtime: pallet_evm::Config
 		+ peaq_pallet_did::Config
 		+ frame_system::pallet::Config
-		+ pallet_timestamp::Config,
+		+ pallet_timestamp::Config
+		+ key_index::Config,
 	peaq_pallet_did::Pallet<Runtime>:
 		PeaqDidT<AccountIdOf<Runtime>, BlockNumberOf<Runtime>, MomentOf<Runtime>>,
-	Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode,
+	Runtime::RuntimeCall:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode + GetCallMetadata,
 	Runtime::RuntimeCall: From<peaq_pallet_did::Call<Runtime>>,
 	<Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<AccountIdOf<Runtime>>>,
 	MomentOf<Runtime>: Into<U256>,
@@ -85,6 +115,72 @@ where
 		}
 	}
 
+	#[precompile::public("getAttribute(bytes32,bytes)")]
+	#[precompile::view]
+	fn get_attribute(
+		handle: &mut impl PrecompileHandle,
+		did_account: H256,
+		name: BoundedBytes<GetBytesLimit>,
+	) -> EvmResult<(UnboundedBytes, UnboundedBytes, u32, U256)> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let did_account = AccountIdOf::<Runtime>::from(did_account.to_fixed_bytes());
+		match peaq_pallet_did::Pallet::<Runtime>::read(&did_account, &Vec::<u8>::from(name)) {
+			Some(v) => Ok((v.name.into(), v.value.into(), v.validity.into(), v.created.into())),
+			None => Err(Revert::new(RevertReason::custom("Cannot find the item")).into()),
+		}
+	}
+
+	/// An attribute is valid until the block its validity ends at, attributes added without
+	/// a validity period never expire. Missing attributes are not valid.
+	#[precompile::public("isValid(bytes32,bytes)")]
+	#[precompile::view]
+	fn is_valid(
+		handle: &mut impl PrecompileHandle,
+		did_account: H256,
+		name: BoundedBytes<GetBytesLimit>,
+	) -> EvmResult<bool> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let did_account = AccountIdOf::<Runtime>::from(did_account.to_fixed_bytes());
+		let now: u32 = frame_system::Pallet::<Runtime>::block_number().into();
+		Ok(peaq_pallet_did::Pallet::<Runtime>::read(&did_account, &Vec::<u8>::from(name))
+			.map_or(false, |v| now < v.validity.into()))
+	}
+
+	/// Attributes are listed from the key index, in no particular order. Attributes added
+	/// before the index was deployed are not listed.
+	#[precompile::public("getAttributes(bytes32,uint32,uint32)")]
+	#[precompile::view]
+	fn get_attributes(
+		handle: &mut impl PrecompileHandle,
+		did_account: H256,
+		offset: u32,
+		limit: u32,
+	) -> EvmResult<Vec<EVMAttribute>> {
+		// The names before the page are read from the index too.
+		handle.record_cost(
+			RuntimeHelper::<Runtime>::db_read_gas_cost().saturating_mul(offset.into()),
+		)?;
+		let did_account = AccountIdOf::<Runtime>::from(did_account.to_fixed_bytes());
+
+		let mut attributes = Vec::new();
+		let names = key_index::Pallet::<Runtime>::keys(KeyKind::DidAttribute, did_account.clone())
+			.skip(offset as usize)
+			.take(limit as usize);
+		for name in names {
+			// Name in the index and attribute in the DID pallet.
+			handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost() * 2)?;
+			if let Some(v) = peaq_pallet_did::Pallet::<Runtime>::read(&did_account, &name) {
+				attributes.push(EVMAttribute {
+					name: v.name.into(),
+					value: v.value.into(),
+					validity: v.validity.into(),
+					created: v.created.into(),
+				});
+			}
+		}
+		Ok(attributes)
+	}
+
 	#[precompile::public("add_attribute(bytes32,bytes,bytes,uint32)")]
 	fn add_attribute(
 		handle: &mut impl PrecompileHandle,
@@ -119,18 +215,14 @@ where
 				4_u64,
 		)?;
 
-		let event = log1(
-			handle.context().address,
-			SELECTOR_LOG_ADD_ATTRIBUTE,
-			solidity::encode_event_data((
-				Address::from(handle.context().caller),
-				did_account,
-				name,
-				value,
-				valid_for,
-			)),
-		);
-		event.record(handle)?;
+		AddAttribute {
+			sender: Address::from(handle.context().caller),
+			did_account,
+			name,
+			value,
+			validity: valid_for,
+		}
+		.record(handle)?;
 
 		Ok(true)
 	}
@@ -166,18 +258,14 @@ where
 			32_u64 + name.as_bytes().len() as u64 + value.as_bytes().len() as u64 + 4_u64,
 		)?;
 
-		let event = log1(
-			handle.context().address,
-			SELECTOR_LOG_UPDATE_ATTRIBUTE,
-			solidity::encode_event_data((
-				Address::from(handle.context().caller),
-				did_account,
-				name,
-				value,
-				valid_for,
-			)),
-		);
-		event.record(handle)?;
+		UpdateAttribute {
+			sender: Address::from(handle.context().caller),
+			did_account,
+			name,
+			value,
+			validity: valid_for,
+		}
+		.record(handle)?;
 
 		Ok(true)
 	}
@@ -203,12 +291,7 @@ where
 			0,
 		)?;
 
-		let event = log1(
-			handle.context().address,
-			SELECTOR_LOG_REMOVE_ATTRIBUTE,
-			solidity::encode_event_data((did_account, name)),
-		);
-		event.record(handle)?;
+		RemoveAttribte { did_account, name }.record(handle)?;
 
 		Ok(true)
 	}
@@ -0,0 +1,383 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use frame_support::traits::Hooks;
+use precompile_utils::testing::*;
+use sha3::{Digest, Keccak256};
+
+use crate::{mock::*, *};
+
+fn precompile_address() -> H160 {
+	H160::from_low_u64_be(1)
+}
+
+fn did_account() -> H256 {
+	H256::repeat_byte(0x42)
+}
+
+fn add_attribute(valid_for: u32) {
+	precompiles()
+		.prepare_test(
+			Alice,
+			precompile_address(),
+			PCall::add_attribute {
+				did_account: did_account(),
+				name: "name".into(),
+				value: "value".into(),
+				valid_for,
+			},
+		)
+		.execute_returns(true);
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::read_attribute_selectors().contains(&0xa4319234));
+	assert!(PCall::get_attribute_selectors().contains(&0xa5be7016));
+	assert!(PCall::is_valid_selectors().contains(&0x4f309cb8));
+	assert!(PCall::get_attributes_selectors().contains(&0xc7a4d63b));
+	assert!(PCall::add_attribute_selectors().contains(&0xa4c1165f));
+	assert!(PCall::update_attribute_selectors().contains(&0x4e8119d4));
+	assert!(PCall::remove_attribute_selectors().contains(&0x463c105d));
+
+	assert_eq!(
+		crate::SELECTOR_LOG_ADD_ATTRIBUTE,
+		&Keccak256::digest(b"AddAttribute(address,bytes32,bytes,bytes,uint32)")[..]
+	);
+	assert_eq!(
+		crate::SELECTOR_LOG_UPDATE_ATTRIBUTE,
+		&Keccak256::digest(b"UpdateAttribute(address,bytes32,bytes,bytes,uint32)")[..]
+	);
+	assert_eq!(
+		crate::SELECTOR_LOG_REMOVE_ATTRIBUTE,
+		&Keccak256::digest(b"RemoveAttribte(bytes32,bytes)")[..]
+	);
+}
+
+#[test]
+fn modifiers() {
+	ExtBuilder::default()
+		.with_balances(vec![(account_of(Alice), 1000)])
+		.build()
+		.execute_with(|| {
+			let mut tester =
+				PrecompilesModifierTester::new(precompiles(), Alice, precompile_address());
+
+			tester.test_view_modifier(PCall::read_attribute_selectors());
+			tester.test_view_modifier(PCall::get_attribute_selectors());
+			tester.test_view_modifier(PCall::is_valid_selectors());
+			tester.test_view_modifier(PCall::get_attributes_selectors());
+			tester.test_default_modifier(PCall::add_attribute_selectors());
+			tester.test_default_modifier(PCall::update_attribute_selectors());
+			tester.test_default_modifier(PCall::remove_attribute_selectors());
+		});
+}
+
+#[test]
+fn add_attribute_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::add_attribute {
+					did_account: did_account(),
+					name: "name".into(),
+					value: "value".into(),
+					valid_for: 10,
+				},
+			)
+			.expect_log(log1(
+				precompile_address(),
+				SELECTOR_LOG_ADD_ATTRIBUTE,
+				solidity::encode_event_data((
+					Address(Alice.into()),
+					did_account(),
+					UnboundedBytes::from("name"),
+					UnboundedBytes::from("value"),
+					10u32,
+				)),
+			))
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::get_attribute { did_account: did_account(), name: "name".into() },
+			)
+			.expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())
+			.expect_no_logs()
+			.execute_returns((
+				UnboundedBytes::from("name"),
+				UnboundedBytes::from("value"),
+				11u32,
+				U256::from(1_000),
+			));
+	});
+}
+
+#[test]
+fn get_attribute_fails_for_missing_attribute() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_attribute { did_account: did_account(), name: "name".into() },
+			)
+			.execute_reverts(|output| output == b"Cannot find the item");
+	});
+}
+
+#[test]
+fn is_valid_follows_validity() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::is_valid { did_account: did_account(), name: "name".into() },
+			)
+			.execute_returns(false);
+
+		add_attribute(10);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::is_valid { did_account: did_account(), name: "name".into() },
+			)
+			.expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())
+			.execute_returns(true);
+
+		System::set_block_number(11);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::is_valid { did_account: did_account(), name: "name".into() },
+			)
+			.execute_returns(false);
+	});
+}
+
+#[test]
+fn attribute_without_validity_never_expires() {
+	ExtBuilder::default().build().execute_with(|| {
+		add_attribute(0);
+
+		System::set_block_number(u32::MAX - 1);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::is_valid { did_account: did_account(), name: "name".into() },
+			)
+			.execute_returns(true);
+	});
+}
+
+#[test]
+fn update_attribute_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		add_attribute(0);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::update_attribute {
+					did_account: did_account(),
+					name: "name".into(),
+					value: "other".into(),
+					valid_for: 0,
+				},
+			)
+			.expect_log(log1(
+				precompile_address(),
+				SELECTOR_LOG_UPDATE_ATTRIBUTE,
+				solidity::encode_event_data((
+					Address(Alice.into()),
+					did_account(),
+					UnboundedBytes::from("name"),
+					UnboundedBytes::from("other"),
+					0u32,
+				)),
+			))
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::read_attribute { did_account: did_account(), name: "name".into() },
+			)
+			.execute_returns(EVMAttribute {
+				name: "name".into(),
+				value: "other".into(),
+				validity: u32::MAX,
+				created: U256::zero(),
+			});
+	});
+}
+
+#[test]
+fn update_attribute_fails_for_non_owner() {
+	ExtBuilder::default().build().execute_with(|| {
+		add_attribute(0);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::update_attribute {
+					did_account: did_account(),
+					name: "name".into(),
+					value: "other".into(),
+					valid_for: 0,
+				},
+			)
+			.execute_reverts(|output| output.starts_with(b"Dispatched call failed with error:"));
+	});
+}
+
+#[test]
+fn remove_attribute_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		add_attribute(0);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::remove_attribute { did_account: did_account(), name: "name".into() },
+			)
+			.expect_log(log1(
+				precompile_address(),
+				SELECTOR_LOG_REMOVE_ATTRIBUTE,
+				solidity::encode_event_data((did_account(), UnboundedBytes::from("name"))),
+			))
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::is_valid { did_account: did_account(), name: "name".into() },
+			)
+			.execute_returns(false);
+	});
+}
+
+fn add_named_attribute(name: &str) {
+	precompiles()
+		.prepare_test(
+			Alice,
+			precompile_address(),
+			PCall::add_attribute {
+				did_account: did_account(),
+				name: name.into(),
+				value: "value".into(),
+				valid_for: 10,
+			},
+		)
+		.execute_returns(true);
+}
+
+fn indexed_names() -> Vec<Vec<u8>> {
+	let did_account = AccountId::from(did_account().to_fixed_bytes());
+	key_index::Pallet::<Runtime>::page(KeyKind::DidAttribute, did_account, 0, u32::MAX)
+}
+
+fn attribute(name: &[u8]) -> EVMAttribute {
+	EVMAttribute {
+		name: name.to_vec().into(),
+		value: "value".into(),
+		validity: 11,
+		created: U256::from(1_000),
+	}
+}
+
+#[test]
+fn get_attributes_pages_the_indexed_attributes() {
+	ExtBuilder::default().build().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		add_named_attribute("a");
+		add_named_attribute("b");
+		add_named_attribute("c");
+		KeyIndex::on_finalize(System::block_number());
+
+		let names = indexed_names();
+		assert_eq!(names.len(), 3);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::get_attributes { did_account: did_account(), offset: 0, limit: 2 },
+			)
+			.expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost() * 4)
+			.expect_no_logs()
+			.execute_returns(vec![attribute(&names[0]), attribute(&names[1])]);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::get_attributes { did_account: did_account(), offset: 2, limit: 2 },
+			)
+			.expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost() * 4)
+			.execute_returns(vec![attribute(&names[2])]);
+	});
+}
+
+#[test]
+fn get_attributes_skips_removed_attributes() {
+	ExtBuilder::default().build().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		add_named_attribute("a");
+		add_named_attribute("b");
+		KeyIndex::on_finalize(System::block_number());
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::remove_attribute { did_account: did_account(), name: "a".into() },
+			)
+			.execute_returns(true);
+		KeyIndex::on_finalize(System::block_number());
+
+		assert_eq!(indexed_names(), vec![b"b".to_vec()]);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::get_attributes { did_account: did_account(), offset: 0, limit: 10 },
+			)
+			.execute_returns(vec![attribute(b"b")]);
+	});
+}
+
+#[test]
+fn events_match_their_signatures() {
+	assert_eq!(AddAttribute::solidity_event().signature(), AddAttribute::SIGNATURE);
+	assert_eq!(UpdateAttribute::solidity_event().signature(), UpdateAttribute::SIGNATURE);
+	assert_eq!(RemoveAttribte::solidity_event().signature(), RemoveAttribte::SIGNATURE);
+}
+
+#[test]
+fn test_solidity_interface_has_all_function_selectors_documented_and_implemented() {
+	check_precompile_implements_solidity_interfaces(&["did.sol"], PCall::supports_selector)
+}
+
+#[test]
+fn test_solidity_interface_matches_precompile() {
+	check_solidity_interface_matches("did.sol", &PCall::solidity_interface())
+}
@@ -0,0 +1,49 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pragma solidity >=0.8.3;
+
+address constant PRECOMPILE_ADDR = address(0x0000000000000000000000000000000000000804);
+
+MOR constant MOR_CONTRACT = MOR(PRECOMPILE_ADDR);
+
+interface MOR {
+
+    /// @dev Returns the balance of the machine owner rewards pot.
+    /// @custom:selector 4111a197
+    function potBalance() external view returns (uint256);
+
+    /// @dev Registers a machine owned by the caller by adding an attribute to its DID.
+    /// @custom:selector 02cd3d9f
+    function registerMachine(
+        bytes32 machine,
+        bytes memory name,
+        bytes memory value
+    ) external returns (bool);
+
+    /// @dev Claims the reward for registering a machine owned by the caller.
+    /// @custom:selector ae861a8c
+    function getRegistrationReward(
+        bytes32 machine
+    ) external returns (bool);
+
+    /// @dev Claims the rewards for a machine owned by the caller being online.
+    /// @custom:selector 39e2e3a3
+    function getOnlineRewards(
+        bytes32 machine
+    ) external returns (bool);
+
+    event MachineRegistered(
+        address sender,
+        bytes32 machine
+    );
+    event RegistrationRewardClaimed(
+        address sender,
+        bytes32 machine,
+        uint256 amount
+    );
+    event OnlineRewardsClaimed(
+        address sender,
+        bytes32 machine,
+        uint256 amount
+    );
+}
@@ -0,0 +1,190 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// primitives and utils imports
+use frame_support::{
+	dispatch::{Dispatchable, GetCallMetadata, GetDispatchInfo, PostDispatchInfo},
+	traits::{ConstU32, Currency, Get},
+};
+use precompile_utils::prelude::*;
+use sp_core::{Decode, H256, U256};
+use sp_runtime::traits::AccountIdConversion;
+use sp_std::marker::PhantomData;
+
+use fp_evm::PrecompileHandle;
+
+use pallet_evm::AddressMapping;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+type AccountIdOf<Runtime> = <Runtime as frame_system::Config>::AccountId;
+type BalanceOf<Runtime> =
+	<<Runtime as peaq_pallet_mor::Config>::Currency as Currency<AccountIdOf<Runtime>>>::Balance;
+
+type GetBytesLimit = ConstU32<{ 2u32.pow(16) }>;
+
+pub(crate) const SELECTOR_LOG_MACHINE_REGISTERED: [u8; 32] = MachineRegistered::TOPIC;
+
+pub(crate) const SELECTOR_LOG_REGISTRATION_REWARD_CLAIMED: [u8; 32] =
+	RegistrationRewardClaimed::TOPIC;
+
+pub(crate) const SELECTOR_LOG_ONLINE_REWARDS_CLAIMED: [u8; 32] = OnlineRewardsClaimed::TOPIC;
+
+#[precompile::event("MachineRegistered(address,bytes32)")]
+pub struct MachineRegistered {
+	pub sender: Address,
+	pub machine: H256,
+}
+
+#[precompile::event("RegistrationRewardClaimed(address,bytes32,uint256)")]
+pub struct RegistrationRewardClaimed {
+	pub sender: Address,
+	pub machine: H256,
+	pub amount: U256,
+}
+
+#[precompile::event("OnlineRewardsClaimed(address,bytes32,uint256)")]
+pub struct OnlineRewardsClaimed {
+	pub sender: Address,
+	pub machine: H256,
+	pub amount: U256,
+}
+
+pub struct PeaqMorPrecompile<Runtime>(PhantomData<Runtime>);
+
+/// Just a rough estimation, as in the DID precompile
+/// Attribute size in bytes = Pallet_Name_Hash (16) + Storage_name_hash (16) +
+/// Blake2_128Concat (16) + Hash (32) + Attribute Data
+/// Onwer size in bytes = Pallet_Name_Hash (16) + Storage_name_hash (16) +
+/// Blake2_128Concat (16) + AccountId (32) + Hash (32) + AccountID (32)
+const PEAQ_DID_KEY_SIZE: u64 = 224;
+
+#[precompile_utils::precompile]
+#[precompile::events(MachineRegistered, RegistrationRewardClaimed, OnlineRewardsClaimed)]
+impl<Runtime> PeaqMorPrecompile<Runtime>
+where
+	Runtime: pallet_evm::Config
+		+ peaq_pallet_mor::Config
+		+ peaq_pallet_did::Config
+		+ frame_system::pallet::Config,
+	Runtime::RuntimeCall:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode + GetCallMetadata,
+	Runtime::RuntimeCall:
+		From<peaq_pallet_mor::Call<Runtime>> + From<peaq_pallet_did::Call<Runtime>>,
+	<Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<AccountIdOf<Runtime>>>,
+	AccountIdOf<Runtime>: From<[u8; 32]>,
+	BalanceOf<Runtime>: Into<U256>,
+{
+	/// Balance of the machine owner rewards pot, out of which registration and online rewards
+	/// are paid.
+	#[precompile::public("potBalance()")]
+	#[precompile::view]
+	fn pot_balance(handle: &mut impl PrecompileHandle) -> EvmResult<U256> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		Ok(Self::pot_free_balance().into())
+	}
+
+	/// Registers a machine owned by the caller by adding an attribute to the DID of the machine.
+	/// The owner of the machine's DID is the one rewarded for the machine.
+	#[precompile::public("registerMachine(bytes32,bytes,bytes)")]
+	fn register_machine(
+		handle: &mut impl PrecompileHandle,
+		machine: H256,
+		name: BoundedBytes<GetBytesLimit>,
+		value: BoundedBytes<GetBytesLimit>,
+	) -> EvmResult<bool> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost())?;
+
+		let caller: AccountIdOf<Runtime> =
+			Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		RuntimeHelper::<Runtime>::try_dispatch(
+			handle,
+			Some(caller).into(),
+			peaq_pallet_did::Call::<Runtime>::add_attribute {
+				did_account: AccountIdOf::<Runtime>::from(machine.to_fixed_bytes()),
+				name: name.as_bytes().to_vec(),
+				value: value.as_bytes().to_vec(),
+				valid_for: None,
+			},
+			PEAQ_DID_KEY_SIZE +
+				32_u64 + name.as_bytes().len() as u64 +
+				value.as_bytes().len() as u64 +
+				4_u64,
+		)?;
+
+		MachineRegistered { sender: Address::from(handle.context().caller), machine }
+			.record(handle)?;
+
+		Ok(true)
+	}
+
+	#[precompile::public("getRegistrationReward(bytes32)")]
+	fn get_registration_reward(
+		handle: &mut impl PrecompileHandle,
+		machine: H256,
+	) -> EvmResult<bool> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost() * 2)?;
+
+		let caller: AccountIdOf<Runtime> =
+			Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let pot_before = Self::pot_free_balance();
+		RuntimeHelper::<Runtime>::try_dispatch(
+			handle,
+			Some(caller).into(),
+			peaq_pallet_mor::Call::<Runtime>::get_registration_reward {
+				machine: AccountIdOf::<Runtime>::from(machine.to_fixed_bytes()),
+			},
+			0,
+		)?;
+		let reward: U256 = pot_before.into().saturating_sub(Self::pot_free_balance().into());
+
+		RegistrationRewardClaimed {
+			sender: Address::from(handle.context().caller),
+			machine,
+			amount: reward,
+		}
+		.record(handle)?;
+
+		Ok(true)
+	}
+
+	#[precompile::public("getOnlineRewards(bytes32)")]
+	fn get_online_rewards(handle: &mut impl PrecompileHandle, machine: H256) -> EvmResult<bool> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost() * 2)?;
+
+		let caller: AccountIdOf<Runtime> =
+			Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let pot_before = Self::pot_free_balance();
+		RuntimeHelper::<Runtime>::try_dispatch(
+			handle,
+			Some(caller).into(),
+			peaq_pallet_mor::Call::<Runtime>::get_online_rewards {
+				machine: AccountIdOf::<Runtime>::from(machine.to_fixed_bytes()),
+			},
+			0,
+		)?;
+		let reward: U256 = pot_before.into().saturating_sub(Self::pot_free_balance().into());
+
+		OnlineRewardsClaimed {
+			sender: Address::from(handle.context().caller),
+			machine,
+			amount: reward,
+		}
+		.record(handle)?;
+
+		Ok(true)
+	}
+
+	fn pot_free_balance() -> BalanceOf<Runtime> {
+		let pot: AccountIdOf<Runtime> =
+			<Runtime as peaq_pallet_mor::Config>::PotId::get().into_account_truncating();
+		<Runtime as peaq_pallet_mor::Config>::Currency::free_balance(&pot)
+	}
+}
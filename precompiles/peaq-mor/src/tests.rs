@@ -0,0 +1,202 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use frame_support::assert_ok;
+use peaq_pallet_did::did::Did;
+use precompile_utils::testing::*;
+use sha3::{Digest, Keccak256};
+
+use crate::{mock::*, *};
+
+fn precompile_address() -> H160 {
+	H160::from_low_u64_be(1)
+}
+
+fn machine() -> H256 {
+	H256::repeat_byte(0x42)
+}
+
+fn register_machine(owner: impl Into<H160>) {
+	assert_ok!(PeaqDid::add_attribute(
+		RuntimeOrigin::signed(account_of(owner)),
+		AccountId::from(machine().to_fixed_bytes()),
+		b"id".to_vec(),
+		b"machine".to_vec(),
+		None
+	));
+}
+
+fn ext_with_funded_pot() -> sp_io::TestExternalities {
+	ExtBuilder::default()
+		.with_balances(vec![(account_of(Alice), 1_000), (pot_account(), 1_000)])
+		.build()
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::pot_balance_selectors().contains(&0x4111a197));
+	assert!(PCall::register_machine_selectors().contains(&0x02cd3d9f));
+	assert!(PCall::get_registration_reward_selectors().contains(&0xae861a8c));
+	assert!(PCall::get_online_rewards_selectors().contains(&0x39e2e3a3));
+
+	assert_eq!(
+		crate::SELECTOR_LOG_MACHINE_REGISTERED,
+		&Keccak256::digest(b"MachineRegistered(address,bytes32)")[..]
+	);
+	assert_eq!(
+		crate::SELECTOR_LOG_REGISTRATION_REWARD_CLAIMED,
+		&Keccak256::digest(b"RegistrationRewardClaimed(address,bytes32,uint256)")[..]
+	);
+	assert_eq!(
+		crate::SELECTOR_LOG_ONLINE_REWARDS_CLAIMED,
+		&Keccak256::digest(b"OnlineRewardsClaimed(address,bytes32,uint256)")[..]
+	);
+}
+
+#[test]
+fn modifiers() {
+	ext_with_funded_pot().execute_with(|| {
+		let mut tester = PrecompilesModifierTester::new(precompiles(), Alice, precompile_address());
+
+		tester.test_view_modifier(PCall::pot_balance_selectors());
+		tester.test_default_modifier(PCall::register_machine_selectors());
+		tester.test_default_modifier(PCall::get_registration_reward_selectors());
+		tester.test_default_modifier(PCall::get_online_rewards_selectors());
+	});
+}
+
+#[test]
+fn pot_balance_works() {
+	ext_with_funded_pot().execute_with(|| {
+		precompiles()
+			.prepare_test(Alice, precompile_address(), PCall::pot_balance {})
+			.expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())
+			.expect_no_logs()
+			.execute_returns(U256::from(1_000));
+	});
+}
+
+#[test]
+fn register_machine_works() {
+	ext_with_funded_pot().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::register_machine {
+					machine: machine(),
+					name: b"id".into(),
+					value: b"machine".into(),
+				},
+			)
+			.expect_log(log1(
+				precompile_address(),
+				SELECTOR_LOG_MACHINE_REGISTERED,
+				solidity::encode_event_data((Address(Alice.into()), machine())),
+			))
+			.execute_returns(true);
+
+		assert!(PeaqDid::read(&AccountId::from(machine().to_fixed_bytes()), b"id").is_some());
+
+		// The caller owns the machine, so it is entitled to its registration reward.
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_registration_reward { machine: machine() },
+			)
+			.execute_returns(true);
+		assert_eq!(Balances::free_balance(account_of(Alice)), 1_000 + REGISTRATION_REWARD);
+	});
+}
+
+#[test]
+fn register_machine_fails_for_registered_machine() {
+	ext_with_funded_pot().execute_with(|| {
+		register_machine(Bob);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::register_machine {
+					machine: machine(),
+					name: b"id".into(),
+					value: b"machine".into(),
+				},
+			)
+			.execute_reverts(|output| output.starts_with(b"Dispatched call failed with error:"));
+	});
+}
+
+#[test]
+fn get_registration_reward_works() {
+	ext_with_funded_pot().execute_with(|| {
+		register_machine(Alice);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_registration_reward { machine: machine() },
+			)
+			.expect_log(log1(
+				precompile_address(),
+				SELECTOR_LOG_REGISTRATION_REWARD_CLAIMED,
+				solidity::encode_event_data((
+					Address(Alice.into()),
+					machine(),
+					U256::from(REGISTRATION_REWARD),
+				)),
+			))
+			.execute_returns(true);
+
+		assert_eq!(Balances::free_balance(account_of(Alice)), 1_000 + REGISTRATION_REWARD);
+		assert_eq!(Balances::free_balance(pot_account()), 1_000 - REGISTRATION_REWARD);
+	});
+}
+
+#[test]
+fn get_registration_reward_fails_for_unregistered_machine() {
+	ext_with_funded_pot().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_registration_reward { machine: machine() },
+			)
+			.execute_reverts(|output| output.starts_with(b"Dispatched call failed with error:"));
+	});
+}
+
+#[test]
+fn get_online_rewards_fails_for_unregistered_machine() {
+	ext_with_funded_pot().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_online_rewards { machine: machine() },
+			)
+			.execute_reverts(|output| output.starts_with(b"Dispatched call failed with error:"));
+	});
+}
+
+#[test]
+fn events_match_their_signatures() {
+	assert_eq!(MachineRegistered::solidity_event().signature(), MachineRegistered::SIGNATURE);
+	assert_eq!(
+		RegistrationRewardClaimed::solidity_event().signature(),
+		RegistrationRewardClaimed::SIGNATURE
+	);
+	assert_eq!(OnlineRewardsClaimed::solidity_event().signature(), OnlineRewardsClaimed::SIGNATURE);
+}
+
+#[test]
+fn test_solidity_interface_has_all_function_selectors_documented_and_implemented() {
+	check_precompile_implements_solidity_interfaces(&["mor.sol"], PCall::supports_selector)
+}
+
+#[test]
+fn test_solidity_interface_matches_precompile() {
+	check_solidity_interface_matches("mor.sol", &PCall::solidity_interface())
+}
@@ -0,0 +1,120 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Benchmarks of the precompile functions, called through the precompile itself so the weights
+//! include the decoding of the input, the dispatched call and the emitted events.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use precompile_utils::benchmarking::call_precompile;
+use sp_core::H160;
+use sp_std::vec;
+
+/// `frame_benchmarking` attaches benchmarks to a pallet, this one only exists for that purpose.
+pub struct Pallet<T>(PhantomData<T>);
+
+const ITEM_TYPE: &[u8] = b"benchmark item";
+
+fn precompile_address() -> H160 {
+	H160::from_low_u64_be(1)
+}
+
+fn caller() -> H160 {
+	H160::repeat_byte(0x01)
+}
+
+benchmarks! {
+	where_clause { where
+		T: pallet_evm::Config + peaq_pallet_storage::Config,
+		peaq_pallet_storage::Pallet<T>: PeaqStorageT<AccountIdOf<T>>,
+		T::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode + GetCallMetadata,
+		T::RuntimeCall: From<peaq_pallet_storage::Call<T>>,
+		<T::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<AccountIdOf<T>>>,
+		AccountIdOf<T>: From<[u8; 32]> + AsRef<[u8]>,
+	}
+
+	get_item {
+		let account = T::AddressMapping::into_account_id(caller());
+		call_precompile::<PeaqStoragePrecompile<T>>(
+			precompile_address(),
+			caller(),
+			PeaqStoragePrecompileCall::<T>::add_item {
+				item_type: ITEM_TYPE.into(),
+				item: vec![1u8; 2usize.pow(16)].into(),
+			},
+		)?;
+		let input = PeaqStoragePrecompileCall::<T>::get_item {
+			did_account: H256::from_slice(account.as_ref()),
+			name: ITEM_TYPE.into(),
+		};
+	}: {
+		call_precompile::<PeaqStoragePrecompile<T>>(precompile_address(), caller(), input)?;
+	}
+
+	add_item {
+		let s in 1 .. 2u32.pow(16);
+		let account = T::AddressMapping::into_account_id(caller());
+		let input = PeaqStoragePrecompileCall::<T>::add_item {
+			item_type: ITEM_TYPE.into(),
+			item: vec![1u8; s as usize].into(),
+		};
+	}: {
+		call_precompile::<PeaqStoragePrecompile<T>>(precompile_address(), caller(), input)?;
+	}
+	verify {
+		assert_eq!(
+			peaq_pallet_storage::Pallet::<T>::read(&account, &ITEM_TYPE.to_vec()),
+			Some(vec![1u8; s as usize]),
+		);
+	}
+
+	update_item {
+		let s in 1 .. 2u32.pow(16);
+		let account = T::AddressMapping::into_account_id(caller());
+		call_precompile::<PeaqStoragePrecompile<T>>(
+			precompile_address(),
+			caller(),
+			PeaqStoragePrecompileCall::<T>::add_item {
+				item_type: ITEM_TYPE.into(),
+				item: vec![1u8; 2usize.pow(16)].into(),
+			},
+		)?;
+		let input = PeaqStoragePrecompileCall::<T>::update_item {
+			item_type: ITEM_TYPE.into(),
+			item: vec![2u8; s as usize].into(),
+		};
+	}: {
+		call_precompile::<PeaqStoragePrecompile<T>>(precompile_address(), caller(), input)?;
+	}
+	verify {
+		assert_eq!(
+			peaq_pallet_storage::Pallet::<T>::read(&account, &ITEM_TYPE.to_vec()),
+			Some(vec![2u8; s as usize]),
+		);
+	}
+
+	remove_item {
+		let account = T::AddressMapping::into_account_id(caller());
+		call_precompile::<PeaqStoragePrecompile<T>>(
+			precompile_address(),
+			caller(),
+			PeaqStoragePrecompileCall::<T>::add_item {
+				item_type: ITEM_TYPE.into(),
+				item: vec![1u8; 2usize.pow(16)].into(),
+			},
+		)?;
+		let input = PeaqStoragePrecompileCall::<T>::remove_item { item_type: ITEM_TYPE.into() };
+	}: {
+		call_precompile::<PeaqStoragePrecompile<T>>(precompile_address(), caller(), input)?;
+	}
+	verify {
+		assert_eq!(peaq_pallet_storage::Pallet::<T>::read(&account, &ITEM_TYPE.to_vec()), None);
+	}
+}
+
+impl_benchmark_test_suite!(
+	Pallet,
+	crate::mock::ExtBuilder::default().build(),
+	crate::mock::Runtime
+);
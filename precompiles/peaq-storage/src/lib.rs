@@ -4,7 +4,7 @@
 
 // primitives and utils imports
 use frame_support::{
-	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	dispatch::{Dispatchable, GetCallMetadata, GetDispatchInfo, PostDispatchInfo},
 	traits::ConstU32,
 };
 use precompile_utils::prelude::*;
@@ -15,16 +15,48 @@ use fp_evm::PrecompileHandle;
 
 use pallet_evm::AddressMapping;
 
+use key_index::KeyKind;
 use peaq_pallet_storage::traits::Storage as PeaqStorageT;
 
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use weights::WeightInfo;
+
 type AccountIdOf<Runtime> = <Runtime as frame_system::Config>::AccountId;
 
 type GetBytesLimit = ConstU32<{ 2u32.pow(16) }>;
-pub(crate) const SELECTOR_LOG_ITEM_ADDED: [u8; 32] =
-	keccak256!("ItemAdded(address,bytes32,bytes,bytes)");
 
-pub(crate) const SELECTOR_LOG_ITEM_UPDATED: [u8; 32] =
-	keccak256!("ItemUpdated(address,bytes32,bytes,bytes)");
+/// Weights of the precompile functions, estimated until they are benchmarked.
+type WeightOf<Runtime> = weights::SubstrateWeight<Runtime>;
+
+#[precompile::event("ItemAdded(address,bytes32,bytes,bytes)")]
+pub struct ItemAdded {
+	pub sender: Address,
+	pub account: H256,
+	pub item_type: BoundedBytes<GetBytesLimit>,
+	pub item: BoundedBytes<GetBytesLimit>,
+}
+
+#[precompile::event("ItemUpdated(address,bytes32,bytes,bytes)")]
+pub struct ItemUpdated {
+	pub sender: Address,
+	pub account: H256,
+	pub item_type: BoundedBytes<GetBytesLimit>,
+	pub item: BoundedBytes<GetBytesLimit>,
+}
+
+#[precompile::event("ItemRemoved(address,bytes32,bytes)")]
+pub struct ItemRemoved {
+	pub sender: Address,
+	pub account: H256,
+	pub item_type: BoundedBytes<GetBytesLimit>,
+}
 
 pub struct PeaqStoragePrecompile<Runtime>(PhantomData<Runtime>);
 
@@ -34,11 +66,16 @@ pub struct PeaqStoragePrecompile<Runtime>(PhantomData<Runtime>);
 const PEAQ_STORAGE_KEY_SIZE: u64 = 80;
 
 #[precompile_utils::precompile]
+#[precompile::events(ItemAdded, ItemUpdated, ItemRemoved)]
 impl<Runtime> PeaqStoragePrecompile<Runtime>
 where
-	Runtime: pallet_evm::Config + peaq_pallet_storage::Config + frame_system::pallet::Config,
+	Runtime: pallet_evm::Config
+		+ peaq_pallet_storage::Config
+		+ frame_system::pallet::Config
+		+ key_index::Config,
 	peaq_pallet_storage::Pallet<Runtime>: PeaqStorageT<AccountIdOf<Runtime>>,
-	Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode,
+	Runtime::RuntimeCall:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode + GetCallMetadata,
 	Runtime::RuntimeCall: From<peaq_pallet_storage::Call<Runtime>>,
 	<Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<AccountIdOf<Runtime>>>,
 	AccountIdOf<Runtime>: From<[u8; 32]> + AsRef<[u8]>,
@@ -50,7 +87,7 @@ where
 		did_account: H256,
 		name: BoundedBytes<GetBytesLimit>,
 	) -> EvmResult<UnboundedBytes> {
-		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		RuntimeHelper::<Runtime>::record_weight_cost(handle, WeightOf::<Runtime>::get_item())?;
 		let did_account = AccountIdOf::<Runtime>::from(did_account.to_fixed_bytes());
 		match peaq_pallet_storage::Pallet::<Runtime>::read(&did_account, &Vec::<u8>::from(name)) {
 			Some(v) => Ok(v.into()),
@@ -58,39 +95,66 @@ where
 		}
 	}
 
+	/// Item types are listed from the key index, in no particular order. Items added before the
+	/// index was deployed are not listed.
+	#[precompile::public("get_item_types(bytes32,uint32,uint32)")]
+	#[precompile::view]
+	fn get_item_types(
+		handle: &mut impl PrecompileHandle,
+		account: H256,
+		offset: u32,
+		limit: u32,
+	) -> EvmResult<Vec<UnboundedBytes>> {
+		// The item types before the page are read from the index too.
+		handle.record_cost(
+			RuntimeHelper::<Runtime>::db_read_gas_cost().saturating_mul(offset.into()),
+		)?;
+		let account = AccountIdOf::<Runtime>::from(account.to_fixed_bytes());
+
+		let mut item_types = Vec::new();
+		let keys = key_index::Pallet::<Runtime>::keys(KeyKind::StorageItem, account)
+			.skip(offset as usize)
+			.take(limit as usize);
+		for item_type in keys {
+			handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+			item_types.push(item_type.into());
+		}
+		Ok(item_types)
+	}
+
 	#[precompile::public("add_item(bytes,bytes)")]
 	fn add_item(
 		handle: &mut impl PrecompileHandle,
 		item_type: BoundedBytes<GetBytesLimit>,
 		item: BoundedBytes<GetBytesLimit>,
 	) -> EvmResult<bool> {
-		handle.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost())?;
-
 		let caller: AccountIdOf<Runtime> =
 			Runtime::AddressMapping::into_account_id(handle.context().caller);
 
+		let call = peaq_pallet_storage::Call::<Runtime>::add_item {
+			item_type: item_type.as_bytes().to_vec(),
+			item: item.as_bytes().to_vec(),
+		};
+		RuntimeHelper::<Runtime>::record_weight_cost_without_call(
+			handle,
+			WeightOf::<Runtime>::add_item(item.as_bytes().len() as u32),
+			&call,
+		)?;
+
 		RuntimeHelper::<Runtime>::try_dispatch(
 			handle,
 			Some(caller.clone()).into(),
-			peaq_pallet_storage::Call::<Runtime>::add_item {
-				item_type: item_type.as_bytes().to_vec(),
-				item: item.as_bytes().to_vec(),
-			},
+			call,
 			PEAQ_STORAGE_KEY_SIZE + item.as_bytes().len() as u64,
 		)?;
-		log::error!("add_item after dispatch");
-
-		let event = log1(
-			handle.context().address,
-			SELECTOR_LOG_ITEM_ADDED,
-			solidity::encode_event_data((
-				Address::from(handle.context().caller),
-				H256::from_slice(caller.as_ref()),
-				item_type,
-				item,
-			)),
-		);
-		event.record(handle)?;
+
+		ItemAdded {
+			sender: Address::from(handle.context().caller),
+			account: H256::from_slice(caller.as_ref()),
+			item_type,
+			item,
+		}
+		.record(handle)?;
 
 		Ok(true)
 	}
@@ -101,32 +165,62 @@ where
 		item_type: BoundedBytes<GetBytesLimit>,
 		item: BoundedBytes<GetBytesLimit>,
 	) -> EvmResult<bool> {
-		handle.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost())?;
-
 		let caller: AccountIdOf<Runtime> =
 			Runtime::AddressMapping::into_account_id(handle.context().caller);
 
+		let call = peaq_pallet_storage::Call::<Runtime>::update_item {
+			item_type: item_type.as_bytes().to_vec(),
+			item: item.as_bytes().to_vec(),
+		};
+		RuntimeHelper::<Runtime>::record_weight_cost_without_call(
+			handle,
+			WeightOf::<Runtime>::update_item(item.as_bytes().len() as u32),
+			&call,
+		)?;
+
 		RuntimeHelper::<Runtime>::try_dispatch(
 			handle,
 			Some(caller.clone()).into(),
-			peaq_pallet_storage::Call::<Runtime>::update_item {
-				item_type: item_type.as_bytes().to_vec(),
-				item: item.as_bytes().to_vec(),
-			},
+			call,
 			item.as_bytes().len() as u64,
 		)?;
 
-		let event = log1(
-			handle.context().address,
-			SELECTOR_LOG_ITEM_UPDATED,
-			solidity::encode_event_data((
-				Address::from(handle.context().caller),
-				H256::from_slice(caller.as_ref()),
-				item_type,
-				item,
-			)),
-		);
-		event.record(handle)?;
+		ItemUpdated {
+			sender: Address::from(handle.context().caller),
+			account: H256::from_slice(caller.as_ref()),
+			item_type,
+			item,
+		}
+		.record(handle)?;
+
+		Ok(true)
+	}
+
+	#[precompile::public("remove_item(bytes)")]
+	fn remove_item(
+		handle: &mut impl PrecompileHandle,
+		item_type: BoundedBytes<GetBytesLimit>,
+	) -> EvmResult<bool> {
+		let caller: AccountIdOf<Runtime> =
+			Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let call = peaq_pallet_storage::Call::<Runtime>::remove_item {
+			item_type: item_type.as_bytes().to_vec(),
+		};
+		RuntimeHelper::<Runtime>::record_weight_cost_without_call(
+			handle,
+			WeightOf::<Runtime>::remove_item(),
+			&call,
+		)?;
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(caller.clone()).into(), call, 0)?;
+
+		ItemRemoved {
+			sender: Address::from(handle.context().caller),
+			account: H256::from_slice(caller.as_ref()),
+			item_type,
+		}
+		.record(handle)?;
 
 		Ok(true)
 	}
@@ -0,0 +1,293 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use frame_support::traits::Hooks;
+use precompile_utils::testing::*;
+use sha3::{Digest, Keccak256};
+
+use crate::{mock::*, *};
+use pallet_evm::GasWeightMapping;
+
+fn precompile_address() -> H160 {
+	H160::from_low_u64_be(1)
+}
+
+fn account_hash(address: impl Into<H160>) -> H256 {
+	H256::from_slice(account_of(address).as_ref())
+}
+
+fn add_item(item: &str) {
+	precompiles()
+		.prepare_test(
+			Alice,
+			precompile_address(),
+			PCall::add_item { item_type: "type".into(), item: item.into() },
+		)
+		.execute_returns(true);
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::get_item_selectors().contains(&0x0186bb7d));
+	assert!(PCall::add_item_selectors().contains(&0x2f3f5cdd));
+	assert!(PCall::update_item_selectors().contains(&0xbaf2077a));
+	assert!(PCall::remove_item_selectors().contains(&0xfb4b3ebb));
+	assert!(PCall::get_item_types_selectors().contains(&0x697aa429));
+
+	assert_eq!(
+		crate::ItemAdded::TOPIC,
+		&Keccak256::digest(b"ItemAdded(address,bytes32,bytes,bytes)")[..]
+	);
+	assert_eq!(
+		crate::ItemUpdated::TOPIC,
+		&Keccak256::digest(b"ItemUpdated(address,bytes32,bytes,bytes)")[..]
+	);
+	assert_eq!(
+		crate::ItemRemoved::TOPIC,
+		&Keccak256::digest(b"ItemRemoved(address,bytes32,bytes)")[..]
+	);
+}
+
+#[test]
+fn modifiers() {
+	ExtBuilder::default()
+		.with_balances(vec![(account_of(Alice), 1000)])
+		.build()
+		.execute_with(|| {
+			let mut tester =
+				PrecompilesModifierTester::new(precompiles(), Alice, precompile_address());
+
+			tester.test_view_modifier(PCall::get_item_selectors());
+			tester.test_view_modifier(PCall::get_item_types_selectors());
+			tester.test_default_modifier(PCall::add_item_selectors());
+			tester.test_default_modifier(PCall::update_item_selectors());
+			tester.test_default_modifier(PCall::remove_item_selectors());
+		});
+}
+
+#[test]
+fn add_item_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::add_item { item_type: "type".into(), item: "item".into() },
+			)
+			.expect_log(log1(
+				precompile_address(),
+				ItemAdded::TOPIC,
+				solidity::encode_event_data((
+					Address(Alice.into()),
+					account_hash(Alice),
+					UnboundedBytes::from("type"),
+					UnboundedBytes::from("item"),
+				)),
+			))
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::get_item { did_account: account_hash(Alice), name: "type".into() },
+			)
+			.expect_cost(<Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(
+				WeightOf::<Runtime>::get_item(),
+			))
+			.expect_no_logs()
+			.execute_returns(UnboundedBytes::from("item"));
+	});
+}
+
+#[test]
+fn add_item_weight_grows_with_item_size() {
+	assert!(
+		WeightOf::<Runtime>::add_item(1_000).ref_time() >
+			WeightOf::<Runtime>::add_item(10).ref_time()
+	);
+	assert!(
+		WeightOf::<Runtime>::update_item(1_000).ref_time() >
+			WeightOf::<Runtime>::update_item(10).ref_time()
+	);
+}
+
+#[test]
+fn get_item_fails_for_missing_item() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_item { did_account: account_hash(Alice), name: "type".into() },
+			)
+			.execute_reverts(|output| output == b"Cannot find the item");
+	});
+}
+
+#[test]
+fn update_item_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		add_item("item");
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::update_item { item_type: "type".into(), item: "other".into() },
+			)
+			.expect_log(log1(
+				precompile_address(),
+				ItemUpdated::TOPIC,
+				solidity::encode_event_data((
+					Address(Alice.into()),
+					account_hash(Alice),
+					UnboundedBytes::from("type"),
+					UnboundedBytes::from("other"),
+				)),
+			))
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_item { did_account: account_hash(Alice), name: "type".into() },
+			)
+			.execute_returns(UnboundedBytes::from("other"));
+	});
+}
+
+#[test]
+fn remove_item_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		add_item("item");
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::remove_item { item_type: "type".into() },
+			)
+			.expect_log(log1(
+				precompile_address(),
+				ItemRemoved::TOPIC,
+				solidity::encode_event_data((
+					Address(Alice.into()),
+					account_hash(Alice),
+					UnboundedBytes::from("type"),
+				)),
+			))
+			.execute_returns(true);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_item { did_account: account_hash(Alice), name: "type".into() },
+			)
+			.execute_reverts(|output| output == b"Cannot find the item");
+	});
+}
+
+#[test]
+fn remove_item_fails_for_missing_item() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::remove_item { item_type: "type".into() },
+			)
+			.execute_reverts(|output| output.starts_with(b"Dispatched call failed with error:"));
+	});
+}
+
+fn add_item_of_type(item_type: &str) {
+	precompiles()
+		.prepare_test(
+			Alice,
+			precompile_address(),
+			PCall::add_item { item_type: item_type.into(), item: "item".into() },
+		)
+		.execute_returns(true);
+}
+
+fn indexed_item_types() -> Vec<UnboundedBytes> {
+	key_index::Pallet::<Runtime>::page(KeyKind::StorageItem, account_of(Alice), 0, u32::MAX)
+		.into_iter()
+		.map(Into::into)
+		.collect()
+}
+
+#[test]
+fn get_item_types_pages_the_indexed_item_types() {
+	ExtBuilder::default().build().execute_with(|| {
+		add_item_of_type("a");
+		add_item_of_type("b");
+		add_item_of_type("c");
+		KeyIndex::on_finalize(System::block_number());
+
+		let item_types = indexed_item_types();
+		assert_eq!(item_types.len(), 3);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::get_item_types { account: account_hash(Alice), offset: 0, limit: 2 },
+			)
+			.expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost() * 2)
+			.expect_no_logs()
+			.execute_returns(item_types[..2].to_vec());
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::get_item_types { account: account_hash(Alice), offset: 2, limit: 2 },
+			)
+			.expect_cost(RuntimeHelper::<Runtime>::db_read_gas_cost() * 3)
+			.execute_returns(item_types[2..].to_vec());
+	});
+}
+
+#[test]
+fn get_item_types_skips_removed_items() {
+	ExtBuilder::default().build().execute_with(|| {
+		add_item_of_type("a");
+		add_item_of_type("b");
+		KeyIndex::on_finalize(System::block_number());
+
+		precompiles()
+			.prepare_test(Alice, precompile_address(), PCall::remove_item { item_type: "a".into() })
+			.execute_returns(true);
+		KeyIndex::on_finalize(System::block_number());
+
+		assert_eq!(indexed_item_types(), vec![UnboundedBytes::from("b")]);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::get_item_types { account: account_hash(Alice), offset: 0, limit: 10 },
+			)
+			.execute_returns(vec![UnboundedBytes::from("b")]);
+	});
+}
+
+#[test]
+fn events_match_their_signatures() {
+	assert_eq!(ItemAdded::solidity_event().signature(), ItemAdded::SIGNATURE);
+	assert_eq!(ItemUpdated::solidity_event().signature(), ItemUpdated::SIGNATURE);
+	assert_eq!(ItemRemoved::solidity_event().signature(), ItemRemoved::SIGNATURE);
+}
+
+#[test]
+fn test_solidity_interface_has_all_function_selectors_documented_and_implemented() {
+	check_precompile_implements_solidity_interfaces(&["storage.sol"], PCall::supports_selector)
+}
+
+#[test]
+fn test_solidity_interface_matches_precompile() {
+	check_solidity_interface_matches("storage.sol", &PCall::solidity_interface())
+}
@@ -0,0 +1,78 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Weights for pallet_evm_precompile_peaq_storage
+//!
+//! Hand-written estimates based on the previous costs of the precompile (one DB access and 16
+//! gas per item byte). They are not benchmark output and should be regenerated from
+//! `benchmarking.rs` with `benchmark pallet` once reference hardware results are available.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_evm_precompile_peaq_storage.
+/// Each function is the weight of the whole precompile call, including the dispatched call.
+pub trait WeightInfo {
+	fn get_item() -> Weight;
+	fn add_item(s: u32, ) -> Weight;
+	fn update_item(s: u32, ) -> Weight;
+	fn remove_item() -> Weight;
+}
+
+/// Hand-written weights for pallet_evm_precompile_peaq_storage.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn get_item() -> Weight {
+		Weight::from_parts(0, 3_593)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+	}
+	/// The range of component `s` is `[1, 65536]`.
+	fn add_item(s: u32, ) -> Weight {
+		Weight::from_parts(0, 3_593)
+			.saturating_add(Weight::from_parts(400_000, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// The range of component `s` is `[1, 65536]`.
+	fn update_item(s: u32, ) -> Weight {
+		Weight::from_parts(0, 3_593)
+			.saturating_add(Weight::from_parts(400_000, 0).saturating_mul(s.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	fn remove_item() -> Weight {
+		Weight::from_parts(0, 3_593)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn get_item() -> Weight {
+		Weight::from_parts(0, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+	}
+	/// The range of component `s` is `[1, 65536]`.
+	fn add_item(s: u32, ) -> Weight {
+		Weight::from_parts(0, 3_593)
+			.saturating_add(Weight::from_parts(400_000, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// The range of component `s` is `[1, 65536]`.
+	fn update_item(s: u32, ) -> Weight {
+		Weight::from_parts(0, 3_593)
+			.saturating_add(Weight::from_parts(400_000, 0).saturating_mul(s.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	fn remove_item() -> Weight {
+		Weight::from_parts(0, 3_593)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
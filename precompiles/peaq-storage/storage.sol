@@ -8,21 +8,39 @@ Storage constant Storage_CONTRACT = Storage(PRECOMPILE_ADDR);
 
 interface Storage {
 
+    /// @custom:selector 0186bb7d
     function get_item(
         bytes32 account,
         bytes memory item_type
     ) external view returns (bytes memory);
 
+    /// @dev Returns `limit` item types of an account from the `offset`-th one, in no particular
+    /// order. Items added before the item types were indexed are not listed.
+    /// @custom:selector 697aa429
+    function get_item_types(
+        bytes32 account,
+        uint32 offset,
+        uint32 limit
+    ) external view returns (bytes[] memory);
+
+    /// @custom:selector 2f3f5cdd
     function add_item(
         bytes memory item_type,
         bytes memory item
     ) external returns (bool);
 
+    /// @custom:selector baf2077a
     function update_item(
         bytes memory item_type,
         bytes memory item
     ) external returns (bool);
 
+    /// @dev Removes an item of the caller.
+    /// @custom:selector fb4b3ebb
+    function remove_item(
+        bytes memory item_type
+    ) external returns (bool);
+
     event ItemAdded(
         address sender,
         bytes32 account,
@@ -35,4 +53,9 @@ interface Storage {
         bytes item_type,
         bytes item
     );
+    event ItemRemoved(
+        address sender,
+        bytes32 account,
+        bytes item_type
+    );
 }
@@ -25,20 +25,19 @@ use syn::{
 pub fn main(input: TokenStream) -> TokenStream {
 	let DeriveInput { ident, mut generics, data, .. } = parse_macro_input!(input as DeriveInput);
 
-	let syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), .. }) = data else {
-		return quote_spanned! { ident.span() =>
-			compile_error!("Codec can only be derived for structs with named fields");
-		}
-		.into()
+	let fields = match data {
+		syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(fields), .. }) =>
+			fields.named,
+		// Empty structs are encoded like the empty tuple.
+		syn::Data::Struct(syn::DataStruct { fields, .. }) if fields.is_empty() => Punctuated::new(),
+		syn::Data::Enum(syn::DataEnum { variants, .. }) =>
+			return derive_for_enum(ident, generics, variants),
+		_ =>
+			return quote_spanned! { ident.span() =>
+				compile_error!("Codec can only be derived for structs with named fields");
+			}
+			.into(),
 	};
-	let fields = fields.named;
-
-	if fields.is_empty() {
-		return quote_spanned! { ident.span() =>
-			compile_error!("Codec can only be derived for structs with at least one field");
-		}
-		.into()
-	}
 
 	if let Some(unamed_field) = fields.iter().find(|f| f.ident.is_none()) {
 		return quote_spanned! { unamed_field.ty.span() =>
@@ -87,32 +86,124 @@ pub fn main(input: TokenStream) -> TokenStream {
 		);
 	}
 
+	// Fields are read and written one by one like tuples do, which avoids being limited by the
+	// maximum size of tuples implementing `Codec`.
 	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 	quote! {
 		impl #impl_generics ::precompile_utils::solidity::codec::Codec for #ident #ty_generics
 		#where_clause {
+			#[allow(unused_variables)]
 			fn read(
 				reader: &mut ::precompile_utils::solidity::codec::Reader
 			) -> ::precompile_utils::solidity::revert::MayRevert<Self> {
-				use ::precompile_utils::solidity::revert::BacktraceExt as _;
-				let (#(#fields_ident,)*): (#(#fields_ty,)*) = reader
-					.read()
-					.map_in_tuple_to_field(&[#(#fields_name_lit),*])?;
+				use ::precompile_utils::solidity::revert::InjectBacktrace as _;
+
+				let mut inner_reader;
+				let reader = if <Self as ::precompile_utils::solidity::codec::Codec>::has_static_size() {
+					reader
+				} else {
+					inner_reader = reader.read_pointer()?;
+					&mut inner_reader
+				};
+
 				Ok(Self {
-					#(#fields_ident,)*
+					#(#fields_ident: reader.read::<#fields_ty>().in_field(#fields_name_lit)?,)*
 				})
 			}
 
+			#[allow(unused_variables)]
+			fn write(writer: &mut ::precompile_utils::solidity::codec::Writer, value: Self) {
+				use ::precompile_utils::solidity::codec::{Codec, Writer};
+
+				if <Self as Codec>::has_static_size() {
+					#(<#fields_ty as Codec>::write(writer, value.#fields_ident);)*
+				} else {
+					let mut inner_writer = Writer::new();
+					#(<#fields_ty as Codec>::write(&mut inner_writer, value.#fields_ident);)*
+					writer.write_pointer(inner_writer.build());
+				}
+			}
+
+			fn has_static_size() -> bool {
+				true #(&& <#fields_ty as ::precompile_utils::solidity::codec::Codec>::has_static_size())*
+			}
+
+			fn signature() -> ::precompile_utils::solidity::codec::String {
+				let subtypes: &[::precompile_utils::solidity::codec::String] = &[
+					#(<#fields_ty as ::precompile_utils::solidity::codec::Codec>::signature()),*
+				];
+
+				let mut signature = ::precompile_utils::solidity::codec::String::from("(");
+				signature.push_str(&subtypes.join(","));
+				signature.push(')');
+				signature
+			}
+		}
+	}
+	.into()
+}
+
+/// Enums with only unit variants are encoded like Solidity enums, which is as an `uint8` of the
+/// index of the variant.
+fn derive_for_enum(
+	ident: Ident,
+	generics: syn::Generics,
+	variants: Punctuated<syn::Variant, syn::token::Comma>,
+) -> TokenStream {
+	if let Some(variant) = variants.iter().find(|v| !matches!(v.fields, syn::Fields::Unit)) {
+		return quote_spanned! { variant.fields.span() =>
+			compile_error!("Codec can only be derived for enums with unit variants");
+		}
+		.into()
+	}
+
+	if let Some(variant) = variants.iter().find(|v| v.discriminant.is_some()) {
+		return quote_spanned! { variant.ident.span() =>
+			compile_error!("Codec cannot be derived for enums with explicit discriminants, \
+				variants are encoded by their index like in Solidity");
+		}
+		.into()
+	}
+
+	if variants.is_empty() || variants.len() > 256 {
+		return quote_spanned! { ident.span() =>
+			compile_error!("Codec can only be derived for enums with 1 to 256 variants");
+		}
+		.into()
+	}
+
+	let variants_ident: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+	let indices: Vec<_> = (0..variants.len()).map(|i| i as u8).collect();
+
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+	quote! {
+		impl #impl_generics ::precompile_utils::solidity::codec::Codec for #ident #ty_generics
+		#where_clause {
+			fn read(
+				reader: &mut ::precompile_utils::solidity::codec::Reader
+			) -> ::precompile_utils::solidity::revert::MayRevert<Self> {
+				match reader.read::<u8>()? {
+					#(#indices => Ok(Self::#variants_ident),)*
+					#[allow(unreachable_patterns)]
+					_ => Err(::precompile_utils::solidity::revert::RevertReason::value_is_too_large(
+						stringify!(#ident)
+					).into()),
+				}
+			}
+
 			fn write(writer: &mut ::precompile_utils::solidity::codec::Writer, value: Self) {
-				::precompile_utils::solidity::codec::Codec::write(writer, (#(value.#fields_ident,)*));
+				let index: u8 = match value {
+					#(Self::#variants_ident => #indices,)*
+				};
+				::precompile_utils::solidity::codec::Codec::write(writer, index);
 			}
 
 			fn has_static_size() -> bool {
-				<(#(#fields_ty,)*)>::has_static_size()
+				true
 			}
 
-			fn signature() -> String {
-				<(#(#fields_ty,)*)>::signature()
+			fn signature() -> ::precompile_utils::solidity::codec::String {
+				::precompile_utils::solidity::codec::String::from("uint8")
 			}
 		}
 	}
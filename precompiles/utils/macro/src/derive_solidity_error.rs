@@ -0,0 +1,118 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::{parse_macro_input, spanned::Spanned, DeriveInput, LitStr};
+
+pub fn main(input: TokenStream) -> TokenStream {
+	let DeriveInput { ident, generics, data, .. } = parse_macro_input!(input as DeriveInput);
+
+	let syn::Data::Enum(syn::DataEnum { variants, .. }) = data else {
+		return quote_spanned! { ident.span() =>
+			compile_error!("SolidityError can only be derived for enums");
+		}
+		.into()
+	};
+
+	if variants.is_empty() {
+		return quote_spanned! { ident.span() =>
+			compile_error!("SolidityError can only be derived for enums with at least one variant");
+		}
+		.into()
+	}
+
+	if let Some(param) = generics.params.first() {
+		return quote_spanned! { param.span() =>
+			compile_error!("SolidityError cannot be derived for enums with type parameters");
+		}
+		.into()
+	}
+
+	let mut signature_arms = vec![];
+	let mut encode_arms = vec![];
+	let mut decode_checks = vec![];
+
+	for variant in &variants {
+		let variant_ident = &variant.ident;
+		let name = LitStr::new(&variant_ident.to_string(), variant_ident.span());
+		let fields_ty: Vec<_> = variant.fields.iter().map(|f| &f.ty).collect();
+
+		// Bindings used to destructure the variant, which are the field names for named fields.
+		let bindings: Vec<_> = variant
+			.fields
+			.iter()
+			.enumerate()
+			.map(|(i, f)| f.ident.clone().unwrap_or_else(|| format_ident!("field{}", i)))
+			.collect();
+
+		let signature = quote!(
+			::precompile_utils::solidity::revert::custom_error_signature(
+				#name,
+				&[#(<#fields_ty as ::precompile_utils::solidity::Codec>::signature()),*],
+			)
+		);
+
+		let pattern = match &variant.fields {
+			syn::Fields::Named(_) => quote!(Self::#variant_ident { #(#bindings),* }),
+			syn::Fields::Unnamed(_) => quote!(Self::#variant_ident ( #(#bindings),* )),
+			syn::Fields::Unit => quote!(Self::#variant_ident),
+		};
+
+		let read_fields = fields_ty.iter().map(|ty| quote!(reader.read::<#ty>().ok()?));
+		let constructor = match &variant.fields {
+			syn::Fields::Named(_) => quote!(Self::#variant_ident { #(#bindings: #read_fields),* }),
+			syn::Fields::Unnamed(_) => quote!(Self::#variant_ident ( #(#read_fields),* )),
+			syn::Fields::Unit => quote!(Self::#variant_ident),
+		};
+
+		signature_arms.push(quote!(Self::#variant_ident { .. } => #signature,));
+		encode_arms.push(quote!(
+			#pattern => Writer::new_with_selector(selector) #(.write(#bindings))* .build(),
+		));
+		decode_checks.push(quote!(
+			if selector == ::precompile_utils::solidity::revert::custom_error_selector(&#signature) {
+				return Some(#constructor)
+			}
+		));
+	}
+
+	quote!(
+		impl ::precompile_utils::solidity::revert::SolidityError for #ident {
+			fn signature(&self) -> ::precompile_utils::solidity::codec::String {
+				match self {
+					#(#signature_arms)*
+				}
+			}
+
+			fn encode(self) -> ::sp_std::vec::Vec<u8> {
+				use ::precompile_utils::solidity::codec::Writer;
+
+				let selector =
+					<Self as ::precompile_utils::solidity::revert::SolidityError>::selector(&self);
+				match self {
+					#(#encode_arms)*
+				}
+			}
+
+			#[allow(unused_mut, unused_variables)]
+			fn decode(output: &[u8]) -> Option<Self> {
+				let selector = ::precompile_utils::solidity::codec::selector(output)?;
+				let mut reader = ::precompile_utils::solidity::codec::Reader::new(&output[4..]);
+
+				#(#decode_checks)*
+
+				None
+			}
+		}
+
+		impl From<#ident> for ::fp_evm::PrecompileFailure {
+			fn from(error: #ident) -> Self {
+				::fp_evm::PrecompileFailure::Revert {
+					exit_status: ::fp_evm::ExitRevert::Reverted,
+					output: ::precompile_utils::solidity::revert::SolidityError::encode(error),
+				}
+			}
+		}
+	)
+	.into()
+}
@@ -0,0 +1,204 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use super::*;
+use proc_macro2::Span;
+
+/// Parameter of the event signature provided in the attribute.
+struct EventParam {
+	/// Solidity type of the parameter.
+	solidity_type: String,
+	/// If the parameter is stored in a topic instead of the data.
+	indexed: bool,
+}
+
+pub fn main(attr: TokenStream, input: TokenStream) -> TokenStream {
+	let signature_lit = parse_macro_input!(attr as LitStr);
+	let item = parse_macro_input!(input as syn::ItemStruct);
+
+	match expand(signature_lit, item) {
+		Ok(output) => output.into(),
+		Err(e) => e.into_compile_error().into(),
+	}
+}
+
+fn expand(signature_lit: LitStr, item: syn::ItemStruct) -> syn::Result<proc_macro2::TokenStream> {
+	let (name, params) = parse_signature(&signature_lit)?;
+
+	if let Some(param) = item.generics.params.first() {
+		let msg = "Events cannot have type parameters";
+		return Err(syn::Error::new(param.span(), msg))
+	}
+
+	let fields: Vec<_> = match &item.fields {
+		syn::Fields::Named(fields) => fields.named.iter().collect(),
+		syn::Fields::Unit => vec![],
+		syn::Fields::Unnamed(fields) => {
+			let msg = "Events must be structs with named fields";
+			return Err(syn::Error::new(fields.span(), msg))
+		},
+	};
+
+	if fields.len() != params.len() {
+		let msg = format!(
+			"The event signature has {} parameters but the struct has {} fields",
+			params.len(),
+			fields.len()
+		);
+		return Err(syn::Error::new(item.ident.span(), msg))
+	}
+
+	// Topic 0 is the event selector, which leaves 3 topics for indexed parameters.
+	if params.iter().filter(|param| param.indexed).count() > 3 {
+		let msg = "An event can have at most 3 indexed parameters";
+		return Err(syn::Error::new(signature_lit.span(), msg))
+	}
+
+	for param in params.iter().filter(|param| param.indexed) {
+		if param.solidity_type.contains('(') || param.solidity_type.contains('[') {
+			let msg = "Indexed arrays and tuples are not supported, only value types, `bytes` \
+			and `string` can be indexed";
+			return Err(syn::Error::new(signature_lit.span(), msg))
+		}
+	}
+
+	let ident = &item.ident;
+	let canonical_signature = format!(
+		"{}({})",
+		name,
+		params
+			.iter()
+			.map(|param| param.solidity_type.as_str())
+			.collect::<Vec<_>>()
+			.join(",")
+	);
+	let topic = Keccak256::digest(canonical_signature.as_bytes()).to_vec();
+
+	let field_ident = |field: &syn::Field| field.ident.clone().expect("fields are named; qed");
+
+	let indexed_fields: Vec<_> = fields
+		.iter()
+		.zip(params.iter())
+		.filter(|(_, param)| param.indexed)
+		.map(|(field, _)| field_ident(field))
+		.collect();
+	let data_fields: Vec<_> = fields
+		.iter()
+		.zip(params.iter())
+		.filter(|(_, param)| !param.indexed)
+		.map(|(field, _)| field_ident(field))
+		.collect();
+
+	let interface_inputs = fields.iter().zip(params.iter()).map(|(field, param)| {
+		let ty = &field.ty;
+		let name = field_ident(field).to_string();
+		let indexed = param.indexed;
+		quote!(.input::<#ty>(#name, #indexed))
+	});
+
+	let name_lit = LitStr::new(&name, Span::call_site());
+
+	Ok(quote!(
+		#item
+
+		impl #ident {
+			/// Canonical Solidity signature of the event, as given in the attribute. Tests check it
+			/// matches the signature of `solidity_event`, which follows the types of the fields.
+			pub const SIGNATURE: &'static str = #canonical_signature;
+
+			/// Topic of the event, which is the hash of its signature.
+			pub const TOPIC: [u8; 32] = [#(#topic),*];
+
+			/// Build the log of this event as emitted by `address`.
+			#[must_use]
+			pub fn into_log(self, address: impl Into<::sp_core::H160>) -> ::fp_evm::Log {
+				use ::precompile_utils::solidity::codec::Writer;
+
+				::fp_evm::Log {
+					address: address.into(),
+					topics: ::sp_std::vec![
+						::sp_core::H256(Self::TOPIC),
+						#(
+							::precompile_utils::evm::logs::indexed_topic(self.#indexed_fields),
+						)*
+					],
+					data: Writer::new()
+						#(.write(self.#data_fields))*
+						.build(),
+				}
+			}
+
+			/// Charge the cost of the log then record it as emitted by the precompile.
+			pub fn record(
+				self,
+				handle: &mut impl ::fp_evm::PrecompileHandle,
+			) -> ::precompile_utils::EvmResult {
+				use ::precompile_utils::evm::{handle::PrecompileHandleExt, logs::LogExt};
+
+				let log = self.into_log(handle.context().address);
+				handle.record_log_costs(&[&log])?;
+				log.record(handle)
+			}
+
+			/// Description of the event in the Solidity interface of the precompile.
+			pub fn solidity_event() -> ::precompile_utils::solidity::interface::InterfaceEvent {
+				::precompile_utils::solidity::interface::InterfaceEvent::new(#name_lit)
+				#(#interface_inputs)*
+			}
+		}
+	))
+}
+
+/// Parse a signature of the form `Name(type1 indexed,type2,...)`.
+fn parse_signature(signature_lit: &LitStr) -> syn::Result<(String, Vec<EventParam>)> {
+	let signature = signature_lit.value();
+	let msg = "Event signature must have form \"Name(type1 indexed,type2,...)\"";
+
+	let (name, rest) = signature
+		.split_once('(')
+		.ok_or_else(|| syn::Error::new(signature_lit.span(), msg))?;
+	let params = rest
+		.strip_suffix(')')
+		.ok_or_else(|| syn::Error::new(signature_lit.span(), msg))?;
+
+	let name = name.trim();
+	if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+		return Err(syn::Error::new(signature_lit.span(), msg))
+	}
+
+	if params.trim().is_empty() {
+		return Ok((name.to_string(), vec![]))
+	}
+
+	let mut parsed = vec![];
+	let mut depth = 0usize;
+	let mut start = 0;
+	let mut parts = vec![];
+	for (index, c) in params.char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' => depth = depth.saturating_sub(1),
+			',' if depth == 0 => {
+				parts.push(&params[start..index]);
+				start = index + 1;
+			},
+			_ => (),
+		}
+	}
+	parts.push(&params[start..]);
+
+	for part in parts {
+		let mut words = part.split_whitespace();
+		let solidity_type = match words.next() {
+			Some(ty) => ty.to_string(),
+			None => return Err(syn::Error::new(signature_lit.span(), msg)),
+		};
+		let indexed = match (words.next(), words.next()) {
+			(None, _) => false,
+			(Some("indexed"), None) => true,
+			_ => return Err(syn::Error::new(signature_lit.span(), msg)),
+		};
+		parsed.push(EventParam { solidity_type, indexed });
+	}
+
+	Ok((name.to_string(), parsed))
+}
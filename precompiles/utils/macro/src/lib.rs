@@ -23,6 +23,8 @@ use sha3::{Digest, Keccak256};
 use syn::{parse_macro_input, spanned::Spanned, Expr, Ident, ItemType, Lit, LitStr};
 
 mod derive_codec;
+mod derive_solidity_error;
+mod event;
 mod precompile;
 mod precompile_name_from_address;
 
@@ -62,6 +64,11 @@ pub fn precompile(attr: TokenStream, input: TokenStream) -> TokenStream {
 	precompile::main(attr, input)
 }
 
+#[proc_macro_attribute]
+pub fn event(attr: TokenStream, input: TokenStream) -> TokenStream {
+	event::main(attr, input)
+}
+
 #[proc_macro_attribute]
 pub fn precompile_name_from_address(attr: TokenStream, input: TokenStream) -> TokenStream {
 	precompile_name_from_address::main(attr, input)
@@ -71,3 +78,8 @@ pub fn precompile_name_from_address(attr: TokenStream, input: TokenStream) -> To
 pub fn derive_codec(input: TokenStream) -> TokenStream {
 	derive_codec::main(input)
 }
+
+#[proc_macro_derive(SolidityError)]
+pub fn derive_solidity_error(input: TokenStream) -> TokenStream {
+	derive_solidity_error::main(input)
+}
@@ -173,6 +173,7 @@ impl Precompile {
 
 		let parse_call_data_fn = self.expand_enum_parse_call_data();
 		let execute_fn = self.expand_enum_execute_fn();
+		let solidity_interface_fn = self.expand_solidity_interface_fn();
 
 		quote!(
 			impl #impl_generics #enum_ident #ty_generics #where_clause {
@@ -205,6 +206,8 @@ impl Precompile {
 					}
 				)*
 
+				#solidity_interface_fn
+
 				pub fn encode(self) -> ::sp_std::vec::Vec<u8> {
 					use ::precompile_utils::solidity::codec::Writer;
 					match self {
@@ -314,6 +317,75 @@ impl Precompile {
 		}
 	}
 
+	/// Expand the function describing the Solidity interface of the precompile, built from
+	/// the `public` attributes and the Rust types of the arguments and outputs, and from the
+	/// events listed in the `events` attribute.
+	fn expand_solidity_interface_fn(&self) -> impl ToTokens {
+		let span = Span::call_site();
+		let name = self.impl_ident.to_string();
+
+		let functions = self.variants_content.values().flat_map(|variant| {
+			let modifier = match variant.modifier {
+				Modifier::NonPayable => "NonPayable",
+				Modifier::Payable => "Payable",
+				Modifier::View => "View",
+			};
+			let modifier = syn::Ident::new(modifier, span);
+
+			let inputs: Vec<_> = variant
+				.arguments
+				.iter()
+				.map(|arg| {
+					let ty = &arg.ty;
+					let ident = arg.ident.to_string();
+					quote!(.input::<#ty>(#ident))
+				})
+				.collect();
+			let output = Self::evm_result_inner_type(&variant.fn_output);
+
+			variant.selectors.iter().zip(variant.solidity_names.iter()).map(
+				move |(selector, solidity_name)| {
+					let inputs = &inputs;
+					quote!(
+						.function(
+							InterfaceFunction::new(#solidity_name, #selector, FunctionModifier::#modifier)
+							#(#inputs)*
+							.output::<#output>()
+						)
+					)
+				},
+			)
+		});
+
+		let events = self.events.iter().map(|ty| quote!(.event(<#ty>::solidity_event())));
+
+		quote!(
+			pub fn solidity_interface() -> ::precompile_utils::solidity::interface::SolidityInterface {
+				use ::precompile_utils::solidity::interface::{InterfaceFunction, SolidityInterface};
+				use ::precompile_utils::solidity::modifier::FunctionModifier;
+
+				SolidityInterface::new(#name)
+				#(#functions)*
+				#(#events)*
+			}
+		)
+	}
+
+	/// Extract `T` from an `EvmResult<T>` output type, defaulting to `()`.
+	fn evm_result_inner_type(output: &syn::Type) -> syn::Type {
+		if let syn::Type::Path(type_path) = output {
+			if let Some(segment) = type_path.path.segments.last() {
+				if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+					if let Some(syn::GenericArgument::Type(ty)) = args.args.first() {
+						return ty.clone()
+					}
+				}
+			}
+		}
+
+		syn::parse_quote!(())
+	}
+
 	/// Expand the main parsing function that, based on the selector in the
 	/// input, dispatch the decoding to one of the variants parsing function.
 	fn expand_enum_parse_call_data(&self) -> impl ToTokens {
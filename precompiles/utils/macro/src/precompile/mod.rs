@@ -84,6 +84,10 @@ struct Precompile {
 	/// The test thus need to be written using concrete types.
 	test_concrete_types: Option<Vec<syn::Type>>,
 
+	/// Events emitted by the precompile, declared with `#[precompile::event]`, which are
+	/// included in its Solidity interface.
+	events: Vec<syn::Type>,
+
 	/// Ident of a function that performs a check before the call is dispatched to the proper
 	/// function.
 	pre_check: Option<syn::Ident>,
@@ -119,6 +123,9 @@ struct Variant {
 	/// Empty if it only the fallback function.
 	selectors: Vec<u32>,
 
+	/// Solidity function name of each selector, used to generate the Solidity interface.
+	solidity_names: Vec<String>,
+
 	/// Output of the variant fn (for better error messages).
 	fn_output: syn::Type,
 }
@@ -36,6 +36,7 @@ impl Precompile {
 			precompile_set_discriminant_fn: None,
 			precompile_set_discriminant_type: None,
 			test_concrete_types: None,
+			events: vec![],
 			pre_check: None,
 		};
 
@@ -60,7 +61,8 @@ impl Precompile {
 	}
 
 	/// Process the attributes used on the `impl` block, which allows to declare
-	/// if it is a PrecompileSet or not, and to provide concrete types for tests if necessary.
+	/// if it is a PrecompileSet or not, to provide concrete types for tests if necessary, and to
+	/// list the events of the precompile.
 	fn process_impl_attr(&mut self, impl_: &mut syn::ItemImpl) -> syn::Result<()> {
 		let attrs = attr::take_attributes::<attr::ImplAttr>(&mut impl_.attrs)?;
 
@@ -83,6 +85,9 @@ impl Precompile {
 
 					self.test_concrete_types = Some(types);
 				},
+				attr::ImplAttr::Events(_, types) => {
+					self.events.extend(types);
+				},
 			}
 		}
 
@@ -129,6 +134,7 @@ impl Precompile {
 		let mut arguments = vec![];
 		let mut is_fallback = false;
 		let mut selectors = vec![];
+		let mut solidity_names = vec![];
 		let initial_arguments = if self.tagged_as_precompile_set { 2 } else { 1 };
 
 		// We first look for unique attributes.
@@ -200,12 +206,16 @@ impl Precompile {
 				attr::MethodAttr::Public(_, signature_lit) => {
 					used = true;
 
+					let signature = signature_lit.value();
+					let solidity_name = signature.split('(').next().unwrap_or_default().to_string();
+
 					let selector = self.parse_public_attr(
 						signature_lit,
 						&method_name,
 						&mut solidity_arguments_type,
 					)?;
 					selectors.push(selector);
+					solidity_names.push(solidity_name);
 				},
 			}
 		}
@@ -298,6 +308,7 @@ impl Precompile {
 					solidity_arguments_type: solidity_arguments_type.unwrap_or(String::from("()")),
 					modifier,
 					selectors,
+					solidity_names,
 					fn_output: output_type.as_ref().clone(),
 				},
 			)
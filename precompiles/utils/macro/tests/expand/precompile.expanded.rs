@@ -226,6 +226,47 @@ where
     pub fn fallback_selectors() -> &'static [u32] {
         &[]
     }
+    pub fn solidity_interface() -> ::precompile_utils::solidity::interface::SolidityInterface {
+        use ::precompile_utils::solidity::interface::{InterfaceFunction, SolidityInterface};
+        use ::precompile_utils::solidity::modifier::FunctionModifier;
+        SolidityInterface::new("BatchPrecompile")
+            .function(
+                InterfaceFunction::new(
+                        "batchAll",
+                        2531431096u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<BoundedVec<Address, GetArrayLimit>>("to")
+                    .input::<BoundedVec<U256, GetArrayLimit>>("value")
+                    .input::<BoundedVec<BoundedBytes<GetCallDataLimit>, GetArrayLimit>>("call_data")
+                    .input::<BoundedVec<u64, GetArrayLimit>>("gas_limit")
+                    .output::<()>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "batchSome",
+                        2044677020u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<BoundedVec<Address, GetArrayLimit>>("to")
+                    .input::<BoundedVec<U256, GetArrayLimit>>("value")
+                    .input::<BoundedVec<BoundedBytes<GetCallDataLimit>, GetArrayLimit>>("call_data")
+                    .input::<BoundedVec<u64, GetArrayLimit>>("gas_limit")
+                    .output::<()>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "batchSomeUntilFailure",
+                        3473183175u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<BoundedVec<Address, GetArrayLimit>>("to")
+                    .input::<BoundedVec<U256, GetArrayLimit>>("value")
+                    .input::<BoundedVec<BoundedBytes<GetCallDataLimit>, GetArrayLimit>>("call_data")
+                    .input::<BoundedVec<u64, GetArrayLimit>>("gas_limit")
+                    .output::<()>(),
+            )
+    }
     pub fn encode(self) -> ::sp_std::vec::Vec<u8> {
         use ::precompile_utils::solidity::codec::Writer;
         match self {
@@ -885,6 +885,273 @@ where
     pub fn transfer_ownership_selectors() -> &'static [u32] {
         &[4076725131u32, 4030008324u32]
     }
+    pub fn solidity_interface() -> ::precompile_utils::solidity::interface::SolidityInterface {
+        use ::precompile_utils::solidity::interface::{InterfaceFunction, SolidityInterface};
+        use ::precompile_utils::solidity::modifier::FunctionModifier;
+        SolidityInterface::new("PrecompileSet")
+            .function(
+                InterfaceFunction::new(
+                        "allowance",
+                        3714247998u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("owner")
+                    .input::<Address>("spender")
+                    .output::<U256>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "approve",
+                        157198259u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("spender")
+                    .input::<U256>("value")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "balanceOf",
+                        1889567281u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("who")
+                    .output::<U256>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "burn",
+                        2646777772u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("from")
+                    .input::<U256>("value")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "clearMetadata",
+                        4021736498u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "clear_metadata",
+                        3552201630u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "decimals",
+                        826074471u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .output::<u8>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "DOMAIN_SEPARATOR",
+                        910484757u32,
+                        FunctionModifier::View,
+                    )
+                    .output::<H256>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "nonces",
+                        2127478272u32,
+                        FunctionModifier::View,
+                    )
+                    .input::<Address>("owner")
+                    .output::<U256>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "permit",
+                        3573918927u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("owner")
+                    .input::<Address>("spender")
+                    .input::<U256>("value")
+                    .input::<U256>("deadline")
+                    .input::<u8>("v")
+                    .input::<H256>("r")
+                    .input::<H256>("s")
+                    .output::<()>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "freeze",
+                        2367676207u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("account")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "freezeAsset",
+                        3566436177u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "freeze_asset",
+                        1804030401u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "mint",
+                        1086394137u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("to")
+                    .input::<U256>("value")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "name",
+                        117300739u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .output::<UnboundedBytes>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "setMetadata",
+                        936559348u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<BoundedString<GetAssetsStringLimit<Runtime>>>("name")
+                    .input::<BoundedString<GetAssetsStringLimit<Runtime>>>("symbol")
+                    .input::<u8>("decimals")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "set_metadata",
+                        3999121892u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<BoundedString<GetAssetsStringLimit<Runtime>>>("name")
+                    .input::<BoundedString<GetAssetsStringLimit<Runtime>>>("symbol")
+                    .input::<u8>("decimals")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "setTeam",
+                        3352902745u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("issuer")
+                    .input::<Address>("admin")
+                    .input::<Address>("freezer")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "set_team",
+                        4173303445u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("issuer")
+                    .input::<Address>("admin")
+                    .input::<Address>("freezer")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "symbol",
+                        2514000705u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .output::<UnboundedBytes>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "thaw",
+                        1587675670u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("account")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "thawAsset",
+                        1374431959u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "thaw_asset",
+                        484305945u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "totalSupply",
+                        404098525u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .output::<U256>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "transfer",
+                        2835717307u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("to")
+                    .input::<U256>("value")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "transferFrom",
+                        599290589u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("from")
+                    .input::<Address>("to")
+                    .input::<U256>("value")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "transferOwnership",
+                        4076725131u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("owner")
+                    .output::<bool>(),
+            )
+            .function(
+                InterfaceFunction::new(
+                        "transfer_ownership",
+                        4030008324u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .input::<Address>("owner")
+                    .output::<bool>(),
+            )
+    }
     pub fn encode(self) -> ::sp_std::vec::Vec<u8> {
         use ::precompile_utils::solidity::codec::Writer;
         match self {
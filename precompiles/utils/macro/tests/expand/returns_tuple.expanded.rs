@@ -80,6 +80,19 @@ impl ExamplePrecompileCall {
     pub fn example_selectors() -> &'static [u32] {
         &[1412775727u32]
     }
+    pub fn solidity_interface() -> ::precompile_utils::solidity::interface::SolidityInterface {
+        use ::precompile_utils::solidity::interface::{InterfaceFunction, SolidityInterface};
+        use ::precompile_utils::solidity::modifier::FunctionModifier;
+        SolidityInterface::new("ExamplePrecompile")
+            .function(
+                InterfaceFunction::new(
+                        "example",
+                        1412775727u32,
+                        FunctionModifier::NonPayable,
+                    )
+                    .output::<(Address, U256, UnboundedBytes)>(),
+            )
+    }
     pub fn encode(self) -> ::sp_std::vec::Vec<u8> {
         use ::precompile_utils::solidity::codec::Writer;
         match self {
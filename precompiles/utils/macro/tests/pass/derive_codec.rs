@@ -29,6 +29,15 @@ struct DynamicSize<T> {
 	array: Vec<T>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Codec)]
+struct Empty;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Codec)]
+enum Status {
+	Pending,
+	Active,
+}
+
 fn main() {
 	// static
 	let static_size = StaticSize {
@@ -76,4 +85,23 @@ fn main() {
 	let mut reader = Reader::new(&bytes);
 	let dynamic_size_2: DynamicSize<u32> = reader.read().expect("to decode properly");
 	assert_eq!(dynamic_size_2, dynamic_size);
+
+	// empty
+	assert!(Empty::has_static_size());
+	assert_eq!(&Empty::signature(), "()");
+	assert!(Writer::new().write(Empty).build().is_empty());
+
+	// enum
+	assert!(Status::has_static_size());
+	assert_eq!(&Status::signature(), "uint8");
+
+	let bytes = Writer::new().write(Status::Active).build();
+	assert_eq!(bytes, Writer::new().write(1u8).build());
+
+	let mut reader = Reader::new(&bytes);
+	let status: Status = reader.read().expect("to decode properly");
+	assert_eq!(status, Status::Active);
+
+	let bytes = Writer::new().write(2u8).build();
+	assert!(Reader::new(&bytes).read::<Status>().is_err());
 }
@@ -0,0 +1,51 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use {
+	precompile_utils::{prelude::*, solidity::codec::Writer},
+	sha3::{Digest, Keccak256},
+	sp_core::{H160, H256, U256},
+};
+
+#[precompile::event("Transfer(address indexed,address indexed,uint256)")]
+pub struct Transfer {
+	pub from: Address,
+	pub to: Address,
+	pub value: U256,
+}
+
+#[precompile::event("ItemAdded(address,bytes indexed,bytes)")]
+pub struct ItemAdded {
+	pub sender: Address,
+	pub item_type: UnboundedBytes,
+	pub item: UnboundedBytes,
+}
+
+#[precompile::event("Ping()")]
+pub struct Ping;
+
+fn main() {
+	let contract = H160::repeat_byte(0xcc);
+	let from = H160::repeat_byte(0x01);
+	let to = H160::repeat_byte(0x02);
+
+	assert_eq!(Transfer::TOPIC, keccak256!("Transfer(address,address,uint256)"));
+	assert_eq!(
+		Transfer { from: from.into(), to: to.into(), value: 42.into() }.into_log(contract),
+		log3(contract, Transfer::TOPIC, from, to, solidity::encode_event_data(U256::from(42))),
+	);
+
+	assert_eq!(ItemAdded::SIGNATURE, "ItemAdded(address,bytes,bytes)");
+	assert_eq!(ItemAdded::solidity_event().signature(), ItemAdded::SIGNATURE);
+	assert_eq!(
+		ItemAdded { sender: from.into(), item_type: "type".into(), item: "item".into() }
+			.into_log(contract),
+		log2(
+			contract,
+			ItemAdded::TOPIC,
+			H256::from_slice(&Keccak256::digest(b"type")),
+			Writer::new().write(Address(from)).write(UnboundedBytes::from("item")).build(),
+		),
+	);
+
+	assert_eq!(Ping.into_log(contract), log1(contract, keccak256!("Ping()"), vec![]));
+}
@@ -0,0 +1,60 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use {
+	precompile_utils::{prelude::*, testing::PrecompileTesterExt, EvmResult},
+	sp_core::{H160, U256},
+};
+
+#[derive(SolidityError, Debug, PartialEq)]
+pub enum TokenError {
+	InsufficientBalance { have: U256, want: U256 },
+	UnknownAccount(Address),
+	Unauthorized,
+}
+
+pub struct PrecompileSet;
+
+#[precompile_utils_macro::precompile]
+#[precompile::precompile_set]
+impl PrecompileSet {
+	#[precompile::discriminant]
+	fn discriminant(_: H160) -> Option<()> {
+		Some(())
+	}
+
+	#[precompile::public("withdraw(uint256)")]
+	fn withdraw(_: (), _: &mut impl PrecompileHandle, amount: U256) -> EvmResult {
+		Err(TokenError::InsufficientBalance { have: 1.into(), want: amount }.into())
+	}
+
+	#[precompile::public("owner()")]
+	#[precompile::view]
+	fn owner(_: (), _: &mut impl PrecompileHandle) -> EvmResult<Address> {
+		Err(TokenError::Unauthorized)?
+	}
+}
+
+fn main() {
+	assert_eq!(
+		TokenError::InsufficientBalance { have: 1.into(), want: 2.into() }.signature(),
+		"InsufficientBalance(uint256,uint256)"
+	);
+	assert_eq!(TokenError::UnknownAccount(Address(H160::zero())).signature(), "UnknownAccount(address)");
+	assert_eq!(TokenError::Unauthorized.selector(), 0x82b42900);
+
+	let error = TokenError::UnknownAccount(Address(H160::repeat_byte(1)));
+	let encoded = error.encode();
+	assert_eq!(&encoded[..4], &0xc4c6fde4u32.to_be_bytes());
+	assert_eq!(
+		TokenError::decode(&encoded),
+		Some(TokenError::UnknownAccount(Address(H160::repeat_byte(1))))
+	);
+
+	PrecompileSet
+		.prepare_test([0u8; 20], [0u8; 20], PrecompileSetCall::withdraw { amount: 42.into() })
+		.execute_reverts_with(TokenError::InsufficientBalance { have: 1.into(), want: 42.into() });
+
+	PrecompileSet
+		.prepare_test([0u8; 20], [0u8; 20], PrecompileSetCall::owner {})
+		.execute_reverts_with(TokenError::Unauthorized);
+}
@@ -0,0 +1,155 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Harness to benchmark precompiles with `frame_benchmarking`.
+//!
+//! Each public function of a precompile is benchmarked by calling the precompile with a
+//! [`BenchmarkHandle`] through [`call_precompile`] or [`call_precompile_set`]. The resulting
+//! weights (ref_time and PoV size) are written by the benchmark CLI in a `weights.rs` file of the
+//! precompile, which then charges them with `RuntimeHelper::record_weight_cost` so they are
+//! converted to gas by the runtime `GasWeightMapping`.
+//!
+//! As `frame_benchmarking` attaches benchmarks to a pallet, the precompile crate declares a
+//! `Pallet<T>` struct without storage nor calls in its `benchmarking` module, which is added to
+//! `define_benchmarks!` in the runtime like any other pallet.
+//!
+//! Only peaq-storage has benchmarks so far, and its weights are estimates until the benchmarks
+//! are run on reference hardware. The other precompiles still charge fixed gas costs.
+
+use alloc::vec::Vec;
+use fp_evm::{
+	Context, ExitError, ExitReason, Log, Precompile, PrecompileFailure, PrecompileHandle,
+	PrecompileResult, PrecompileSet, Transfer,
+};
+use frame_benchmarking::BenchmarkError;
+use sp_core::{H160, H256, U256};
+
+/// Handle used to call a precompile in a benchmark.
+/// Subcalls are not supported and the gas is unlimited, which only matters for precompiles
+/// checking the remaining gas before dispatching a call.
+pub struct BenchmarkHandle {
+	pub gas_used: u64,
+	pub ref_time_used: u64,
+	pub proof_size_used: u64,
+	pub logs: Vec<Log>,
+	pub code_address: H160,
+	pub input: Vec<u8>,
+	pub context: Context,
+	pub is_static: bool,
+}
+
+impl BenchmarkHandle {
+	pub fn new(code_address: H160, caller: H160, input: Vec<u8>) -> Self {
+		Self {
+			gas_used: 0,
+			ref_time_used: 0,
+			proof_size_used: 0,
+			logs: Vec::new(),
+			code_address,
+			input,
+			context: Context { address: code_address, caller, apparent_value: U256::zero() },
+			is_static: false,
+		}
+	}
+}
+
+impl PrecompileHandle for BenchmarkHandle {
+	fn call(
+		&mut self,
+		_address: H160,
+		_transfer: Option<Transfer>,
+		_input: Vec<u8>,
+		_target_gas: Option<u64>,
+		_is_static: bool,
+		_context: &Context,
+	) -> (ExitReason, Vec<u8>) {
+		(
+			ExitReason::Error(ExitError::Other("subcalls are not supported in benchmarks".into())),
+			Vec::new(),
+		)
+	}
+
+	fn record_cost(&mut self, cost: u64) -> Result<(), ExitError> {
+		self.gas_used = self.gas_used.saturating_add(cost);
+		Ok(())
+	}
+
+	fn record_external_cost(
+		&mut self,
+		ref_time: Option<u64>,
+		proof_size: Option<u64>,
+		_storage_growth: Option<u64>,
+	) -> Result<(), ExitError> {
+		self.ref_time_used = self.ref_time_used.saturating_add(ref_time.unwrap_or_default());
+		self.proof_size_used = self.proof_size_used.saturating_add(proof_size.unwrap_or_default());
+		Ok(())
+	}
+
+	fn refund_external_cost(&mut self, ref_time: Option<u64>, proof_size: Option<u64>) {
+		self.ref_time_used = self.ref_time_used.saturating_sub(ref_time.unwrap_or_default());
+		self.proof_size_used = self.proof_size_used.saturating_sub(proof_size.unwrap_or_default());
+	}
+
+	fn remaining_gas(&self) -> u64 {
+		u64::MAX - self.gas_used
+	}
+
+	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) -> Result<(), ExitError> {
+		self.logs.push(Log { address, topics, data });
+		Ok(())
+	}
+
+	fn code_address(&self) -> H160 {
+		self.code_address
+	}
+
+	fn input(&self) -> &[u8] {
+		&self.input
+	}
+
+	fn context(&self) -> &Context {
+		&self.context
+	}
+
+	fn is_static(&self) -> bool {
+		self.is_static
+	}
+
+	fn gas_limit(&self) -> Option<u64> {
+		None
+	}
+}
+
+/// Call the precompile `P` with `input` as `caller`.
+/// Fails if the call doesn't succeed, as the benchmark would otherwise measure an early exit
+/// instead of the worst case.
+pub fn call_precompile<P: Precompile>(
+	code_address: H160,
+	caller: H160,
+	input: impl Into<Vec<u8>>,
+) -> Result<Vec<u8>, BenchmarkError> {
+	let mut handle = BenchmarkHandle::new(code_address, caller, input.into());
+	into_benchmark_result(P::execute(&mut handle))
+}
+
+/// Call the member of the precompile set `precompiles` at `code_address` with `input` as
+/// `caller`. Fails if there is no precompile at this address or if the call doesn't succeed.
+pub fn call_precompile_set<P: PrecompileSet>(
+	precompiles: &P,
+	code_address: H160,
+	caller: H160,
+	input: impl Into<Vec<u8>>,
+) -> Result<Vec<u8>, BenchmarkError> {
+	let mut handle = BenchmarkHandle::new(code_address, caller, input.into());
+	match precompiles.execute(&mut handle) {
+		Some(result) => into_benchmark_result(result),
+		None => Err(BenchmarkError::Stop("no precompile at this address")),
+	}
+}
+
+fn into_benchmark_result(result: PrecompileResult) -> Result<Vec<u8>, BenchmarkError> {
+	match result {
+		Ok(output) => Ok(output.output),
+		Err(PrecompileFailure::Revert { .. }) => Err(BenchmarkError::Stop("precompile reverted")),
+		Err(_) => Err(BenchmarkError::Stop("precompile failed")),
+	}
+}
@@ -23,6 +23,7 @@ use crate::{
 	EvmResult,
 };
 use fp_evm::{Log, PrecompileHandle};
+use frame_support::traits::Get;
 
 pub trait PrecompileHandleExt: PrecompileHandle {
 	/// Record cost of one DB read manually.
@@ -56,8 +57,11 @@ impl<T: PrecompileHandle> PrecompileHandleExt for T {
 		data_max_encoded_len: usize,
 	) -> Result<(), evm::ExitError> {
 		self.record_cost(crate::prelude::RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		// TODO: record ref time when precompile will be benchmarked
-		self.record_external_cost(None, Some(data_max_encoded_len as u64), None)
+		self.record_external_cost(
+			Some(<Runtime as frame_system::Config>::DbWeight::get().reads(1).ref_time()),
+			Some(data_max_encoded_len as u64),
+			None,
+		)
 	}
 
 	/// Record cost of a log manualy.
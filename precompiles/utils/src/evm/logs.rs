@@ -14,9 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::EvmResult;
+use crate::{
+	solidity::codec::{Codec, Writer},
+	EvmResult,
+};
 use pallet_evm::{Log, PrecompileHandle};
-use sp_core::{H160, H256};
+use sp_core::{H160, H256, U256};
 use sp_std::{vec, vec::Vec};
 
 /// Create a 0-topic log.
@@ -75,6 +78,21 @@ pub fn log4(
 	}
 }
 
+/// Compute the topic of an indexed event parameter.
+/// Value types are stored as their 32 bytes encoding, while `bytes` and `string` are stored as the
+/// hash of their content. Arrays and tuples are not supported.
+pub fn indexed_topic<T: Codec>(value: T) -> H256 {
+	let encoded = Writer::new().write(value).build();
+
+	if T::has_static_size() {
+		return H256::from_slice(&encoded[..32])
+	}
+
+	// Dynamic values are encoded as an offset, followed by the length and the padded content.
+	let len = U256::from_big_endian(&encoded[32..64]).low_u64() as usize;
+	H256(sp_io::hashing::keccak_256(&encoded[64..64 + len]))
+}
+
 /// Extension trait allowing to record logs into a PrecompileHandle.
 pub trait LogExt {
 	fn record(self, handle: &mut impl PrecompileHandle) -> EvmResult;
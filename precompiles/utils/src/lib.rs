@@ -22,6 +22,8 @@ extern crate alloc;
 // `precompile_utils` being in the list of imported crates.
 extern crate self as precompile_utils;
 
+#[cfg(feature = "runtime-benchmarks")]
+pub mod benchmarking;
 pub mod evm;
 pub mod precompile_set;
 pub mod substrate;
@@ -31,6 +33,8 @@ pub mod solidity;
 #[cfg(feature = "testing")]
 pub mod testing;
 
+#[cfg(test)]
+mod mock;
 #[cfg(test)]
 mod tests;
 
@@ -42,6 +46,11 @@ use fp_evm::PrecompileFailure;
 pub use fp_evm::Precompile;
 pub use precompile_utils_macro::{keccak256, precompile, precompile_name_from_address};
 
+/// Attributes of the `precompile` macro that are used outside of the precompile `impl` block.
+pub mod precompile {
+	pub use precompile_utils_macro::event;
+}
+
 /// Alias for Result returning an EVM precompile error.
 pub type EvmResult<T = ()> = Result<T, PrecompileFailure>;
 
@@ -52,6 +61,8 @@ pub mod prelude {
 				handle::PrecompileHandleExt,
 				logs::{log0, log1, log2, log3, log4, LogExt},
 			},
+			// Both the `precompile` macro and the module of its outer attributes.
+			precompile,
 			precompile_set::DiscriminantResult,
 			solidity::{
 				// We export solidity itself to encourage using `solidity::Codec` to avoid
@@ -62,15 +73,18 @@ pub mod prelude {
 					BoundedBytes,
 					BoundedString,
 					BoundedVec,
+					BytesN,
 					// Allow usage of Codec methods while not exporting the name directly.
 					Codec as _,
 					Convert,
+					IntN,
+					UintN,
 					UnboundedBytes,
 					UnboundedString,
 				},
 				revert::{
 					revert, BacktraceExt, InjectBacktrace, MayRevert, Revert, RevertExt,
-					RevertReason,
+					RevertReason, SolidityError,
 				},
 			},
 			substrate::{RuntimeHelper, TryDispatchError, SYSTEM_ACCOUNT_SIZE},
@@ -78,6 +92,6 @@ pub mod prelude {
 		},
 		alloc::string::String,
 		pallet_evm::{PrecompileHandle, PrecompileOutput},
-		precompile_utils_macro::{keccak256, precompile},
+		precompile_utils_macro::keccak256,
 	};
 }
@@ -27,7 +27,7 @@ use fp_evm::{
 	ExitError, IsPrecompileResult, Precompile, PrecompileFailure, PrecompileHandle,
 	PrecompileResult, PrecompileSet,
 };
-use frame_support::pallet_prelude::Get;
+use frame_support::{pallet_prelude::Get, traits::Contains};
 use impl_trait_for_tuples::impl_for_tuples;
 // use pallet_evm::AddressMapping;
 use sp_core::{H160, H256};
@@ -441,6 +441,15 @@ impl<'a, H: PrecompileHandle> PrecompileHandle for RestrictiveHandle<'a, H> {
 		topics: Vec<H256>,
 		data: Vec<u8>,
 	) -> Result<(), evm::ExitError> {
+		// Tracers can't rebuild the logs of precompiles from `LOG` steps, so they are sent
+		// once recorded.
+		#[cfg(feature = "evm-tracing")]
+		{
+			self.handle.log(address, topics.clone(), data.clone())?;
+			peaq_evm_tracer::tracer::EvmTracer::emit_log(address, topics, data);
+			Ok(())
+		}
+		#[cfg(not(feature = "evm-tracing"))]
 		self.handle.log(address, topics, data)
 	}
 
@@ -855,6 +864,82 @@ impl<A> IsActivePrecompile for RemovedPrecompileAt<A> {
 	}
 }
 
+/// Wraps a precompile(set) whose precompiles can be disabled at runtime, for example by
+/// governance. `D` is the on-chain set of disabled addresses.
+/// Like `RemovedPrecompileAt`, a disabled precompile is still considered a precompile but is
+/// inactive and always revert.
+/// Each call to a wrapped precompile pays a read of the disabled set, so only the precompiles
+/// which may need to be switched off should be wrapped.
+pub struct DisableablePrecompiles<D, P> {
+	precompile_set: P,
+	_phantom: PhantomData<D>,
+}
+
+impl<D, P> PrecompileSetFragment for DisableablePrecompiles<D, P>
+where
+	D: Contains<H160>,
+	P: PrecompileSetFragment,
+{
+	#[inline(always)]
+	fn new() -> Self {
+		Self { precompile_set: P::new(), _phantom: PhantomData }
+	}
+
+	#[inline(always)]
+	fn execute<R: pallet_evm::Config>(
+		&self,
+		handle: &mut impl PrecompileHandle,
+	) -> Option<PrecompileResult> {
+		let code_address = handle.code_address();
+		let IsPrecompileResult::Answer { is_precompile: true, .. } =
+			self.precompile_set.is_precompile(code_address, handle.remaining_gas())
+		else {
+			return self.precompile_set.execute::<R>(handle)
+		};
+
+		// DisabledPrecompiles:
+		// Blake2128(16) + H160(20)
+		if let Err(e) = handle.record_db_read::<R>(36) {
+			return Some(Err(e.into()))
+		}
+
+		if D::contains(&code_address) {
+			return Some(Err(revert("Disabled precompile")))
+		}
+
+		self.precompile_set.execute::<R>(handle)
+	}
+
+	#[inline(always)]
+	fn is_precompile(&self, address: H160, gas: u64) -> IsPrecompileResult {
+		self.precompile_set.is_precompile(address, gas)
+	}
+
+	#[inline(always)]
+	fn used_addresses(&self) -> Vec<H160> {
+		self.precompile_set.used_addresses()
+	}
+
+	fn summarize_checks(&self) -> Vec<PrecompileCheckSummary> {
+		self.precompile_set.summarize_checks()
+	}
+}
+
+impl<D, P> IsActivePrecompile for DisableablePrecompiles<D, P>
+where
+	D: Contains<H160>,
+	P: IsActivePrecompile,
+{
+	#[inline(always)]
+	fn is_active_precompile(&self, address: H160, gas: u64) -> IsPrecompileResult {
+		match self.precompile_set.is_active_precompile(address, gas) {
+			IsPrecompileResult::Answer { is_precompile: true, .. } if D::contains(&address) =>
+				IsPrecompileResult::Answer { is_precompile: false, extra_cost: 0 },
+			result => result,
+		}
+	}
+}
+
 // COMPOSITION OF PARTS
 #[impl_for_tuples(1, 100)]
 impl PrecompileSetFragment for Tuple {
@@ -1009,7 +1094,15 @@ pub struct PrecompileSetBuilder<R, P> {
 
 impl<R: pallet_evm::Config, P: PrecompileSetFragment> PrecompileSet for PrecompileSetBuilder<R, P> {
 	fn execute(&self, handle: &mut impl PrecompileHandle) -> Option<PrecompileResult> {
-		self.inner.execute::<R>(handle)
+		let result = self.inner.execute::<R>(handle);
+
+		// Let tracers tell calls to precompiles apart from calls to contracts.
+		#[cfg(feature = "evm-tracing")]
+		if result.is_some() {
+			peaq_evm_tracer::tracer::EvmTracer::emit_precompile();
+		}
+
+		result
 	}
 
 	fn is_precompile(&self, address: H160, gas: u64) -> IsPrecompileResult {
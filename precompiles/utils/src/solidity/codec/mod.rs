@@ -30,7 +30,7 @@ use sp_std::{convert::TryInto, vec, vec::Vec};
 
 pub use alloc::string::String;
 pub use bytes::{BoundedBytes, BoundedString, UnboundedBytes, UnboundedString};
-pub use native::{Address, BoundedVec};
+pub use native::{Address, BoundedVec, BytesN, IntN, UintN};
 
 // derive macro
 pub use precompile_utils_macro::Codec;
@@ -222,6 +222,243 @@ macro_rules! impl_evmdata_for_uints {
 
 impl_evmdata_for_uints!(u8, u16, u32, u64, u128,);
 
+/// Checks that `value` is a valid two's complement representation of an `int{bits}` sign
+/// extended to 256 bits, which is that all the bits above the sign bit are equal to it.
+fn is_sign_extended(value: U256, bits: usize) -> bool {
+	let high_bits = value >> (bits - 1);
+	high_bits.is_zero() || high_bits == U256::MAX >> (bits - 1)
+}
+
+/// Two's complement representation of `value` sign extended to 256 bits.
+fn sign_extend(value: i128) -> U256 {
+	if value < 0 {
+		!U256::from(!value as u128)
+	} else {
+		U256::from(value as u128)
+	}
+}
+
+macro_rules! impl_evmdata_for_ints {
+	($($int:ty, )*) => {
+		$(
+			impl Codec for $int {
+				fn read(reader: &mut Reader) -> MayRevert<Self> {
+					let value256: U256 = reader.read()
+					.map_err(|_| RevertReason::read_out_of_bounds(
+						Self::signature()
+					))?;
+
+					if !is_sign_extended(value256, core::mem::size_of::<Self>() * 8) {
+						return Err(RevertReason::value_is_too_large(Self::signature()).into())
+					}
+
+					// Truncating keeps the two's complement representation, which fits in the
+					// type thanks to the check above.
+					Ok(value256.low_u128() as Self)
+				}
+
+				fn write(writer: &mut Writer, value: Self) {
+					U256::write(writer, sign_extend(value.into()));
+				}
+
+				fn has_static_size() -> bool {
+					true
+				}
+
+				fn signature() -> String {
+					alloc::format!("int{}", core::mem::size_of::<Self>() * 8)
+				}
+			}
+		)*
+	};
+}
+
+impl_evmdata_for_ints!(i8, i16, i32, i64, i128,);
+
+/// Solidity `uint{BITS}` for widths that don't have a native Rust equivalent (`uint24`,
+/// `uint160`, ...). `BITS` must be a multiple of 8 between 8 and 256.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UintN<const BITS: usize>(U256);
+
+impl<const BITS: usize> UintN<BITS> {
+	const VALID_BITS: () = assert!(BITS % 8 == 0 && BITS >= 8 && BITS <= 256, "invalid uint width");
+
+	/// Returns `None` if the value doesn't fit in `BITS` bits.
+	pub fn new(value: U256) -> Option<Self> {
+		let () = Self::VALID_BITS;
+
+		if BITS < 256 && !(value >> BITS).is_zero() {
+			return None
+		}
+
+		Some(Self(value))
+	}
+
+	pub fn value(&self) -> U256 {
+		self.0
+	}
+}
+
+impl<const BITS: usize> From<UintN<BITS>> for U256 {
+	fn from(value: UintN<BITS>) -> U256 {
+		value.0
+	}
+}
+
+impl<const BITS: usize> Codec for UintN<BITS> {
+	fn read(reader: &mut Reader) -> MayRevert<Self> {
+		let value256: U256 =
+			reader.read().map_err(|_| RevertReason::read_out_of_bounds(Self::signature()))?;
+
+		Self::new(value256)
+			.ok_or_else(|| RevertReason::value_is_too_large(Self::signature()).into())
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		U256::write(writer, value.0);
+	}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		let () = Self::VALID_BITS;
+
+		alloc::format!("uint{}", BITS)
+	}
+}
+
+/// Solidity `int{BITS}` for any width, stored as its two's complement representation sign
+/// extended to 256 bits like in the ABI encoding. `BITS` must be a multiple of 8 between 8 and 256.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct IntN<const BITS: usize>(U256);
+
+impl<const BITS: usize> IntN<BITS> {
+	const VALID_BITS: () = assert!(BITS % 8 == 0 && BITS >= 8 && BITS <= 256, "invalid int width");
+
+	/// Build from a two's complement representation sign extended to 256 bits.
+	/// Returns `None` if the value doesn't fit in `BITS` bits.
+	pub fn from_raw(raw: U256) -> Option<Self> {
+		let () = Self::VALID_BITS;
+
+		if !is_sign_extended(raw, BITS) {
+			return None
+		}
+
+		Some(Self(raw))
+	}
+
+	/// Returns `None` if the value doesn't fit in `BITS` bits.
+	pub fn from_i128(value: i128) -> Option<Self> {
+		Self::from_raw(sign_extend(value))
+	}
+
+	/// Two's complement representation sign extended to 256 bits.
+	pub fn raw(&self) -> U256 {
+		self.0
+	}
+
+	pub fn is_negative(&self) -> bool {
+		self.0.bit(255)
+	}
+
+	/// Returns `None` if the value doesn't fit in an `i128`.
+	pub fn to_i128(&self) -> Option<i128> {
+		if !is_sign_extended(self.0, 128) {
+			return None
+		}
+
+		Some(self.0.low_u128() as i128)
+	}
+}
+
+impl<const BITS: usize> Codec for IntN<BITS> {
+	fn read(reader: &mut Reader) -> MayRevert<Self> {
+		let value256: U256 =
+			reader.read().map_err(|_| RevertReason::read_out_of_bounds(Self::signature()))?;
+
+		Self::from_raw(value256)
+			.ok_or_else(|| RevertReason::value_is_too_large(Self::signature()).into())
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		U256::write(writer, value.0);
+	}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		let () = Self::VALID_BITS;
+
+		alloc::format!("int{}", BITS)
+	}
+}
+
+/// Solidity `bytes{N}`, which is left aligned and padded with zeros.
+/// `bytes32` can also be represented with `H256`. `N` must be between 1 and 32.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BytesN<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> BytesN<N> {
+	const VALID_SIZE: () = assert!(N >= 1 && N <= 32, "invalid bytesN size");
+}
+
+impl<const N: usize> Default for BytesN<N> {
+	fn default() -> Self {
+		Self([0; N])
+	}
+}
+
+impl<const N: usize> From<[u8; N]> for BytesN<N> {
+	fn from(value: [u8; N]) -> Self {
+		Self(value)
+	}
+}
+
+impl<const N: usize> From<BytesN<N>> for [u8; N] {
+	fn from(value: BytesN<N>) -> Self {
+		value.0
+	}
+}
+
+impl<const N: usize> Codec for BytesN<N> {
+	fn read(reader: &mut Reader) -> MayRevert<Self> {
+		let range = reader.move_cursor(32)?;
+
+		let data = reader
+			.input
+			.get(range)
+			.ok_or_else(|| RevertReason::read_out_of_bounds(Self::signature()))?;
+
+		if data[N..].iter().any(|byte| *byte != 0) {
+			return Err(RevertReason::value_is_too_large(Self::signature()).into())
+		}
+
+		let mut value = [0u8; N];
+		value.copy_from_slice(&data[..N]);
+		Ok(Self(value))
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		let mut buffer = [0u8; 32];
+		buffer[..N].copy_from_slice(&value.0);
+		writer.data.extend_from_slice(&buffer);
+	}
+
+	fn has_static_size() -> bool {
+		true
+	}
+
+	fn signature() -> String {
+		let () = Self::VALID_SIZE;
+
+		alloc::format!("bytes{}", N)
+	}
+}
+
 impl Codec for bool {
 	fn read(reader: &mut Reader) -> MayRevert<Self> {
 		let h256 = H256::read(reader).map_err(|_| RevertReason::read_out_of_bounds("bool"))?;
@@ -270,6 +507,51 @@ impl<T: Codec> Codec for Vec<T> {
 	}
 }
 
+/// Fixed-size array `T[N]`. Like tuples, it is encoded inline if `T` has a static size and
+/// behind a pointer otherwise, without any length prefix.
+impl<T: Codec, const N: usize> Codec for [T; N] {
+	fn read(reader: &mut Reader) -> MayRevert<Self> {
+		let mut array = Vec::with_capacity(N);
+
+		if Self::has_static_size() {
+			for i in 0..N {
+				array.push(reader.read().in_array(i)?);
+			}
+		} else {
+			let reader = &mut reader.read_pointer()?;
+			for i in 0..N {
+				array.push(reader.read().in_array(i)?);
+			}
+		}
+
+		array
+			.try_into()
+			.map_err(|_| RevertReason::read_out_of_bounds(Self::signature()).into())
+	}
+
+	fn write(writer: &mut Writer, value: Self) {
+		if Self::has_static_size() {
+			for item in value {
+				T::write(writer, item);
+			}
+		} else {
+			let mut inner_writer = Writer::new();
+			for item in value {
+				T::write(&mut inner_writer, item);
+			}
+			writer.write_pointer(inner_writer.build());
+		}
+	}
+
+	fn has_static_size() -> bool {
+		T::has_static_size()
+	}
+
+	fn signature() -> String {
+		alloc::format!("{}[{}]", T::signature(), N)
+	}
+}
+
 /// Wrapper around a Vec that provides a max length bound on read.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BoundedVec<T, S> {
@@ -0,0 +1,423 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Solidity interface and ABI JSON of a precompile, generated from its `#[precompile]`
+//! definition so that they cannot drift from the selectors it actually implements.
+
+use crate::solidity::{codec::Codec, modifier::FunctionModifier};
+use alloc::{
+	boxed::Box,
+	format,
+	string::{String, ToString},
+	vec,
+	vec::Vec,
+};
+
+/// Solidity type, parsed from a signature such as `(address,uint256)[]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SolidityType {
+	/// Elementary type such as `address`, `uint256`, `bytes` or `string`.
+	Elementary(String),
+	/// Tuple, which is rendered as a struct in a Solidity interface.
+	Tuple(Vec<SolidityType>),
+	/// Dynamic (`T[]`) or fixed size (`T[N]`) array.
+	Array(Box<SolidityType>, Option<usize>),
+}
+
+impl SolidityType {
+	/// Parses a type signature, returning `None` if it is malformed.
+	pub fn parse(signature: &str) -> Option<Self> {
+		let signature = signature.trim();
+
+		if let Some(inner) = signature.strip_suffix(']') {
+			let open = inner.rfind('[')?;
+			let size = &inner[open + 1..];
+			let size = if size.is_empty() { None } else { Some(size.parse().ok()?) };
+			let elem = Self::parse(&inner[..open])?;
+			return Some(Self::Array(Box::new(elem), size))
+		}
+
+		if let Some(inner) = signature.strip_prefix('(') {
+			let inner = inner.strip_suffix(')')?;
+			if inner.trim().is_empty() {
+				return Some(Self::Tuple(vec![]))
+			}
+			let elems = split_top_level(inner)
+				.into_iter()
+				.map(Self::parse)
+				.collect::<Option<Vec<_>>>()?;
+			return Some(Self::Tuple(elems))
+		}
+
+		let valid = !signature.is_empty() &&
+			signature.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+		valid.then(|| Self::Elementary(normalize_elementary(signature)))
+	}
+
+	/// Parses the signature of a `Codec` type.
+	pub fn of<T: Codec>() -> Self {
+		let signature = T::signature();
+		Self::parse(&signature)
+			.unwrap_or_else(|| panic!("Codec signature '{signature}' is not a valid Solidity type"))
+	}
+
+	/// Canonical signature of the type, as used to compute selectors.
+	pub fn signature(&self) -> String {
+		match self {
+			Self::Elementary(name) => name.clone(),
+			Self::Tuple(elems) =>
+				format!("({})", elems.iter().map(Self::signature).collect::<Vec<_>>().join(",")),
+			Self::Array(elem, size) => format!("{}{}", elem.signature(), array_suffix(*size)),
+		}
+	}
+
+	/// Whether the type is a reference type, which requires a data location in function
+	/// parameters.
+	pub fn is_reference(&self) -> bool {
+		match self {
+			Self::Elementary(name) => name == "bytes" || name == "string",
+			Self::Tuple(_) | Self::Array(_, _) => true,
+		}
+	}
+
+	/// Type as written in the ABI JSON, where tuples are described by their components.
+	fn abi_type(&self) -> String {
+		match self {
+			Self::Elementary(name) => name.clone(),
+			Self::Tuple(_) => String::from("tuple"),
+			Self::Array(elem, size) => format!("{}{}", elem.abi_type(), array_suffix(*size)),
+		}
+	}
+
+	/// Components of the innermost tuple type, if any.
+	fn abi_components(&self) -> Option<&[SolidityType]> {
+		match self {
+			Self::Elementary(_) => None,
+			Self::Tuple(elems) => Some(elems),
+			Self::Array(elem, _) => elem.abi_components(),
+		}
+	}
+}
+
+/// Parameter of a function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Param {
+	pub name: String,
+	pub ty: SolidityType,
+}
+
+/// Parameter of an event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventParam {
+	pub name: String,
+	pub ty: SolidityType,
+	pub indexed: bool,
+}
+
+/// Function exposed by a precompile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InterfaceFunction {
+	pub name: String,
+	pub selector: u32,
+	pub modifier: FunctionModifier,
+	pub inputs: Vec<Param>,
+	pub outputs: Vec<SolidityType>,
+}
+
+impl InterfaceFunction {
+	pub fn new(name: &str, selector: u32, modifier: FunctionModifier) -> Self {
+		Self { name: name.to_string(), selector, modifier, inputs: vec![], outputs: vec![] }
+	}
+
+	/// Adds an input of type `T`.
+	pub fn input<T: Codec>(mut self, name: &str) -> Self {
+		self.inputs.push(Param { name: name.to_string(), ty: SolidityType::of::<T>() });
+		self
+	}
+
+	/// Sets the outputs from the type returned by the precompile function.
+	/// Rust tuples are returned as multiple values, like in `encode_return_value`.
+	pub fn output<T: Codec>(mut self) -> Self {
+		self.outputs = match SolidityType::of::<T>() {
+			SolidityType::Tuple(elems) if T::is_explicit_tuple() => elems,
+			SolidityType::Tuple(elems) if elems.is_empty() => vec![],
+			ty => vec![ty],
+		};
+		self
+	}
+
+	/// Signature of the function, as used to compute its selector.
+	pub fn signature(&self) -> String {
+		let inputs: Vec<_> = self.inputs.iter().map(|param| param.ty.signature()).collect();
+		format!("{}({})", self.name, inputs.join(","))
+	}
+}
+
+/// Event emitted by a precompile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InterfaceEvent {
+	pub name: String,
+	pub inputs: Vec<EventParam>,
+}
+
+impl InterfaceEvent {
+	pub fn new(name: &str) -> Self {
+		Self { name: name.to_string(), inputs: vec![] }
+	}
+
+	/// Adds a field of type `T`, stored in a topic if `indexed`.
+	pub fn input<T: Codec>(mut self, name: &str, indexed: bool) -> Self {
+		self.inputs.push(EventParam {
+			name: name.to_string(),
+			ty: SolidityType::of::<T>(),
+			indexed,
+		});
+		self
+	}
+
+	/// Signature of the event, as used to compute its topic.
+	pub fn signature(&self) -> String {
+		let inputs: Vec<_> = self.inputs.iter().map(|param| param.ty.signature()).collect();
+		format!("{}({})", self.name, inputs.join(","))
+	}
+}
+
+/// Solidity interface of a precompile.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolidityInterface {
+	pub name: String,
+	pub functions: Vec<InterfaceFunction>,
+	pub events: Vec<InterfaceEvent>,
+}
+
+impl SolidityInterface {
+	pub fn new(name: &str) -> Self {
+		Self { name: name.to_string(), ..Default::default() }
+	}
+
+	pub fn function(mut self, function: InterfaceFunction) -> Self {
+		self.functions.push(function);
+		self
+	}
+
+	pub fn event(mut self, event: InterfaceEvent) -> Self {
+		self.events.push(event);
+		self
+	}
+
+	/// Renders the interface as a Solidity file. Tuples are declared as structs named
+	/// `Tuple0`, `Tuple1`, ... in order of appearance.
+	pub fn to_solidity(&self) -> String {
+		let structs = self.structs();
+		let render = |ty: &SolidityType| render_type(ty, &structs);
+
+		let mut out = String::from(
+			"// SPDX-License-Identifier: GPL-3.0-or-later\n\npragma solidity >=0.8.3;\n\n",
+		);
+		out += &format!("interface {} {{\n", self.name);
+
+		for (index, ty) in structs.iter().enumerate() {
+			if let SolidityType::Tuple(elems) = ty {
+				out += &format!("    struct Tuple{index} {{\n");
+				for (field, elem) in elems.iter().enumerate() {
+					out += &format!("        {} field{field};\n", render(elem));
+				}
+				out += "    }\n\n";
+			}
+		}
+
+		for function in &self.functions {
+			let inputs: Vec<_> = function
+				.inputs
+				.iter()
+				.map(|param| format!("{}{} {}", render(&param.ty), location(&param.ty), param.name))
+				.collect();
+			let modifier = match function.modifier {
+				FunctionModifier::View => " view",
+				FunctionModifier::Payable => " payable",
+				FunctionModifier::NonPayable => "",
+			};
+			let returns = if function.outputs.is_empty() {
+				String::new()
+			} else {
+				let outputs: Vec<_> = function
+					.outputs
+					.iter()
+					.map(|ty| format!("{}{}", render(ty), location(ty)))
+					.collect();
+				format!(" returns ({})", outputs.join(", "))
+			};
+
+			out += &format!("    /// @custom:selector {:0>8x}\n", function.selector);
+			out += &format!(
+				"    function {}({}) external{modifier}{returns};\n\n",
+				function.name,
+				inputs.join(", ")
+			);
+		}
+
+		for event in &self.events {
+			let inputs: Vec<_> = event
+				.inputs
+				.iter()
+				.map(|param| {
+					let indexed = if param.indexed { " indexed" } else { "" };
+					format!("{}{indexed} {}", render(&param.ty), param.name)
+				})
+				.collect();
+			out += &format!("    event {}({});\n\n", event.name, inputs.join(", "));
+		}
+
+		// Remove the blank line after the last item.
+		if out.ends_with("\n\n") {
+			out.pop();
+		}
+		out += "}\n";
+		out
+	}
+
+	/// Renders the interface as an ABI JSON array.
+	pub fn to_abi_json(&self) -> String {
+		let mut entries = vec![];
+
+		for function in &self.functions {
+			let inputs: Vec<_> = function
+				.inputs
+				.iter()
+				.map(|param| abi_param(&param.name, &param.ty, None))
+				.collect();
+			let outputs: Vec<_> =
+				function.outputs.iter().map(|ty| abi_param("", ty, None)).collect();
+			let mutability = match function.modifier {
+				FunctionModifier::View => "view",
+				FunctionModifier::Payable => "payable",
+				FunctionModifier::NonPayable => "nonpayable",
+			};
+			entries.push(format!(
+				"{{\"type\":\"function\",\"name\":\"{}\",\"inputs\":[{}],\"outputs\":[{}],\
+				\"stateMutability\":\"{mutability}\"}}",
+				function.name,
+				inputs.join(","),
+				outputs.join(",")
+			));
+		}
+
+		for event in &self.events {
+			let inputs: Vec<_> = event
+				.inputs
+				.iter()
+				.map(|param| abi_param(&param.name, &param.ty, Some(param.indexed)))
+				.collect();
+			entries.push(format!(
+				"{{\"type\":\"event\",\"name\":\"{}\",\"inputs\":[{}],\"anonymous\":false}}",
+				event.name,
+				inputs.join(",")
+			));
+		}
+
+		if entries.is_empty() {
+			return String::from("[]\n")
+		}
+		format!("[\n  {}\n]\n", entries.join(",\n  "))
+	}
+
+	/// Tuple types used by the interface, innermost first.
+	fn structs(&self) -> Vec<SolidityType> {
+		fn collect(ty: &SolidityType, structs: &mut Vec<SolidityType>) {
+			match ty {
+				SolidityType::Elementary(_) => (),
+				SolidityType::Array(elem, _) => collect(elem, structs),
+				SolidityType::Tuple(elems) => {
+					elems.iter().for_each(|elem| collect(elem, structs));
+					if !structs.contains(ty) {
+						structs.push(ty.clone());
+					}
+				},
+			}
+		}
+
+		let mut structs = vec![];
+		for function in &self.functions {
+			function.inputs.iter().for_each(|param| collect(&param.ty, &mut structs));
+			function.outputs.iter().for_each(|ty| collect(ty, &mut structs));
+		}
+		for event in &self.events {
+			event.inputs.iter().for_each(|param| collect(&param.ty, &mut structs));
+		}
+		structs
+	}
+}
+
+/// Splits a list of types on the commas which are not nested in parentheses.
+pub fn split_top_level(list: &str) -> Vec<&str> {
+	let mut parts = vec![];
+	let mut depth = 0usize;
+	let mut start = 0;
+
+	for (index, c) in list.char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' => depth = depth.saturating_sub(1),
+			',' if depth == 0 => {
+				parts.push(list[start..index].trim());
+				start = index + 1;
+			},
+			_ => (),
+		}
+	}
+	parts.push(list[start..].trim());
+	parts
+}
+
+/// `uint` and `int` are aliases of `uint256` and `int256`.
+fn normalize_elementary(name: &str) -> String {
+	match name {
+		"uint" => String::from("uint256"),
+		"int" => String::from("int256"),
+		name => name.to_string(),
+	}
+}
+
+fn array_suffix(size: Option<usize>) -> String {
+	match size {
+		Some(size) => format!("[{size}]"),
+		None => String::from("[]"),
+	}
+}
+
+fn render_type(ty: &SolidityType, structs: &[SolidityType]) -> String {
+	match ty {
+		SolidityType::Elementary(name) => name.clone(),
+		SolidityType::Tuple(_) => {
+			let index =
+				structs.iter().position(|s| s == ty).expect("all tuples are collected; qed");
+			format!("Tuple{index}")
+		},
+		SolidityType::Array(elem, size) =>
+			format!("{}{}", render_type(elem, structs), array_suffix(*size)),
+	}
+}
+
+fn location(ty: &SolidityType) -> &'static str {
+	if ty.is_reference() {
+		" memory"
+	} else {
+		""
+	}
+}
+
+fn abi_param(name: &str, ty: &SolidityType, indexed: Option<bool>) -> String {
+	let mut out = format!("{{\"name\":\"{name}\",\"type\":\"{}\"", ty.abi_type());
+	if let Some(components) = ty.abi_components() {
+		let components: Vec<_> = components
+			.iter()
+			.enumerate()
+			.map(|(index, elem)| abi_param(&format!("field{index}"), elem, None))
+			.collect();
+		out += &format!(",\"components\":[{}]", components.join(","));
+	}
+	if let Some(indexed) = indexed {
+		out += &format!(",\"indexed\":{indexed}");
+	}
+	out.push('}');
+	out
+}
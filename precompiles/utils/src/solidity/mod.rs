@@ -17,6 +17,7 @@
 //! Provides utilities for compatibility with Solidity tooling.
 
 pub mod codec;
+pub mod interface;
 pub mod modifier;
 pub mod revert;
 
@@ -18,10 +18,15 @@
 //! consistent formatting.
 
 use crate::solidity::{self, codec::bytes::UnboundedBytes};
-use alloc::string::{String, ToString};
+use alloc::{
+	format,
+	string::{String, ToString},
+};
 use fp_evm::{ExitRevert, PrecompileFailure};
 use sp_std::vec::Vec;
 
+pub use precompile_utils_macro::SolidityError;
+
 /// Represent the result of a computation that can revert.
 pub type MayRevert<T = ()> = Result<T, Revert>;
 
@@ -41,6 +46,39 @@ pub fn revert_as_bytes(msg: impl Into<String>) -> Vec<u8> {
 /// See: https://docs.soliditylang.org/en/latest/control-structures.html?highlight=revert#revert
 pub const ERROR_SELECTOR: u32 = 0x08c379a0;
 
+/// Error that is ABI-encoded as a Solidity custom error, such as
+/// `error InsufficientBalance(uint256 have, uint256 want)`, which callers can decode.
+/// Should be derived with `#[derive(SolidityError)]` on an enum, each variant being an error named
+/// after the variant and whose parameters are the variant fields.
+/// The derive also implements `From<Self> for PrecompileFailure` to be used with `EvmResult`.
+pub trait SolidityError: Sized {
+	/// Signature of the error, such as `InsufficientBalance(uint256,uint256)`.
+	fn signature(&self) -> String;
+
+	/// Encode the error with its selector, as a revert output.
+	fn encode(self) -> Vec<u8>;
+
+	/// Decode the error from a revert output.
+	/// Returns `None` if the selector or the parameters don't match any of the errors.
+	fn decode(output: &[u8]) -> Option<Self>;
+
+	/// Selector of the error, computed from its signature like function selectors.
+	fn selector(&self) -> u32 {
+		custom_error_selector(&self.signature())
+	}
+}
+
+/// Build the signature of a custom error from its name and the signature of its parameters.
+pub fn custom_error_signature(name: &str, params: &[String]) -> String {
+	format!("{name}({})", params.join(","))
+}
+
+/// Compute the selector of a custom error from its signature.
+pub fn custom_error_selector(signature: &str) -> u32 {
+	let hash = sp_io::hashing::keccak_256(signature.as_bytes());
+	u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+}
+
 #[derive(Clone, PartialEq, Eq)]
 enum BacktracePart {
 	Field(String),
@@ -18,11 +18,14 @@
 //! - Substrate call dispatch.
 //! - Substrate DB read and write costs
 
-use crate::{evm::handle::using_precompile_handle, solidity::revert::revert};
+use crate::{
+	evm::handle::{using_precompile_handle, PrecompileHandleExt},
+	solidity::revert::revert,
+};
 use core::marker::PhantomData;
 use fp_evm::{ExitError, PrecompileFailure, PrecompileHandle};
 use frame_support::{
-	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	dispatch::{Dispatchable, GetCallMetadata, GetDispatchInfo, PostDispatchInfo},
 	pallet_prelude::*,
 	traits::Get,
 };
@@ -58,22 +61,49 @@ where
 	Runtime: pallet_evm::Config,
 	Runtime::RuntimeCall: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
 {
+	/// Record the ref time and proof size of `weight` as external costs of the EVM.
+	/// The EVM fails with `OutOfGas` once the proof size recorded by the transaction exceeds its
+	/// gas limit divided by `GasLimitPovSizeRatio`, so the proof size is checked against the
+	/// budget it actually meters.
 	#[inline(always)]
-	pub fn reocrd_external_cost(
+	pub fn record_external_cost(
 		handle: &mut impl PrecompileHandle,
 		weight: Weight,
 		storage_growth: u64,
 	) -> Result<(), ExitError> {
-		// Make sure there is enough gas.
+		// Make sure there is enough gas for the execution.
 		let remaining_gas = handle.remaining_gas();
 		let required_gas = Runtime::GasWeightMapping::weight_to_gas(weight);
 		if required_gas > remaining_gas {
 			return Err(ExitError::OutOfGas)
 		}
 
-		// Make sure there is enough remaining weight
-		// TODO: record ref time when precompile will be benchmarked
-		handle.record_external_cost(None, Some(weight.proof_size()), Some(storage_growth))
+		handle.record_external_cost(
+			Some(weight.ref_time()),
+			Some(weight.proof_size()),
+			Some(storage_growth),
+		)
+	}
+
+	/// Charge the `weight` of a precompile function, converted to gas through the
+	/// runtime `GasWeightMapping`.
+	pub fn record_weight_cost(
+		handle: &mut impl PrecompileHandle,
+		weight: Weight,
+	) -> Result<(), ExitError> {
+		Self::record_external_cost(handle, weight, 0)?;
+		handle.record_cost(Runtime::GasWeightMapping::weight_to_gas(weight))
+	}
+
+	/// Charge the `weight` of a precompile function dispatching `call`.
+	/// The weight covers the whole function, but the weight of the call is charged by
+	/// `try_dispatch` which can refund it, so only the remaining weight is charged here.
+	pub fn record_weight_cost_without_call(
+		handle: &mut impl PrecompileHandle,
+		weight: Weight,
+		call: &impl GetDispatchInfo,
+	) -> Result<(), ExitError> {
+		Self::record_weight_cost(handle, weight.saturating_sub(call.get_dispatch_info().weight))
 	}
 
 	#[inline(always)]
@@ -82,11 +112,15 @@ where
 		weight: Weight,
 		maybe_actual_weight: Option<Weight>,
 	) -> Result<u64, ExitError> {
-		// Refund weights and compute used weight them record used gas
-		// TODO: refund ref time when precompile will be benchmarked
+		// Refund weights and compute used weight them record used gas.
+		// Each component is refunded separately, so an unused proof size is refunded even if the
+		// call used more ref time than estimated.
 		let used_weight = if let Some(actual_weight) = maybe_actual_weight {
-			let refund_weight = weight.checked_sub(&actual_weight).unwrap_or_default();
-			handle.refund_external_cost(None, Some(refund_weight.proof_size()));
+			let refund_weight = weight.saturating_sub(actual_weight);
+			handle.refund_external_cost(
+				Some(refund_weight.ref_time()),
+				Some(refund_weight.proof_size()),
+			);
 			actual_weight
 		} else {
 			weight
@@ -106,12 +140,22 @@ where
 		storage_growth: u64,
 	) -> Result<PostDispatchInfo, TryDispatchError>
 	where
-		Runtime::RuntimeCall: From<Call>,
+		Runtime::RuntimeCall: From<Call> + GetCallMetadata,
 	{
 		let call = Runtime::RuntimeCall::from(call);
 		let dispatch_info = call.get_dispatch_info();
 
-		Self::reocrd_external_cost(handle, dispatch_info.weight, storage_growth)
+		// Annotate the precompile call of the trace with the dispatched call.
+		#[cfg(feature = "evm-tracing")]
+		{
+			let metadata = call.get_call_metadata();
+			peaq_evm_tracer::tracer::EvmTracer::emit_dispatch(
+				metadata.pallet_name,
+				metadata.function_name,
+			);
+		}
+
+		Self::record_external_cost(handle, dispatch_info.weight, storage_growth)
 			.map_err(TryDispatchError::Evm)?;
 
 		// Dispatch call.
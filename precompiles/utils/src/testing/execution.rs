@@ -15,7 +15,7 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-	solidity::codec::Codec,
+	solidity::{codec::Codec, revert::SolidityError},
 	testing::{decode_revert_message, MockHandle, PrettyLog, SubcallHandle, SubcallTrait},
 };
 use fp_evm::{
@@ -209,6 +209,34 @@ impl<'p, P: PrecompileSet> PrecompilesTester<'p, P> {
 		self.assert_optionals();
 	}
 
+	/// Execute the precompile set and check it reverts with the provided custom error.
+	pub fn execute_reverts_with<E>(mut self, expected: E)
+	where
+		E: SolidityError + PartialEq + core::fmt::Debug,
+	{
+		let res = self.execute();
+
+		match res {
+			Some(Err(PrecompileFailure::Revert { output, .. })) => match E::decode(&output) {
+				Some(error) => assert_eq!(error, expected, "Revert error doesn't match !"),
+				None => {
+					eprintln!(
+						"Revert output (bytes): {:?}",
+						sp_core::hexdisplay::HexDisplay::from(&output)
+					);
+					eprintln!(
+						"Revert message (string): {:?}",
+						core::str::from_utf8(decode_revert_message(&output)).ok()
+					);
+					panic!("Revert output is not a {} error !", core::any::type_name::<E>());
+				},
+			},
+			other => panic!("Didn't revert, instead returned {:?}", other),
+		}
+
+		self.assert_optionals();
+	}
+
 	/// Execute the precompile set and check it returns provided output.
 	pub fn execute_error(mut self, error: ExitError) {
 		let res = self.execute();
@@ -0,0 +1,225 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Harness to fuzz precompiles with random calldata.
+//!
+//! A [`FuzzCall`] is executed on a precompile set by [`check_call`], which fails if the
+//! precompile exits with a fatal error, reverts with an output that is not ABI encoded or records
+//! more gas than the gas limit while succeeding. Panics are caught by the fuzzer itself.
+//!
+//! Calls are generated either by `proptest` with [`fuzz_call`], which can be run deterministically
+//! in CI with [`run_deterministic`], or from raw fuzzer bytes with [`FuzzCall::from_bytes`] in a
+//! `cargo fuzz` target. The precompiles of the runtime are all checked at once with
+//! [`check_random_calls`] in the `precompiles_fuzz` test of peaq-dev.
+
+use super::{MockHandle, SubcallOutput};
+use fp_evm::{Context, ExitError, PrecompileFailure, PrecompileSet};
+use proptest::{
+	collection::vec,
+	prelude::*,
+	test_runner::{Config, RngAlgorithm, TestCaseError, TestRng, TestRunner},
+};
+use sp_core::{H160, U256};
+
+/// A call to a precompile set.
+#[derive(Clone)]
+pub struct FuzzCall {
+	pub code_address: H160,
+	pub caller: H160,
+	pub value: U256,
+	pub is_static: bool,
+	pub gas_limit: u64,
+	pub input: Vec<u8>,
+}
+
+impl core::fmt::Debug for FuzzCall {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+		f.debug_struct("FuzzCall")
+			.field("code_address", &self.code_address)
+			.field("caller", &self.caller)
+			.field("value", &self.value)
+			.field("is_static", &self.is_static)
+			.field("gas_limit", &self.gas_limit)
+			.field("input", &hex::encode(&self.input))
+			.finish()
+	}
+}
+
+impl FuzzCall {
+	/// Build a call from the raw bytes provided by a fuzzer. The first bytes select the
+	/// precompile among `addresses` and the caller among `callers`, followed by flags and the gas
+	/// limit, the remaining bytes being the input.
+	pub fn from_bytes(data: &[u8], addresses: &[H160], callers: &[H160]) -> Option<Self> {
+		if data.len() < 11 || addresses.is_empty() || callers.is_empty() {
+			return None
+		}
+
+		let mut gas_limit = [0u8; 8];
+		gas_limit.copy_from_slice(&data[3..11]);
+
+		Some(Self {
+			code_address: addresses[data[0] as usize % addresses.len()],
+			caller: callers[data[1] as usize % callers.len()],
+			value: U256::from(data[2] & 1),
+			is_static: data[2] & 2 != 0,
+			gas_limit: u64::from_be_bytes(gas_limit),
+			input: data[11..].to_vec(),
+		})
+	}
+}
+
+/// A violation of the expectations of [`check_call`].
+#[derive(Debug)]
+pub struct FuzzFailure {
+	pub call: Box<FuzzCall>,
+	pub reason: String,
+}
+
+impl core::fmt::Display for FuzzFailure {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+		write!(f, "{} for {:?}", self.reason, self.call)
+	}
+}
+
+impl std::error::Error for FuzzFailure {}
+
+/// Execute `call` on `precompiles` and check it either succeeds or fails cleanly.
+/// Subcalls made by the precompile revert. Calls to addresses which are not a precompile of the
+/// set are ignored.
+pub fn check_call<P: PrecompileSet>(precompiles: &P, call: FuzzCall) -> Result<(), FuzzFailure> {
+	let mut handle = MockHandle::new(
+		call.code_address,
+		Context { address: call.code_address, caller: call.caller, apparent_value: call.value },
+	);
+	handle.input = call.input.clone();
+	handle.gas_limit = call.gas_limit;
+	handle.is_static = call.is_static;
+	handle.subcall_handle = Some(Box::new(|_| SubcallOutput::revert()));
+
+	let Some(result) = precompiles.execute(&mut handle) else { return Ok(()) };
+
+	let fail = |reason: String| Err(FuzzFailure { call: Box::new(call.clone()), reason });
+	match &result {
+		Err(PrecompileFailure::Fatal { exit_status }) =>
+			return fail(format!("Fatal error {exit_status:?}")),
+		Err(PrecompileFailure::Revert { output, .. }) if !is_abi_encoded_revert(output) =>
+			return fail(format!("Revert output is not ABI encoded: {}", hex::encode(output))),
+		_ => (),
+	}
+
+	let out_of_gas =
+		matches!(result, Err(PrecompileFailure::Error { exit_status: ExitError::OutOfGas }));
+	if handle.gas_used > call.gas_limit && !out_of_gas {
+		return fail(format!(
+			"Recorded {} gas with a limit of {} without running out of gas",
+			handle.gas_used, call.gas_limit
+		))
+	}
+
+	Ok(())
+}
+
+/// Revert outputs are either empty or a selector followed by ABI encoded arguments.
+fn is_abi_encoded_revert(output: &[u8]) -> bool {
+	output.is_empty() || (output.len() >= 4 && (output.len() - 4) % 32 == 0)
+}
+
+/// Strategy generating calls to `addresses` by `callers`. Inputs are either random bytes, or
+/// one of `selectors` (or a random one) followed by words biased towards small values, which
+/// reach deeper into the decoding of offsets and lengths.
+pub fn fuzz_call(
+	addresses: Vec<H160>,
+	callers: Vec<H160>,
+	selectors: Vec<u32>,
+) -> BoxedStrategy<FuzzCall> {
+	(
+		proptest::sample::select(addresses),
+		proptest::sample::select(callers),
+		any::<bool>(),
+		any::<bool>(),
+		gas_limit(),
+		calldata(selectors),
+	)
+		.prop_map(|(code_address, caller, payable, is_static, gas_limit, input)| FuzzCall {
+			code_address,
+			caller,
+			value: U256::from(payable as u8),
+			is_static,
+			gas_limit,
+			input,
+		})
+		.boxed()
+}
+
+/// Strategy generating gas limits, from not enough to pay for anything to the whole block.
+pub fn gas_limit() -> BoxedStrategy<u64> {
+	prop_oneof![0..1_000u64, 1_000..1_000_000u64, 1_000_000..30_000_000u64, Just(u64::MAX)].boxed()
+}
+
+/// Strategy generating calldata, see [`fuzz_call`].
+pub fn calldata(selectors: Vec<u32>) -> BoxedStrategy<Vec<u8>> {
+	let selector = if selectors.is_empty() {
+		any::<u32>().boxed()
+	} else {
+		prop_oneof![1 => any::<u32>(), 4 => proptest::sample::select(selectors)].boxed()
+	};
+
+	let word = prop_oneof![
+		(0..1024u64).prop_map(|value| {
+			let mut word = [0u8; 32];
+			word[24..].copy_from_slice(&value.to_be_bytes());
+			word
+		}),
+		any::<[u8; 20]>().prop_map(|address| {
+			let mut word = [0u8; 32];
+			word[12..].copy_from_slice(&address);
+			word
+		}),
+		any::<[u8; 32]>(),
+		Just([0xff; 32]),
+	];
+
+	prop_oneof![
+		vec(any::<u8>(), 0..256),
+		(selector, vec(word, 0..8), vec(any::<u8>(), 0..32)).prop_map(|(selector, words, tail)| {
+			let mut input = selector.to_be_bytes().to_vec();
+			input.extend(words.into_iter().flatten());
+			input.extend(tail);
+			input
+		}),
+	]
+	.boxed()
+}
+
+/// Number of calls checked by [`check_random_calls`].
+pub const RANDOM_CALLS: u32 = 1_000;
+
+/// Check [`RANDOM_CALLS`] calls generated by [`fuzz_call`] with [`check_call`], each one in fresh
+/// externalities returned by `new_externalities`, which can contain some setup.
+pub fn check_random_calls<P: PrecompileSet>(
+	addresses: Vec<H160>,
+	callers: Vec<H160>,
+	selectors: Vec<u32>,
+	new_externalities: impl Fn() -> sp_io::TestExternalities,
+	precompiles: impl Fn() -> P,
+) {
+	run_deterministic(RANDOM_CALLS, fuzz_call(addresses, callers, selectors), |call| {
+		new_externalities().execute_with(|| check_call(&precompiles(), call))?;
+		Ok(())
+	});
+}
+
+/// Run `test` on `cases` values of `strategy` generated with a fixed seed, so runs in CI are
+/// reproducible. Panics with the minimal failing value if the test fails.
+pub fn run_deterministic<S: Strategy>(
+	cases: u32,
+	strategy: S,
+	test: impl Fn(S::Value) -> Result<(), TestCaseError>,
+) {
+	let config = Config { cases, failure_persistence: None, ..Config::default() };
+	let mut runner =
+		TestRunner::new_with_rng(config, TestRng::deterministic_rng(RngAlgorithm::ChaCha));
+
+	if let Err(e) = runner.run(&strategy, test) {
+		panic!("{e}");
+	}
+}
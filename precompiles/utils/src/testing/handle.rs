@@ -148,7 +148,7 @@ impl PrecompileHandle for MockHandle {
 	}
 
 	fn record_cost(&mut self, cost: u64) -> Result<(), ExitError> {
-		self.gas_used += cost;
+		self.gas_used = self.gas_used.saturating_add(cost);
 
 		if self.gas_used > self.gas_limit {
 			Err(ExitError::OutOfGas)
@@ -158,7 +158,7 @@ impl PrecompileHandle for MockHandle {
 	}
 
 	fn remaining_gas(&self) -> u64 {
-		self.gas_limit - self.gas_used
+		self.gas_limit.saturating_sub(self.gas_used)
 	}
 
 	fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) -> Result<(), ExitError> {
@@ -16,6 +16,7 @@
 
 pub mod account;
 pub mod execution;
+pub mod fuzz;
 pub mod handle;
 pub mod modifier;
 mod solidity;
@@ -24,7 +25,10 @@ pub use account::*;
 pub use execution::*;
 pub use handle::*;
 pub use modifier::*;
-pub use solidity::{check_precompile_implements_solidity_interfaces, compute_selector};
+pub use solidity::{
+	check_precompile_implements_solidity_interfaces, check_solidity_interface_matches,
+	compute_selector,
+};
 
 use fp_evm::Log;
 
@@ -16,9 +16,13 @@
 
 //! Utility module to interact with solidity file.
 
+use crate::solidity::{
+	interface::{SolidityInterface, SolidityType},
+	modifier::FunctionModifier,
+};
 use sp_io::hashing::keccak_256;
 use std::{
-	collections::HashMap,
+	collections::{BTreeMap, HashMap, HashSet},
 	fs::File,
 	io::{BufRead, BufReader, Read},
 };
@@ -258,6 +262,309 @@ fn get_selectors_from_reader<R: Read>(reader: R) -> Vec<SolidityFunction> {
 	functions
 }
 
+/// Checks that the functions (and events, if the precompile declares any) of a Solidity file
+/// match the interface generated by the `#[precompile]` macro, which is accessible with
+/// `PCall::solidity_interface()`. Panics with the list of differences and the generated
+/// interface otherwise.
+pub fn check_solidity_interface_matches(file: &str, interface: &SolidityInterface) {
+	let mut content = String::new();
+	File::open(file)
+		.and_then(|mut f| f.read_to_string(&mut content))
+		.unwrap_or_else(|e| panic!("failed reading file '{}': {}", file, e));
+	let declared = parse_solidity_interface(&content);
+
+	let mut errors = vec![];
+
+	let declared_functions: BTreeMap<_, _> =
+		declared.functions.iter().map(|f| (f.signature(), f)).collect();
+	let generated_functions: BTreeMap<_, _> =
+		interface.functions.iter().map(|f| (f.signature(), f)).collect();
+
+	for (signature, generated) in &generated_functions {
+		match declared_functions.get(signature) {
+			None => errors.push(format!("function '{signature}' is missing from the file")),
+			Some(declared) => {
+				if declared.modifier != generated.modifier {
+					errors.push(format!(
+						"function '{signature}' is {:?} in the file but {:?} in the precompile",
+						declared.modifier, generated.modifier
+					));
+				}
+				if declared.outputs != generated.outputs {
+					errors.push(format!(
+						"function '{signature}' returns ({}) in the file but ({}) in the precompile",
+						signatures(&declared.outputs),
+						signatures(&generated.outputs),
+					));
+				}
+			},
+		}
+	}
+	for signature in declared_functions.keys() {
+		if !generated_functions.contains_key(signature) {
+			errors.push(format!("function '{signature}' is not implemented by the precompile"));
+		}
+	}
+
+	if !interface.events.is_empty() {
+		let declared_events: BTreeMap<_, _> =
+			declared.events.iter().map(|e| (e.signature(), e)).collect();
+		let generated_events: BTreeMap<_, _> =
+			interface.events.iter().map(|e| (e.signature(), e)).collect();
+
+		for (signature, generated) in &generated_events {
+			match declared_events.get(signature) {
+				None => errors.push(format!("event '{signature}' is missing from the file")),
+				Some(declared) => {
+					let indexed = |e: &crate::solidity::interface::InterfaceEvent| {
+						e.inputs.iter().map(|param| param.indexed).collect::<Vec<_>>()
+					};
+					if indexed(declared) != indexed(generated) {
+						errors.push(format!(
+							"event '{signature}' has different indexed fields in the file"
+						));
+					}
+				},
+			}
+		}
+		for signature in declared_events.keys() {
+			if !generated_events.contains_key(signature) {
+				errors.push(format!("event '{signature}' is not emitted by the precompile"));
+			}
+		}
+	}
+
+	if !errors.is_empty() {
+		panic!(
+			"file '{file}' doesn't match the precompile interface:\n- {}\n\nGenerated interface:\n{}",
+			errors.join("\n- "),
+			interface.to_solidity(),
+		);
+	}
+}
+
+fn signatures(types: &[SolidityType]) -> String {
+	types.iter().map(SolidityType::signature).collect::<Vec<_>>().join(",")
+}
+
+/// Parses the functions and events declared in the content of a Solidity file.
+/// Structs are resolved to tuples and enums to `uint8`.
+pub fn parse_solidity_interface(content: &str) -> SolidityInterface {
+	use crate::solidity::interface::{EventParam, InterfaceEvent, InterfaceFunction, Param};
+
+	/// Type as written in the file, before custom types are resolved.
+	struct RawType {
+		name: String,
+		array_suffixes: Vec<Option<usize>>,
+	}
+
+	struct Parser {
+		tokens: Vec<String>,
+		index: usize,
+	}
+
+	impl Parser {
+		fn peek(&self) -> Option<&str> {
+			self.tokens.get(self.index).map(String::as_str)
+		}
+
+		fn next(&mut self) -> Option<String> {
+			let token = self.tokens.get(self.index).cloned();
+			self.index += 1;
+			token
+		}
+
+		fn skip_until(&mut self, end: &str) {
+			while let Some(token) = self.next() {
+				if token == end {
+					break
+				}
+			}
+		}
+
+		fn raw_type(&mut self) -> RawType {
+			let name = self.next().unwrap_or_default();
+			let mut array_suffixes = vec![];
+			while self.peek() == Some("[") {
+				self.next();
+				let size = match self.next().as_deref() {
+					Some("]") => None,
+					size => {
+						self.next();
+						size.and_then(|size| size.parse().ok())
+					},
+				};
+				array_suffixes.push(size);
+			}
+			RawType { name, array_suffixes }
+		}
+
+		/// Parses a parenthesized list of parameters as (type, name, indexed).
+		fn params(&mut self) -> Vec<(RawType, String, bool)> {
+			let mut params = vec![];
+			if self.next().as_deref() != Some("(") {
+				return params
+			}
+			if self.peek() == Some(")") {
+				self.next();
+				return params
+			}
+			loop {
+				let ty = self.raw_type();
+				let mut name = String::new();
+				let mut indexed = false;
+				loop {
+					match self.next().as_deref() {
+						Some("memory" | "calldata" | "storage") => (),
+						Some("indexed") => indexed = true,
+						Some(",") => break,
+						Some(")") | None => {
+							params.push((ty, name, indexed));
+							return params
+						},
+						Some(word) => name = word.to_string(),
+					}
+				}
+				params.push((ty, name, indexed));
+			}
+		}
+	}
+
+	fn resolve(
+		raw: &RawType,
+		enums: &HashSet<String>,
+		structs: &HashMap<String, Vec<RawType>>,
+	) -> SolidityType {
+		let mut ty = if enums.contains(&raw.name) {
+			SolidityType::Elementary(String::from("uint8"))
+		} else if let Some(fields) = structs.get(&raw.name) {
+			SolidityType::Tuple(fields.iter().map(|field| resolve(field, enums, structs)).collect())
+		} else {
+			SolidityType::parse(&raw.name)
+				.unwrap_or_else(|| panic!("unknown Solidity type '{}'", raw.name))
+		};
+		for size in &raw.array_suffixes {
+			ty = SolidityType::Array(Box::new(ty), *size);
+		}
+		ty
+	}
+
+	// Remove comments, then split into words and punctuation.
+	let mut code = String::new();
+	let mut rest = content;
+	while !rest.is_empty() {
+		if let Some(after) = rest.strip_prefix("//") {
+			rest = after.find('\n').map(|end| &after[end..]).unwrap_or("");
+		} else if let Some(after) = rest.strip_prefix("/*") {
+			rest = after.find("*/").map(|end| &after[end + 2..]).unwrap_or("");
+		} else {
+			let c = rest.chars().next().expect("rest is not empty; qed");
+			code.push(c);
+			rest = &rest[c.len_utf8()..];
+		}
+	}
+	let mut tokens = vec![];
+	let mut word = String::new();
+	for c in code.chars() {
+		if c.is_ascii_alphanumeric() || c == '_' || c == '$' {
+			word.push(c);
+			continue
+		}
+		if !word.is_empty() {
+			tokens.push(core::mem::take(&mut word));
+		}
+		if "(),;{}[]".contains(c) {
+			tokens.push(c.to_string());
+		}
+	}
+	if !word.is_empty() {
+		tokens.push(word);
+	}
+
+	let mut parser = Parser { tokens, index: 0 };
+	let mut enums = HashSet::new();
+	let mut structs = HashMap::new();
+	let mut raw_functions = vec![];
+	let mut raw_events = vec![];
+	let mut name = String::new();
+
+	while let Some(token) = parser.next() {
+		match token.as_str() {
+			"interface" | "contract" if name.is_empty() => {
+				name = parser.next().unwrap_or_default();
+			},
+			"enum" => {
+				enums.insert(parser.next().unwrap_or_default());
+				parser.skip_until("}");
+			},
+			"struct" => {
+				let struct_name = parser.next().unwrap_or_default();
+				parser.next(); // {
+				let mut fields = vec![];
+				while !matches!(parser.peek(), Some("}") | None) {
+					fields.push(parser.raw_type());
+					parser.skip_until(";");
+				}
+				parser.next(); // }
+				structs.insert(struct_name, fields);
+			},
+			"function" => {
+				let fn_name = parser.next().unwrap_or_default();
+				let inputs = parser.params();
+				let mut modifier = FunctionModifier::NonPayable;
+				let mut outputs = vec![];
+				loop {
+					match parser.peek() {
+						Some("view" | "pure") => modifier = FunctionModifier::View,
+						Some("payable") => modifier = FunctionModifier::Payable,
+						Some("returns") => {
+							parser.next();
+							outputs = parser.params();
+							continue
+						},
+						Some(";") | None => break,
+						_ => (),
+					}
+					parser.next();
+				}
+				raw_functions.push((fn_name, inputs, modifier, outputs));
+			},
+			"event" => {
+				let event_name = parser.next().unwrap_or_default();
+				let inputs = parser.params();
+				parser.skip_until(";");
+				raw_events.push((event_name, inputs));
+			},
+			_ => (),
+		}
+	}
+
+	let mut interface = SolidityInterface::new(&name);
+	for (fn_name, inputs, modifier, outputs) in raw_functions {
+		let mut function = InterfaceFunction::new(&fn_name, 0, modifier);
+		function.inputs = inputs
+			.iter()
+			.map(|(ty, name, _)| Param { name: name.clone(), ty: resolve(ty, &enums, &structs) })
+			.collect();
+		function.outputs = outputs.iter().map(|(ty, _, _)| resolve(ty, &enums, &structs)).collect();
+		function.selector = compute_selector(&function.signature());
+		interface = interface.function(function);
+	}
+	for (event_name, inputs) in raw_events {
+		let mut event = InterfaceEvent::new(&event_name);
+		event.inputs = inputs
+			.iter()
+			.map(|(ty, name, indexed)| EventParam {
+				name: name.clone(),
+				ty: resolve(ty, &enums, &structs),
+				indexed: *indexed,
+			})
+			.collect();
+		interface = interface.event(event);
+	}
+	interface
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -351,4 +658,96 @@ mod tests {
 
 		assert_eq!(expected, actual);
 	}
+
+	#[test]
+	fn test_interface_is_parsed() {
+		let content = std::fs::read_to_string("tests/solidity_test.sol").unwrap();
+		let interface = parse_solidity_interface(&content);
+
+		assert_eq!(interface.name, "SolidityTest");
+		assert_eq!(
+			interface.functions.iter().map(|f| f.signature()).collect::<Vec<_>>(),
+			get_selectors("tests/solidity_test.sol")
+				.iter()
+				.map(SolidityFunction::signature)
+				.collect::<Vec<_>>(),
+		);
+	}
+
+	#[test]
+	fn test_generated_interface_round_trips() {
+		use crate::solidity::{
+			codec::{Address, UnboundedBytes},
+			interface::{InterfaceEvent, InterfaceFunction},
+		};
+		use sp_core::U256;
+
+		let interface = SolidityInterface::new("Test")
+			.function(
+				InterfaceFunction::new("balanceOf", 0x70a08231, FunctionModifier::View)
+					.input::<Address>("owner")
+					.output::<U256>(),
+			)
+			.function(
+				InterfaceFunction::new("batch", 0, FunctionModifier::Payable)
+					.input::<Vec<(Address, UnboundedBytes)>>("calls")
+					.output::<(bool, UnboundedBytes)>(),
+			)
+			.function(
+				InterfaceFunction::new("clear", 0, FunctionModifier::NonPayable).output::<()>(),
+			)
+			.event(
+				InterfaceEvent::new("Transfer")
+					.input::<Address>("from", true)
+					.input::<Address>("to", true)
+					.input::<U256>("value", false),
+			);
+
+		let solidity = interface.to_solidity();
+		assert!(solidity.contains("    struct Tuple0 {\n        address field0;\n"));
+		assert!(solidity.contains(
+			"    /// @custom:selector 70a08231\n    function balanceOf(address owner) external \
+			view returns (uint256);\n"
+		));
+		assert!(solidity.contains(
+			"    function batch(Tuple0[] memory calls) external payable returns (bool, bytes \
+			memory);\n"
+		));
+		assert!(solidity.contains("    function clear() external;\n"));
+		assert!(solidity.contains(
+			"    event Transfer(address indexed from, address indexed to, uint256 value);\n"
+		));
+
+		let parsed = parse_solidity_interface(&solidity);
+		assert_eq!(parsed.name, interface.name);
+		assert_eq!(parsed.events, interface.events);
+		for (parsed, generated) in parsed.functions.iter().zip(interface.functions.iter()) {
+			assert_eq!(parsed.signature(), generated.signature());
+			assert_eq!(parsed.modifier, generated.modifier);
+			assert_eq!(parsed.outputs, generated.outputs);
+		}
+		assert_eq!(parsed.functions[1].signature(), "batch((address,bytes)[])");
+
+		let abi = interface.to_abi_json();
+		assert!(abi.contains(
+			"{\"type\":\"function\",\"name\":\"balanceOf\",\"inputs\":[{\"name\":\"owner\",\
+			\"type\":\"address\"}],\"outputs\":[{\"name\":\"\",\"type\":\"uint256\"}],\
+			\"stateMutability\":\"view\"}"
+		));
+		assert!(abi.contains(
+			"{\"name\":\"calls\",\"type\":\"tuple[]\",\"components\":[{\"name\":\"field0\",\
+			\"type\":\"address\"},{\"name\":\"field1\",\"type\":\"bytes\"}]}"
+		));
+		assert!(abi.contains("{\"name\":\"from\",\"type\":\"address\",\"indexed\":true}"));
+	}
+
+	#[test]
+	fn test_solidity_types_are_parsed() {
+		let ty = SolidityType::parse("((uint8,bytes[])[2],uint)[]").unwrap();
+		assert_eq!(ty.signature(), "((uint8,bytes[])[2],uint256)[]");
+		assert!(ty.is_reference());
+		assert!(!SolidityType::parse("bytes32").unwrap().is_reference());
+		assert_eq!(SolidityType::parse("(uint8,"), None);
+		assert_eq!(SolidityType::parse("uint8[x]"), None);
+	}
 }
@@ -1023,3 +1023,645 @@ fn evm_data_solidity_types() {
 	// Struct encode like tuples
 	assert_eq!(MultiLocation::signature(), "(uint8,bytes[])");
 }
+
+#[test]
+fn indexed_topic_of_value_types_is_their_encoding() {
+	use crate::evm::logs::indexed_topic;
+
+	assert_eq!(indexed_topic(H256::repeat_byte(0xab)), H256::repeat_byte(0xab));
+	assert_eq!(indexed_topic(U256::from(42)), H256::from_low_u64_be(42));
+	assert_eq!(
+		indexed_topic(Address(H160::repeat_byte(0x11))),
+		H256::from(H160::repeat_byte(0x11))
+	);
+	assert_eq!(indexed_topic(true), H256::from_low_u64_be(1));
+}
+
+#[test]
+fn indexed_topic_of_dynamic_types_is_their_hash() {
+	use crate::evm::logs::indexed_topic;
+
+	// Content longer than 32 bytes to check the padding is not hashed.
+	let content = b"a string spanning more than one 32 bytes word";
+
+	assert_eq!(
+		indexed_topic(UnboundedBytes::from(&content[..])),
+		H256(sp_io::hashing::keccak_256(content))
+	);
+	assert_eq!(
+		indexed_topic(UnboundedString::from(&content[..])),
+		H256(sp_io::hashing::keccak_256(content))
+	);
+	assert_eq!(
+		indexed_topic(UnboundedBytes::from(&b""[..])),
+		H256(sp_io::hashing::keccak_256(b""))
+	);
+}
+
+#[test]
+fn custom_error_selector_matches_solidity() {
+	use crate::solidity::revert::{custom_error_selector, custom_error_signature};
+
+	let signature =
+		custom_error_signature("InsufficientBalance", &[U256::signature(), U256::signature()]);
+	assert_eq!(signature, "InsufficientBalance(uint256,uint256)");
+	assert_eq!(custom_error_selector(&signature), 0xcf479181);
+
+	let signature = custom_error_signature("Unauthorized", &[]);
+	assert_eq!(signature, "Unauthorized()");
+	assert_eq!(custom_error_selector(&signature), 0x82b42900);
+}
+
+mod abi_reference {
+	//! Round-trip property tests of the `Codec` implementations against the `ethabi` reference
+	//! encoder, covering every ABI v2 type: all `intN`/`uintN` widths, `bytesN`, `T[N]` and nested
+	//! dynamic structs.
+
+	use super::*;
+	use core::fmt::Debug;
+	use ethabi::{param_type::Reader as ParamReader, Token};
+	use proptest::prelude::*;
+
+	/// Checks that `value` is encoded like `token` by the reference encoder, that the reference
+	/// encoding is decoded back to `value`, and that the signature is understood by the reference
+	/// encoder as the type of `token`.
+	fn check_round_trip<T: solidity::Codec + Clone + Debug + PartialEq>(value: T, token: Token) {
+		let reference = ethabi::encode(core::slice::from_ref(&token));
+		assert_eq!(Writer::new().write(value.clone()).build(), reference, "{}", T::signature());
+		assert_eq!(Reader::new(&reference).read::<T>().expect("to decode"), value);
+
+		let param_type = ParamReader::read(&T::signature()).expect("valid signature");
+		assert_eq!(ethabi::decode(&[param_type], &reference).expect("to decode"), vec![token]);
+	}
+
+	fn sign_extend(raw: U256, bits: usize) -> U256 {
+		if bits == 256 {
+			return raw
+		}
+
+		let value = raw & ((U256::one() << bits) - 1);
+		if value.bit(bits - 1) {
+			value | (U256::MAX << bits)
+		} else {
+			value
+		}
+	}
+
+	fn truncate(raw: U256, bits: usize) -> U256 {
+		if bits == 256 {
+			raw
+		} else {
+			raw & ((U256::one() << bits) - 1)
+		}
+	}
+
+	macro_rules! for_all_widths {
+		($check:ident, $raw:expr, $($bits:literal)*) => {
+			$( $check::<$bits>($raw); )*
+		};
+	}
+
+	fn check_uint<const BITS: usize>(raw: U256) {
+		let value = truncate(raw, BITS);
+		check_round_trip(UintN::<BITS>::new(value).unwrap(), Token::Uint(value));
+	}
+
+	fn check_int<const BITS: usize>(raw: U256) {
+		let value = sign_extend(raw, BITS);
+		check_round_trip(IntN::<BITS>::from_raw(value).unwrap(), Token::Int(value));
+	}
+
+	fn check_bytes<const N: usize>(raw: [u8; 32]) {
+		let mut value = [0u8; N];
+		value.copy_from_slice(&raw[..N]);
+		check_round_trip(BytesN(value), Token::FixedBytes(value.to_vec()));
+	}
+
+	#[derive(Clone, Copy, Debug, Eq, PartialEq, solidity::Codec)]
+	enum Status {
+		Pending,
+		Active,
+		Revoked,
+	}
+
+	#[derive(Clone, Debug, Eq, PartialEq, solidity::Codec)]
+	struct Empty {}
+
+	#[derive(Clone, Debug, Eq, PartialEq, solidity::Codec)]
+	struct Leaf {
+		id: i32,
+		tags: Vec<UnboundedBytes>,
+		pair: [u16; 2],
+	}
+
+	#[derive(Clone, Debug, Eq, PartialEq, solidity::Codec)]
+	struct Node {
+		name: UnboundedString,
+		status: Status,
+		code: BytesN<4>,
+		leaves: Vec<Leaf>,
+		fixed_leaves: [Leaf; 2],
+		nested: Vec<[Vec<(u8, UnboundedString)>; 2]>,
+	}
+
+	fn leaf_token(leaf: &Leaf) -> Token {
+		Token::Tuple(vec![
+			Token::Int(sign_extend(U256::from(leaf.id as u32), 32)),
+			Token::Array(
+				leaf.tags.iter().map(|tag| Token::Bytes(tag.as_bytes().to_vec())).collect(),
+			),
+			Token::FixedArray(leaf.pair.iter().map(|x| Token::Uint((*x).into())).collect()),
+		])
+	}
+
+	fn node_token(node: &Node) -> Token {
+		Token::Tuple(vec![
+			Token::String(node.name.as_str().unwrap().into()),
+			Token::Uint((node.status as u8).into()),
+			Token::FixedBytes(node.code.0.to_vec()),
+			Token::Array(node.leaves.iter().map(leaf_token).collect()),
+			Token::FixedArray(node.fixed_leaves.iter().map(leaf_token).collect()),
+			Token::Array(
+				node.nested
+					.iter()
+					.map(|fixed| {
+						Token::FixedArray(
+							fixed
+								.iter()
+								.map(|items| {
+									Token::Array(
+										items
+											.iter()
+											.map(|(a, b)| {
+												Token::Tuple(vec![
+													Token::Uint((*a).into()),
+													Token::String(b.as_str().unwrap().into()),
+												])
+											})
+											.collect(),
+									)
+								})
+								.collect(),
+						)
+					})
+					.collect(),
+			),
+		])
+	}
+
+	fn any_u256() -> impl Strategy<Value = U256> {
+		any::<[u8; 32]>().prop_map(|bytes| U256::from_big_endian(&bytes))
+	}
+
+	fn any_leaf() -> impl Strategy<Value = Leaf> {
+		(any::<i32>(), prop::collection::vec(prop::collection::vec(any::<u8>(), 0..70), 0..3))
+			.prop_flat_map(|(id, tags)| {
+				any::<[u16; 2]>().prop_map(move |pair| Leaf {
+					id,
+					tags: tags.iter().map(|tag| UnboundedBytes::from(&tag[..])).collect(),
+					pair,
+				})
+			})
+	}
+
+	fn any_node() -> impl Strategy<Value = Node> {
+		let status =
+			prop_oneof![Just(Status::Pending), Just(Status::Active), Just(Status::Revoked)];
+		let nested_items = prop::collection::vec((any::<u8>(), ".{0,40}"), 0..3);
+
+		(
+			".{0,40}",
+			status,
+			any::<[u8; 4]>(),
+			prop::collection::vec(any_leaf(), 0..3),
+			[any_leaf(), any_leaf()],
+			prop::collection::vec([nested_items.clone(), nested_items], 0..3),
+		)
+			.prop_map(|(name, status, code, leaves, fixed_leaves, nested)| Node {
+				name: name.as_str().into(),
+				status,
+				code: BytesN(code),
+				leaves,
+				fixed_leaves,
+				nested: nested
+					.into_iter()
+					.map(|fixed| {
+						fixed.map(|items| {
+							items.into_iter().map(|(a, b)| (a, b.as_str().into())).collect()
+						})
+					})
+					.collect(),
+			})
+	}
+
+	proptest! {
+		#[test]
+		fn uint_of_all_widths_round_trip(raw in any_u256()) {
+			for_all_widths!(check_uint, raw,
+				8 16 24 32 40 48 56 64 72 80 88 96 104 112 120 128
+				136 144 152 160 168 176 184 192 200 208 216 224 232 240 248 256
+			);
+		}
+
+		#[test]
+		fn int_of_all_widths_round_trip(raw in any_u256()) {
+			for_all_widths!(check_int, raw,
+				8 16 24 32 40 48 56 64 72 80 88 96 104 112 120 128
+				136 144 152 160 168 176 184 192 200 208 216 224 232 240 248 256
+			);
+		}
+
+		#[test]
+		fn bytes_of_all_sizes_round_trip(raw in any::<[u8; 32]>()) {
+			for_all_widths!(check_bytes, raw,
+				1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16
+				17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32
+			);
+		}
+
+		#[test]
+		fn native_ints_round_trip(
+			a in any::<i8>(),
+			b in any::<i16>(),
+			c in any::<i64>(),
+			d in any::<i128>(),
+		) {
+			check_round_trip(a, Token::Int(sign_extend(U256::from(a as u8), 8)));
+			check_round_trip(b, Token::Int(sign_extend(U256::from(b as u16), 16)));
+			check_round_trip(c, Token::Int(sign_extend(U256::from(c as u64), 64)));
+			check_round_trip(d, Token::Int(sign_extend(U256::from(d as u128), 128)));
+			prop_assert_eq!(IntN::<128>::from_i128(d).unwrap().to_i128(), Some(d));
+		}
+
+		#[test]
+		fn fixed_arrays_round_trip(
+			values in any::<[u32; 3]>(),
+			addresses in any::<[[u8; 20]; 2]>(),
+			strings in [".{0,40}", ".{0,40}"],
+		) {
+			check_round_trip(
+				values,
+				Token::FixedArray(values.iter().map(|x| Token::Uint((*x).into())).collect()),
+			);
+
+			let addresses = addresses.map(|a| Address(H160(a)));
+			let matrix = [addresses, addresses];
+			let address_token =
+				Token::FixedArray(addresses.iter().map(|a| Token::Address(a.0)).collect());
+			check_round_trip(
+				matrix,
+				Token::FixedArray(vec![address_token.clone(), address_token]),
+			);
+
+			let strings_token =
+				Token::FixedArray(strings.iter().map(|s| Token::String(s.clone())).collect());
+			check_round_trip(
+				strings.clone().map(|s| UnboundedString::from(s.as_str())),
+				strings_token,
+			);
+		}
+
+		#[test]
+		fn nested_dynamic_structs_round_trip(node in any_node()) {
+			let token = node_token(&node);
+			check_round_trip(node.clone(), token.clone());
+			check_round_trip(vec![node.clone(), node], Token::Array(vec![token.clone(), token]));
+		}
+	}
+
+	#[test]
+	fn signatures_of_abi_v2_types() {
+		assert_eq!(i8::signature(), "int8");
+		assert_eq!(i128::signature(), "int128");
+		assert_eq!(UintN::<24>::signature(), "uint24");
+		assert_eq!(IntN::<256>::signature(), "int256");
+		assert_eq!(BytesN::<1>::signature(), "bytes1");
+		assert_eq!(<[Address; 3]>::signature(), "address[3]");
+		assert_eq!(<[Vec<u8>; 2]>::signature(), "uint8[][2]");
+		assert_eq!(Vec::<[u8; 2]>::signature(), "uint8[2][]");
+		assert_eq!(Status::signature(), "uint8");
+		assert_eq!(Empty::signature(), "()");
+		assert_eq!(Leaf::signature(), "(int32,bytes[],uint16[2])");
+
+		assert!(<[u8; 2]>::has_static_size());
+		assert!(!<[UnboundedBytes; 2]>::has_static_size());
+		assert!(Empty::has_static_size());
+		assert!(Writer::new().write(Empty {}).build().is_empty());
+		assert_eq!(Reader::new(&[]).read::<Empty>(), Ok(Empty {}));
+		assert!(!Leaf::has_static_size());
+	}
+
+	#[test]
+	fn values_out_of_range_are_rejected() {
+		// 128 doesn't fit in int8 and is not sign extended.
+		let input = Writer::new().write(U256::from(128)).build();
+		assert_eq!(
+			Reader::new(&input).read::<i8>(),
+			Err(RevertReason::value_is_too_large("int8").into())
+		);
+		assert_eq!(
+			Reader::new(&input).read::<IntN<8>>(),
+			Err(RevertReason::value_is_too_large("int8").into())
+		);
+		assert_eq!(Reader::new(&input).read::<i16>(), Ok(128));
+
+		let input = Writer::new().write(U256::one() << 24).build();
+		assert_eq!(
+			Reader::new(&input).read::<UintN<24>>(),
+			Err(RevertReason::value_is_too_large("uint24").into())
+		);
+
+		// Non-zero padding.
+		let input = Writer::new().write(U256::one()).build();
+		assert_eq!(
+			Reader::new(&input).read::<BytesN<4>>(),
+			Err(RevertReason::value_is_too_large("bytes4").into())
+		);
+
+		let input = Writer::new().write(3u8).build();
+		assert_eq!(
+			Reader::new(&input).read::<Status>(),
+			Err(RevertReason::value_is_too_large("Status").into())
+		);
+	}
+
+	#[test]
+	fn fixed_array_reverts_have_backtrace() {
+		let input = Writer::new()
+			.write((0i32, Vec::<UnboundedBytes>::new(), [1u32, 1 << 16]))
+			.build();
+
+		match Reader::new(&input).read::<Leaf>() {
+			Ok(_) => panic!("should fail"),
+			Err(err) => assert_eq!(err.to_string(), "pair[1]: Value is too large for uint16"),
+		}
+	}
+}
+
+mod proof_size {
+	use crate::{mock::*, prelude::*};
+	use fp_evm::{Context, ExitError, ExitReason, PrecompileHandle, Transfer};
+	use frame_support::{dispatch::GetDispatchInfo, weights::Weight};
+	use pallet_evm::GasWeightMapping;
+	use sp_core::{H160, H256, U256};
+
+	/// Handle enforcing the proof size limit of the EVM, which is the gas limit converted with
+	/// `GasLimitPovSizeRatio`.
+	pub(super) struct ExternalCostHandle {
+		pub(super) code_address: H160,
+		gas_limit: u64,
+		gas_used: u64,
+		ref_time_used: u64,
+		proof_size_used: u64,
+		context: Context,
+	}
+
+	impl ExternalCostHandle {
+		pub(super) fn new(gas_limit: u64) -> Self {
+			Self {
+				code_address: H160::zero(),
+				gas_limit,
+				gas_used: 0,
+				ref_time_used: 0,
+				proof_size_used: 0,
+				context: Context {
+					address: H160::zero(),
+					caller: H160::zero(),
+					apparent_value: U256::zero(),
+				},
+			}
+		}
+	}
+
+	impl PrecompileHandle for ExternalCostHandle {
+		fn call(
+			&mut self,
+			_: H160,
+			_: Option<Transfer>,
+			_: Vec<u8>,
+			_: Option<u64>,
+			_: bool,
+			_: &Context,
+		) -> (ExitReason, Vec<u8>) {
+			unimplemented!()
+		}
+
+		fn record_cost(&mut self, cost: u64) -> Result<(), ExitError> {
+			if self.gas_used.saturating_add(cost) > self.gas_limit {
+				return Err(ExitError::OutOfGas)
+			}
+			self.gas_used += cost;
+			Ok(())
+		}
+
+		fn record_external_cost(
+			&mut self,
+			ref_time: Option<u64>,
+			proof_size: Option<u64>,
+			_storage_growth: Option<u64>,
+		) -> Result<(), ExitError> {
+			let proof_size_used =
+				self.proof_size_used.saturating_add(proof_size.unwrap_or_default());
+			if proof_size_used.saturating_mul(GAS_PER_PROOF_BYTE) > self.gas_limit {
+				return Err(ExitError::OutOfGas)
+			}
+			self.ref_time_used = self.ref_time_used.saturating_add(ref_time.unwrap_or_default());
+			self.proof_size_used = proof_size_used;
+			Ok(())
+		}
+
+		fn refund_external_cost(&mut self, ref_time: Option<u64>, proof_size: Option<u64>) {
+			self.ref_time_used = self.ref_time_used.saturating_sub(ref_time.unwrap_or_default());
+			self.proof_size_used =
+				self.proof_size_used.saturating_sub(proof_size.unwrap_or_default());
+		}
+
+		fn remaining_gas(&self) -> u64 {
+			self.gas_limit - self.gas_used
+		}
+
+		fn log(&mut self, _: H160, _: Vec<H256>, _: Vec<u8>) -> Result<(), ExitError> {
+			Ok(())
+		}
+
+		fn code_address(&self) -> H160 {
+			self.code_address
+		}
+
+		fn input(&self) -> &[u8] {
+			&[]
+		}
+
+		fn context(&self) -> &Context {
+			&self.context
+		}
+
+		fn is_static(&self) -> bool {
+			false
+		}
+
+		fn gas_limit(&self) -> Option<u64> {
+			Some(self.gas_limit)
+		}
+	}
+
+	#[test]
+	fn external_cost_records_ref_time_and_proof_size() {
+		let mut handle = ExternalCostHandle::new(1_000);
+
+		assert_eq!(
+			RuntimeHelper::<Runtime>::record_external_cost(
+				&mut handle,
+				Weight::from_parts(100, 200),
+				0
+			),
+			Ok(())
+		);
+		assert_eq!(handle.ref_time_used, 100);
+		assert_eq!(handle.proof_size_used, 200);
+	}
+
+	#[test]
+	fn external_cost_fails_above_proof_size_budget() {
+		let mut handle = ExternalCostHandle::new(1_000);
+
+		assert_eq!(
+			RuntimeHelper::<Runtime>::record_external_cost(
+				&mut handle,
+				Weight::from_parts(100, 251),
+				0
+			),
+			Err(ExitError::OutOfGas)
+		);
+		assert_eq!(handle.ref_time_used, 0);
+		assert_eq!(handle.proof_size_used, 0);
+	}
+
+	#[test]
+	fn external_cost_fails_once_the_proof_size_budget_is_used() {
+		let mut handle = ExternalCostHandle::new(1_000);
+		let weight = Weight::from_parts(100, 200);
+		RuntimeHelper::<Runtime>::record_external_cost(&mut handle, weight, 0).unwrap();
+
+		// The gas is not used, but the proof size budget of the transaction is.
+		assert_eq!(handle.remaining_gas(), 1_000);
+		assert_eq!(
+			RuntimeHelper::<Runtime>::record_external_cost(
+				&mut handle,
+				Weight::from_parts(100, 51),
+				0
+			),
+			Err(ExitError::OutOfGas)
+		);
+		assert_eq!(handle.proof_size_used, 200);
+	}
+
+	#[test]
+	fn unused_proof_size_is_refunded() {
+		let mut handle = ExternalCostHandle::new(1_000);
+		let weight = Weight::from_parts(100, 200);
+		RuntimeHelper::<Runtime>::record_external_cost(&mut handle, weight, 0).unwrap();
+
+		// The call used more ref time than estimated but less proof size.
+		assert_eq!(
+			RuntimeHelper::<Runtime>::refund_weight_v2_cost(
+				&mut handle,
+				weight,
+				Some(Weight::from_parts(120, 50))
+			),
+			Ok(120)
+		);
+		assert_eq!(handle.gas_used, 120);
+		assert_eq!(handle.ref_time_used, 100);
+		assert_eq!(handle.proof_size_used, 50);
+	}
+
+	#[test]
+	fn try_dispatch_records_weight_of_the_call() {
+		new_test_ext().execute_with(|| {
+			let mut handle = ExternalCostHandle::new(1_000_000_000);
+			let call = frame_system::Call::<Runtime>::remark { remark: vec![1u8; 100] };
+			let weight = call.get_dispatch_info().weight;
+
+			RuntimeHelper::<Runtime>::try_dispatch(
+				&mut handle,
+				RuntimeOrigin::signed(AccountId::new([1u8; 32])),
+				call,
+				0,
+			)
+			.expect("remark succeeds");
+
+			assert_eq!(
+				handle.gas_used,
+				<Runtime as pallet_evm::Config>::GasWeightMapping::weight_to_gas(weight)
+			);
+			assert_eq!(handle.ref_time_used, weight.ref_time());
+			assert_eq!(handle.proof_size_used, weight.proof_size());
+		});
+	}
+}
+
+mod disabled_precompiles {
+	use super::proof_size::ExternalCostHandle;
+	use crate::{mock::Runtime, precompile_set::*, solidity::revert::revert};
+	use fp_evm::{IsPrecompileResult, PrecompileResult};
+	use frame_support::traits::Contains;
+	use sp_core::H160;
+	use std::{cell::RefCell, collections::BTreeSet};
+
+	thread_local! {
+		static DISABLED: RefCell<BTreeSet<H160>> = RefCell::new(BTreeSet::new());
+	}
+
+	struct Disabled;
+
+	impl Contains<H160> for Disabled {
+		fn contains(address: &H160) -> bool {
+			DISABLED.with(|disabled| disabled.borrow().contains(address))
+		}
+	}
+
+	fn disable(address: H160) {
+		DISABLED.with(|disabled| disabled.borrow_mut().insert(address));
+	}
+
+	type Precompiles = DisableablePrecompiles<Disabled, RevertPrecompile<AddressU64<1>>>;
+
+	fn execute(address: H160) -> Option<PrecompileResult> {
+		let mut handle = ExternalCostHandle::new(1_000_000);
+		handle.code_address = address;
+		Precompiles::new().execute::<Runtime>(&mut handle)
+	}
+
+	#[test]
+	fn enabled_precompile_is_executed() {
+		assert_eq!(execute(H160::from_low_u64_be(1)), Some(Err(revert("revert"))));
+		assert!(matches!(
+			Precompiles::new().is_active_precompile(H160::from_low_u64_be(1), 0),
+			IsPrecompileResult::Answer { is_precompile: true, .. }
+		));
+	}
+
+	#[test]
+	fn disabled_precompile_reverts() {
+		disable(H160::from_low_u64_be(1));
+
+		assert_eq!(execute(H160::from_low_u64_be(1)), Some(Err(revert("Disabled precompile"))));
+		assert!(matches!(
+			Precompiles::new().is_precompile(H160::from_low_u64_be(1), 0),
+			IsPrecompileResult::Answer { is_precompile: true, .. }
+		));
+		assert!(matches!(
+			Precompiles::new().is_active_precompile(H160::from_low_u64_be(1), 0),
+			IsPrecompileResult::Answer { is_precompile: false, .. }
+		));
+	}
+
+	#[test]
+	fn disabling_other_addresses_has_no_effect() {
+		disable(H160::from_low_u64_be(2));
+
+		assert_eq!(execute(H160::from_low_u64_be(1)), Some(Err(revert("revert"))));
+		assert_eq!(execute(H160::from_low_u64_be(2)), None);
+	}
+}
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pragma solidity >=0.8.3;
+
+address constant PRECOMPILE_ADDR = address(0x0000000000000000000000000000000000000805);
+
+XcmUtils constant XCM_UTILS_CONTRACT = XcmUtils(PRECOMPILE_ADDR);
+
+interface XcmUtils {
+
+    /// A location is defined by its number of parents and the encoded junctions (interior).
+    struct Location {
+        uint8 parents;
+        bytes[] interior;
+    }
+
+    /// @dev Returns the account of a location, as used for its XCM sovereign account.
+    /// @custom:selector 343b3e00
+    function multilocationToAddress(
+        Location memory location
+    ) external view returns (bytes32 account);
+
+    /// @dev Returns the ref time weight of a SCALE encoded VersionedXcm message.
+    /// @custom:selector 25d54154
+    function weightMessage(
+        bytes memory message
+    ) external view returns (uint64 weight);
+
+    /// @dev Sends a SCALE encoded VersionedXcm message to dest, from the location of the caller.
+    /// @custom:selector 98600e64
+    function xcmSend(
+        Location memory dest,
+        bytes memory message
+    ) external;
+
+    /// @dev Executes a SCALE encoded VersionedXcm message with the origin of the caller.
+    /// maxWeight is the ref time the message is allowed to use.
+    /// @custom:selector 34334a02
+    function xcmExecute(
+        bytes memory message,
+        uint64 maxWeight
+    ) external;
+}
@@ -0,0 +1,137 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Precompile allowing contracts to weigh, send and execute XCM messages.
+//!
+//! Messages are sent and executed through `pallet_xcm` with the origin of the caller, so the
+//! `XcmExecuteFilter` of `pallet_xcm` and the `SafeCallFilter` of the XCM executor apply as they
+//! do for extrinsics.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use fp_evm::PrecompileHandle;
+use frame_support::{
+	dispatch::{Dispatchable, GetCallMetadata, GetDispatchInfo, PostDispatchInfo},
+	traits::ConstU32,
+	weights::Weight,
+};
+use pallet_evm::AddressMapping;
+use parity_scale_codec::{Decode, DecodeLimit};
+use precompile_utils::prelude::*;
+use sp_core::H256;
+use sp_std::{boxed::Box, marker::PhantomData, vec::Vec};
+use xcm::{
+	latest::{MultiLocation, Xcm},
+	VersionedXcm, MAX_XCM_DECODE_DEPTH,
+};
+use xcm_executor::traits::{Convert as XcmConvert, WeightBounds};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+type AccountIdOf<Runtime> = <Runtime as frame_system::Config>::AccountId;
+type CallOf<Runtime> = <Runtime as frame_system::Config>::RuntimeCall;
+
+/// Maximum size of an encoded XCM message.
+pub const XCM_SIZE_LIMIT: u32 = 2u32.pow(16);
+type GetXcmSizeLimit = ConstU32<XCM_SIZE_LIMIT>;
+
+/// Proof size allowed to a message executed with `xcmExecute`, which only takes the ref time
+/// returned by `weightMessage`.
+pub const XCM_EXECUTE_PROOF_SIZE: u64 = 256 * 1024;
+
+pub struct XcmUtilsPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> XcmUtilsPrecompile<Runtime>
+where
+	Runtime: pallet_evm::Config + pallet_xcm::Config + frame_system::pallet::Config,
+	CallOf<Runtime>:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode + GetCallMetadata,
+	CallOf<Runtime>: From<pallet_xcm::Call<Runtime>>,
+	<CallOf<Runtime> as Dispatchable>::RuntimeOrigin: From<Option<AccountIdOf<Runtime>>>,
+	AccountIdOf<Runtime>: Into<[u8; 32]>,
+{
+	/// Account of a location on this chain, as used for its XCM sovereign account.
+	#[precompile::public("multilocationToAddress((uint8,bytes[]))")]
+	#[precompile::view]
+	fn multilocation_to_address(
+		_handle: &mut impl PrecompileHandle,
+		location: MultiLocation,
+	) -> EvmResult<H256> {
+		let account = <Runtime as pallet_xcm::Config>::SovereignAccountOf::convert(location)
+			.map_err(|_| {
+				RevertReason::custom("Failed multilocation conversion").in_field("location")
+			})?;
+
+		Ok(H256(account.into()))
+	}
+
+	/// Ref time weight of a SCALE encoded `VersionedXcm` message, according to the runtime
+	/// XCM weigher.
+	#[precompile::public("weightMessage(bytes)")]
+	#[precompile::view]
+	fn weight_message(
+		_handle: &mut impl PrecompileHandle,
+		message: BoundedBytes<GetXcmSizeLimit>,
+	) -> EvmResult<u64> {
+		let mut message = Xcm::<CallOf<Runtime>>::try_from(Self::decode_message(message)?)
+			.map_err(|_| RevertReason::custom("Unsupported XCM version").in_field("message"))?;
+
+		let weight = <Runtime as pallet_xcm::Config>::Weigher::weight(&mut message)
+			.map_err(|_| revert("Failed weighting message"))?;
+
+		Ok(weight.ref_time())
+	}
+
+	#[precompile::public("xcmSend((uint8,bytes[]),bytes)")]
+	fn xcm_send(
+		handle: &mut impl PrecompileHandle,
+		dest: MultiLocation,
+		message: BoundedBytes<GetXcmSizeLimit>,
+	) -> EvmResult {
+		let message = Self::decode_message::<()>(message)?;
+
+		let caller = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let call = pallet_xcm::Call::<Runtime>::send {
+			dest: Box::new(dest.into()),
+			message: Box::new(message),
+		};
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(caller).into(), call, 0)?;
+
+		Ok(())
+	}
+
+	#[precompile::public("xcmExecute(bytes,uint64)")]
+	fn xcm_execute(
+		handle: &mut impl PrecompileHandle,
+		message: BoundedBytes<GetXcmSizeLimit>,
+		max_weight: u64,
+	) -> EvmResult {
+		let message = Self::decode_message::<CallOf<Runtime>>(message)?;
+
+		let caller = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let call = pallet_xcm::Call::<Runtime>::execute {
+			message: Box::new(message),
+			max_weight: Weight::from_parts(max_weight, XCM_EXECUTE_PROOF_SIZE),
+		};
+
+		RuntimeHelper::<Runtime>::try_dispatch(handle, Some(caller).into(), call, 0)?;
+
+		Ok(())
+	}
+
+	fn decode_message<Call: Decode>(
+		message: BoundedBytes<GetXcmSizeLimit>,
+	) -> MayRevert<VersionedXcm<Call>> {
+		let message: Vec<u8> = message.into();
+
+		VersionedXcm::<Call>::decode_all_with_depth_limit(
+			MAX_XCM_DECODE_DEPTH,
+			&mut message.as_slice(),
+		)
+		.map_err(|_| RevertReason::custom("Failed decoding").in_field("message"))
+	}
+}
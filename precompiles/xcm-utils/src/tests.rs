@@ -0,0 +1,218 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use parity_scale_codec::Encode;
+use precompile_utils::testing::*;
+use sp_core::H160;
+use xcm::latest::prelude::*;
+
+use crate::{mock::*, *};
+
+fn precompile_address() -> H160 {
+	H160::from_low_u64_be(1)
+}
+
+fn remark_message(remark: &[u8]) -> Vec<u8> {
+	let call =
+		RuntimeCall::System(frame_system::Call::remark_with_event { remark: remark.to_vec() });
+	VersionedXcm::V3(Xcm::<RuntimeCall>(vec![Transact {
+		origin_kind: OriginKind::SovereignAccount,
+		require_weight_at_most: Weight::from_parts(1_000_000_000, 64 * 1024),
+		call: call.encode().into(),
+	}]))
+	.encode()
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::multilocation_to_address_selectors().contains(&0x343b3e00));
+	assert!(PCall::weight_message_selectors().contains(&0x25d54154));
+	assert!(PCall::xcm_send_selectors().contains(&0x98600e64));
+	assert!(PCall::xcm_execute_selectors().contains(&0x34334a02));
+}
+
+#[test]
+fn modifiers() {
+	new_test_ext().execute_with(|| {
+		let mut tester = PrecompilesModifierTester::new(precompiles(), Alice, precompile_address());
+
+		tester.test_view_modifier(PCall::multilocation_to_address_selectors());
+		tester.test_view_modifier(PCall::weight_message_selectors());
+		tester.test_default_modifier(PCall::xcm_send_selectors());
+		tester.test_default_modifier(PCall::xcm_execute_selectors());
+	});
+}
+
+#[test]
+fn multilocation_to_address_converts_local_account() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::multilocation_to_address { location: location_of(Alice) },
+			)
+			.expect_no_logs()
+			.execute_returns(H256(account_of(Alice).into()));
+	});
+}
+
+#[test]
+fn multilocation_to_address_converts_parent() {
+	new_test_ext().execute_with(|| {
+		let mut account = [0u8; 32];
+		account[..6].copy_from_slice(b"Parent");
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::multilocation_to_address { location: MultiLocation::parent() },
+			)
+			.expect_no_logs()
+			.execute_returns(H256(account));
+	});
+}
+
+#[test]
+fn multilocation_to_address_reverts_for_unknown_location() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::multilocation_to_address {
+					location: MultiLocation { parents: 1, interior: X1(Parachain(2000)) },
+				},
+			)
+			.execute_reverts(|output| output == b"location: Failed multilocation conversion");
+	});
+}
+
+#[test]
+fn weight_message_uses_runtime_weigher() {
+	new_test_ext().execute_with(|| {
+		let message = VersionedXcm::V3(Xcm::<()>(vec![ClearOrigin, ClearOrigin])).encode();
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::weight_message { message: message.into() },
+			)
+			.expect_no_logs()
+			.execute_returns(UnitWeightCost::get().ref_time() * 2);
+	});
+}
+
+#[test]
+fn weight_message_reverts_for_invalid_message() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::weight_message { message: vec![0xff; 4].into() },
+			)
+			.execute_reverts(|output| output == b"message: Failed decoding");
+	});
+}
+
+#[test]
+fn xcm_send_descends_to_caller_origin() {
+	new_test_ext().execute_with(|| {
+		let message = VersionedXcm::V3(Xcm::<()>(vec![ClearOrigin])).encode();
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::xcm_send { dest: MultiLocation::parent(), message: message.into() },
+			)
+			.execute_returns(());
+
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				MultiLocation::parent(),
+				Xcm(vec![DescendOrigin(location_of(Alice).interior), ClearOrigin]),
+			)]
+		);
+	});
+}
+
+#[test]
+fn xcm_execute_transacts_with_caller_origin() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::xcm_execute {
+					message: remark_message(b"peaq").into(),
+					max_weight: 2_000_000_000,
+				},
+			)
+			.execute_returns(());
+
+		assert!(System::events().iter().any(|record| matches!(
+			&record.event,
+			RuntimeEvent::System(frame_system::Event::Remarked { sender, .. })
+				if *sender == account_of(Alice)
+		)));
+	});
+}
+
+#[test]
+fn xcm_execute_respects_safe_call_filter() {
+	new_test_ext().execute_with(|| {
+		let call = RuntimeCall::System(frame_system::Call::remark { remark: b"peaq".to_vec() });
+		let message = VersionedXcm::V3(Xcm::<RuntimeCall>(vec![Transact {
+			origin_kind: OriginKind::SovereignAccount,
+			require_weight_at_most: Weight::from_parts(1_000_000_000, 64 * 1024),
+			call: call.encode().into(),
+		}]))
+		.encode();
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::xcm_execute { message: message.into(), max_weight: 2_000_000_000 },
+			)
+			.execute_returns(());
+
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::PolkadotXcm(pallet_xcm::Event::Attempted(Outcome::Incomplete(
+				_,
+				XcmError::NoPermission
+			)))
+		)));
+	});
+}
+
+#[test]
+fn xcm_execute_respects_execute_filter() {
+	new_test_ext().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::xcm_execute {
+					message: remark_message(b"peaq").into(),
+					max_weight: 2_000_000_000,
+				},
+			)
+			.execute_reverts(|output| output.starts_with(b"Dispatched call failed with error:"));
+	});
+}
+
+#[test]
+fn test_solidity_interface_has_all_function_selectors_documented_and_implemented() {
+	check_precompile_implements_solidity_interfaces(&["XcmUtils.sol"], PCall::supports_selector)
+}
+
+#[test]
+fn test_solidity_interface_matches_precompile() {
+	check_solidity_interface_matches("XcmUtils.sol", &PCall::solidity_interface())
+}
@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+pragma solidity >=0.8.3;
+
+address constant PRECOMPILE_ADDR = address(0x0000000000000000000000000000000000000803);
+
+ZenlinkDex constant ZENLINK_DEX_CONTRACT = ZenlinkDex(PRECOMPILE_ADDR);
+
+interface ZenlinkDex {
+    struct PairInfo {
+        address lp_token;
+        uint256 reserve0;
+        uint256 reserve1;
+        uint256 total_liquidity;
+    }
+
+    /// @dev Returns the pair of two assets, reserves ordered as the given assets.
+    /// @custom:selector e6a43905
+    function getPair(
+        address asset0,
+        address asset1
+    ) external view returns (PairInfo memory);
+
+    /// @dev Returns the amounts along the path when swapping amount_in of its first asset.
+    /// @custom:selector d06ca61f
+    function getAmountsOut(
+        uint256 amount_in,
+        address[] memory path
+    ) external view returns (uint256[] memory);
+
+    /// @dev Returns the amounts along the path needed to receive amount_out of its last asset.
+    /// @custom:selector 1f00ca74
+    function getAmountsIn(
+        uint256 amount_out,
+        address[] memory path
+    ) external view returns (uint256[] memory);
+
+    /// @dev Swaps an exact amount of the first asset of the path for as many of the last one.
+    /// @custom:selector 48f34771
+    function swapExactAssetsForAssets(
+        uint256 amount_in,
+        uint256 amount_out_min,
+        address[] memory path,
+        address to,
+        uint32 deadline
+    ) external returns (bool);
+
+    /// @dev Swaps as few of the first asset of the path for an exact amount of the last one.
+    /// @custom:selector f8770837
+    function swapAssetsForExactAssets(
+        uint256 amount_out,
+        uint256 amount_in_max,
+        address[] memory path,
+        address to,
+        uint32 deadline
+    ) external returns (bool);
+
+    /// @dev Adds liquidity to the pair of two assets.
+    /// @custom:selector 44e651db
+    function addLiquidity(
+        address asset0,
+        address asset1,
+        uint256 amount0_desired,
+        uint256 amount1_desired,
+        uint256 amount0_min,
+        uint256 amount1_min,
+        uint32 deadline
+    ) external returns (bool);
+
+    /// @dev Removes liquidity from the pair of two assets.
+    /// @custom:selector 81744501
+    function removeLiquidity(
+        address asset0,
+        address asset1,
+        uint256 liquidity,
+        uint256 amount0_min,
+        uint256 amount1_min,
+        address to,
+        uint32 deadline
+    ) external returns (bool);
+
+    event Swap(
+        address indexed sender,
+        address indexed to,
+        address[] path,
+        uint256 amount_in,
+        uint256 amount_out
+    );
+    event LiquidityAdded(
+        address indexed provider,
+        address indexed asset0,
+        address indexed asset1,
+        uint256 amount0,
+        uint256 amount1
+    );
+    event LiquidityRemoved(
+        address indexed provider,
+        address indexed asset0,
+        address indexed asset1,
+        uint256 amount0,
+        uint256 amount1
+    );
+}
@@ -0,0 +1,474 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use fp_evm::PrecompileHandle;
+use frame_support::{
+	dispatch::{Dispatchable, GetCallMetadata, GetDispatchInfo, PostDispatchInfo},
+	sp_runtime::traits::{Convert, StaticLookup},
+	traits::ConstU32,
+};
+use pallet_evm::AddressMapping;
+use pallet_evm_precompile_assets_erc20::EVMAddressToAssetId;
+use peaq_primitives_xcm::{AssetId, AssetIdToZenlinkId};
+use precompile_utils::prelude::*;
+use sp_core::{H160, U256};
+use sp_std::{convert::TryFrom, marker::PhantomData, vec, vec::Vec};
+use zenlink_protocol::{AssetBalance, AssetId as ZenlinkAssetId, MultiAssetsHandler};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Solidity selector of the Swap log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_SWAP: [u8; 32] = Swap::TOPIC;
+
+/// Solidity selector of the LiquidityAdded log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_LIQUIDITY_ADDED: [u8; 32] = LiquidityAdded::TOPIC;
+
+/// Solidity selector of the LiquidityRemoved log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_LIQUIDITY_REMOVED: [u8; 32] = LiquidityRemoved::TOPIC;
+
+#[precompile::event("Swap(address indexed,address indexed,address[],uint256,uint256)")]
+pub struct Swap {
+	pub sender: Address,
+	pub to: Address,
+	pub path: Vec<Address>,
+	pub amount_in: U256,
+	pub amount_out: U256,
+}
+
+#[precompile::event(
+	"LiquidityAdded(address indexed,address indexed,address indexed,uint256,uint256)"
+)]
+pub struct LiquidityAdded {
+	pub provider: Address,
+	pub asset0: Address,
+	pub asset1: Address,
+	pub amount0: U256,
+	pub amount1: U256,
+}
+
+#[precompile::event(
+	"LiquidityRemoved(address indexed,address indexed,address indexed,uint256,uint256)"
+)]
+pub struct LiquidityRemoved {
+	pub provider: Address,
+	pub asset0: Address,
+	pub asset1: Address,
+	pub amount0: U256,
+	pub amount1: U256,
+}
+
+type AccountIdOf<Runtime> = <Runtime as frame_system::Config>::AccountId;
+type BlockNumberOf<Runtime> = <Runtime as frame_system::Config>::BlockNumber;
+type SelfParaIdOf<Runtime> = <Runtime as zenlink_protocol::Config>::SelfParaId;
+
+type GetPathLimit = ConstU32<8>;
+
+/// Reads done by Zenlink for every hop of a path: pair status and both reserves.
+const READS_PER_HOP: u64 = 3;
+
+#[derive(Default, Debug, solidity::Codec)]
+pub struct EVMPairInfo {
+	lp_token: Address,
+	reserve0: U256,
+	reserve1: U256,
+	total_liquidity: U256,
+}
+
+/// Precompile exposing Zenlink swaps and liquidity management to the EVM.
+/// Assets are addressed by their ERC20 precompile address, the native token being
+/// the address of `AssetId::Token(0)`.
+pub struct ZenlinkDexPrecompile<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+#[precompile::events(Swap, LiquidityAdded, LiquidityRemoved)]
+impl<Runtime> ZenlinkDexPrecompile<Runtime>
+where
+	Runtime: zenlink_protocol::Config<AssetId = ZenlinkAssetId>
+		+ pallet_evm::Config
+		+ frame_system::Config,
+	Runtime: EVMAddressToAssetId<AssetId>,
+	Runtime::RuntimeCall:
+		Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + GetCallMetadata,
+	Runtime::RuntimeCall: From<zenlink_protocol::Call<Runtime>>,
+	<Runtime::RuntimeCall as Dispatchable>::RuntimeOrigin: From<Option<AccountIdOf<Runtime>>>,
+	BlockNumberOf<Runtime>: From<u32>,
+{
+	#[precompile::public("getPair(address,address)")]
+	#[precompile::view]
+	fn get_pair(
+		handle: &mut impl PrecompileHandle,
+		asset0: Address,
+		asset1: Address,
+	) -> EvmResult<EVMPairInfo> {
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost() * READS_PER_HOP)?;
+
+		let asset0 = Self::address_to_zenlink_id(asset0).in_field("asset0")?;
+		let asset1 = Self::address_to_zenlink_id(asset1).in_field("asset1")?;
+
+		let pair = zenlink_protocol::Pallet::<Runtime>::get_pair_by_asset_id(asset0, asset1)
+			.ok_or_else(|| revert("Pair does not exist"))?;
+		let (reserve0, reserve1) = if pair.asset_0 == asset0 {
+			(pair.reserve_0, pair.reserve_1)
+		} else {
+			(pair.reserve_1, pair.reserve_0)
+		};
+		let lp_token = AssetId::try_from(pair.lp_asset_id)
+			.map(|asset_id| Address(Runtime::asset_id_to_address(asset_id)))
+			.map_err(|_| revert("Pair has no LP token"))?;
+
+		Ok(EVMPairInfo {
+			lp_token,
+			reserve0: reserve0.into(),
+			reserve1: reserve1.into(),
+			total_liquidity: pair.total_liquidity.into(),
+		})
+	}
+
+	#[precompile::public("getAmountsOut(uint256,address[])")]
+	#[precompile::view]
+	fn get_amounts_out(
+		handle: &mut impl PrecompileHandle,
+		amount_in: U256,
+		path: BoundedVec<Address, GetPathLimit>,
+	) -> EvmResult<Vec<U256>> {
+		let amount_in = Self::u256_to_amount(amount_in).in_field("amountIn")?;
+		let path = Self::path_to_zenlink_ids(path).in_field("path")?;
+		handle.record_cost(Self::quote_cost(&path))?;
+
+		let amounts = Self::amounts_out(amount_in, &path)?;
+
+		Ok(amounts.into_iter().map(Into::into).collect())
+	}
+
+	#[precompile::public("getAmountsIn(uint256,address[])")]
+	#[precompile::view]
+	fn get_amounts_in(
+		handle: &mut impl PrecompileHandle,
+		amount_out: U256,
+		path: BoundedVec<Address, GetPathLimit>,
+	) -> EvmResult<Vec<U256>> {
+		let amount_out = Self::u256_to_amount(amount_out).in_field("amountOut")?;
+		let path = Self::path_to_zenlink_ids(path).in_field("path")?;
+		handle.record_cost(Self::quote_cost(&path))?;
+
+		let amounts = Self::amounts_in(amount_out, &path)?;
+
+		Ok(amounts.into_iter().map(Into::into).collect())
+	}
+
+	#[precompile::public("swapExactAssetsForAssets(uint256,uint256,address[],address,uint32)")]
+	fn swap_exact_assets_for_assets(
+		handle: &mut impl PrecompileHandle,
+		amount_in: U256,
+		amount_out_min: U256,
+		path: BoundedVec<Address, GetPathLimit>,
+		to: Address,
+		deadline: u32,
+	) -> EvmResult<bool> {
+		let path_addresses: Vec<Address> = path.clone().into();
+		let amount_in = Self::u256_to_amount(amount_in).in_field("amountIn")?;
+		let amount_out_min = Self::u256_to_amount(amount_out_min).in_field("amountOutMin")?;
+		let path = Self::path_to_zenlink_ids(path).in_field("path")?;
+		let (asset_in, asset_out) = Self::path_ends(&path);
+
+		let to: H160 = to.into();
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let recipient = Runtime::AddressMapping::into_account_id(to);
+
+		// The balances are read by the swap itself, which its weight accounts for.
+		let balance_before = Self::balance_of(asset_out, &recipient);
+
+		// Dispatch call (if enough gas).
+		RuntimeHelper::<Runtime>::try_dispatch(
+			handle,
+			Some(origin.clone()).into(),
+			zenlink_protocol::Call::<Runtime>::swap_exact_assets_for_assets {
+				amount_in,
+				amount_out_min,
+				path,
+				recipient: Runtime::Lookup::unlookup(recipient.clone()),
+				deadline: deadline.into(),
+			},
+			SYSTEM_ACCOUNT_SIZE,
+		)?;
+
+		// A path ending with its first asset pays the recipient from its own input.
+		let paid_by_recipient =
+			if asset_in == asset_out && origin == recipient { amount_in } else { 0 };
+		let amount_out = Self::balance_of(asset_out, &recipient)
+			.saturating_add(paid_by_recipient)
+			.saturating_sub(balance_before);
+
+		Self::record_swap_log(handle, to, path_addresses, amount_in, amount_out)?;
+
+		Ok(true)
+	}
+
+	#[precompile::public("swapAssetsForExactAssets(uint256,uint256,address[],address,uint32)")]
+	fn swap_assets_for_exact_assets(
+		handle: &mut impl PrecompileHandle,
+		amount_out: U256,
+		amount_in_max: U256,
+		path: BoundedVec<Address, GetPathLimit>,
+		to: Address,
+		deadline: u32,
+	) -> EvmResult<bool> {
+		let path_addresses: Vec<Address> = path.clone().into();
+		let amount_out = Self::u256_to_amount(amount_out).in_field("amountOut")?;
+		let amount_in_max = Self::u256_to_amount(amount_in_max).in_field("amountInMax")?;
+		let path = Self::path_to_zenlink_ids(path).in_field("path")?;
+		let (asset_in, asset_out) = Self::path_ends(&path);
+
+		let to: H160 = to.into();
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+		let recipient = Runtime::AddressMapping::into_account_id(to);
+
+		// The balances are read by the swap itself, which its weight accounts for.
+		let balance_before = Self::balance_of(asset_in, &origin);
+
+		// Dispatch call (if enough gas).
+		RuntimeHelper::<Runtime>::try_dispatch(
+			handle,
+			Some(origin.clone()).into(),
+			zenlink_protocol::Call::<Runtime>::swap_assets_for_exact_assets {
+				amount_out,
+				amount_in_max,
+				path,
+				recipient: Runtime::Lookup::unlookup(recipient.clone()),
+				deadline: deadline.into(),
+			},
+			SYSTEM_ACCOUNT_SIZE,
+		)?;
+
+		// A path ending with its first asset refunds the sender part of its input.
+		let received_by_sender =
+			if asset_in == asset_out && origin == recipient { amount_out } else { 0 };
+		let amount_in = balance_before
+			.saturating_add(received_by_sender)
+			.saturating_sub(Self::balance_of(asset_in, &origin));
+
+		Self::record_swap_log(handle, to, path_addresses, amount_in, amount_out)?;
+
+		Ok(true)
+	}
+
+	#[precompile::public("addLiquidity(address,address,uint256,uint256,uint256,uint256,uint32)")]
+	fn add_liquidity(
+		handle: &mut impl PrecompileHandle,
+		asset0: Address,
+		asset1: Address,
+		amount0_desired: U256,
+		amount1_desired: U256,
+		amount0_min: U256,
+		amount1_min: U256,
+		deadline: u32,
+	) -> EvmResult<bool> {
+		let zenlink_asset0 = Self::address_to_zenlink_id(asset0).in_field("asset0")?;
+		let zenlink_asset1 = Self::address_to_zenlink_id(asset1).in_field("asset1")?;
+		let amount0_desired = Self::u256_to_amount(amount0_desired).in_field("amount0Desired")?;
+		let amount1_desired = Self::u256_to_amount(amount1_desired).in_field("amount1Desired")?;
+		let amount0_min = Self::u256_to_amount(amount0_min).in_field("amount0Min")?;
+		let amount1_min = Self::u256_to_amount(amount1_min).in_field("amount1Min")?;
+
+		// The reserves are read by the call itself, which its weight accounts for.
+		let (reserve0_before, reserve1_before) =
+			Self::pair_reserves(zenlink_asset0, zenlink_asset1).unwrap_or_default();
+
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		// Dispatch call (if enough gas).
+		RuntimeHelper::<Runtime>::try_dispatch(
+			handle,
+			Some(origin).into(),
+			zenlink_protocol::Call::<Runtime>::add_liquidity {
+				asset_0: zenlink_asset0,
+				asset_1: zenlink_asset1,
+				amount_0_desired: amount0_desired,
+				amount_1_desired: amount1_desired,
+				amount_0_min: amount0_min,
+				amount_1_min: amount1_min,
+				deadline: deadline.into(),
+			},
+			SYSTEM_ACCOUNT_SIZE,
+		)?;
+
+		let (reserve0_after, reserve1_after) =
+			Self::pair_reserves(zenlink_asset0, zenlink_asset1).unwrap_or_default();
+
+		LiquidityAdded {
+			provider: Address::from(handle.context().caller),
+			asset0,
+			asset1,
+			amount0: U256::from(reserve0_after.saturating_sub(reserve0_before)),
+			amount1: U256::from(reserve1_after.saturating_sub(reserve1_before)),
+		}
+		.record(handle)?;
+
+		Ok(true)
+	}
+
+	#[precompile::public("removeLiquidity(address,address,uint256,uint256,uint256,address,uint32)")]
+	fn remove_liquidity(
+		handle: &mut impl PrecompileHandle,
+		asset0: Address,
+		asset1: Address,
+		liquidity: U256,
+		amount0_min: U256,
+		amount1_min: U256,
+		to: Address,
+		deadline: u32,
+	) -> EvmResult<bool> {
+		let zenlink_asset0 = Self::address_to_zenlink_id(asset0).in_field("asset0")?;
+		let zenlink_asset1 = Self::address_to_zenlink_id(asset1).in_field("asset1")?;
+		let liquidity = Self::u256_to_amount(liquidity).in_field("liquidity")?;
+		let amount0_min = Self::u256_to_amount(amount0_min).in_field("amount0Min")?;
+		let amount1_min = Self::u256_to_amount(amount1_min).in_field("amount1Min")?;
+
+		// The reserves are read by the call itself, which its weight accounts for.
+		let (reserve0_before, reserve1_before) =
+			Self::pair_reserves(zenlink_asset0, zenlink_asset1).unwrap_or_default();
+
+		let to: H160 = to.into();
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		// Dispatch call (if enough gas).
+		RuntimeHelper::<Runtime>::try_dispatch(
+			handle,
+			Some(origin).into(),
+			zenlink_protocol::Call::<Runtime>::remove_liquidity {
+				asset_0: zenlink_asset0,
+				asset_1: zenlink_asset1,
+				liquidity,
+				amount_0_min: amount0_min,
+				amount_1_min: amount1_min,
+				recipient: Runtime::Lookup::unlookup(Runtime::AddressMapping::into_account_id(to)),
+				deadline: deadline.into(),
+			},
+			SYSTEM_ACCOUNT_SIZE,
+		)?;
+
+		let (reserve0_after, reserve1_after) =
+			Self::pair_reserves(zenlink_asset0, zenlink_asset1).unwrap_or_default();
+
+		LiquidityRemoved {
+			provider: Address::from(handle.context().caller),
+			asset0,
+			asset1,
+			amount0: U256::from(reserve0_before.saturating_sub(reserve0_after)),
+			amount1: U256::from(reserve1_before.saturating_sub(reserve1_after)),
+		}
+		.record(handle)?;
+
+		Ok(true)
+	}
+
+	fn record_swap_log(
+		handle: &mut impl PrecompileHandle,
+		to: H160,
+		path: Vec<Address>,
+		amount_in: AssetBalance,
+		amount_out: AssetBalance,
+	) -> EvmResult {
+		Swap {
+			sender: Address::from(handle.context().caller),
+			to: Address(to),
+			path,
+			amount_in: U256::from(amount_in),
+			amount_out: U256::from(amount_out),
+		}
+		.record(handle)
+	}
+
+	/// Amounts along the path when swapping `amount_in` of its first asset, hop by hop.
+	fn amounts_out(
+		amount_in: AssetBalance,
+		path: &[ZenlinkAssetId],
+	) -> EvmResult<Vec<AssetBalance>> {
+		let mut amounts = vec![amount_in];
+		for hop in path.windows(2) {
+			let last = *amounts.last().expect("amounts is never empty; qed");
+			let amount = zenlink_protocol::Pallet::<Runtime>::supply_out_amount(last, hop.to_vec());
+			if amount == 0 {
+				return Err(revert("Insufficient liquidity"))
+			}
+			amounts.push(amount);
+		}
+		Ok(amounts)
+	}
+
+	/// Amounts along the path needed to receive `amount_out` of its last asset, hop by hop.
+	fn amounts_in(
+		amount_out: AssetBalance,
+		path: &[ZenlinkAssetId],
+	) -> EvmResult<Vec<AssetBalance>> {
+		let mut amounts = vec![amount_out];
+		for hop in path.windows(2).rev() {
+			let first = *amounts.first().expect("amounts is never empty; qed");
+			let amount =
+				zenlink_protocol::Pallet::<Runtime>::desired_in_amount(first, hop.to_vec());
+			if amount == 0 {
+				return Err(revert("Insufficient liquidity"))
+			}
+			amounts.insert(0, amount);
+		}
+		Ok(amounts)
+	}
+
+	/// Reserves of the pair, ordered as the assets were given.
+	fn pair_reserves(
+		asset0: ZenlinkAssetId,
+		asset1: ZenlinkAssetId,
+	) -> Option<(AssetBalance, AssetBalance)> {
+		let pair = zenlink_protocol::Pallet::<Runtime>::get_pair_by_asset_id(asset0, asset1)?;
+		if pair.asset_0 == asset0 {
+			Some((pair.reserve_0, pair.reserve_1))
+		} else {
+			Some((pair.reserve_1, pair.reserve_0))
+		}
+	}
+
+	/// First and last assets of a path, which has at least two assets.
+	fn path_ends(path: &[ZenlinkAssetId]) -> (ZenlinkAssetId, ZenlinkAssetId) {
+		(path[0], path[path.len() - 1])
+	}
+
+	fn balance_of(asset: ZenlinkAssetId, who: &AccountIdOf<Runtime>) -> AssetBalance {
+		<Runtime as zenlink_protocol::Config>::MultiAssetsHandler::balance_of(asset, who)
+	}
+
+	fn quote_cost(path: &[ZenlinkAssetId]) -> u64 {
+		RuntimeHelper::<Runtime>::db_read_gas_cost() *
+			READS_PER_HOP *
+			path.len().saturating_sub(1) as u64
+	}
+
+	fn path_to_zenlink_ids(
+		path: BoundedVec<Address, GetPathLimit>,
+	) -> MayRevert<Vec<ZenlinkAssetId>> {
+		let path: Vec<Address> = path.into();
+		if path.len() < 2 {
+			return Err(RevertReason::custom("Path must contain at least two assets").into())
+		}
+
+		path.into_iter()
+			.enumerate()
+			.map(|(index, address)| Self::address_to_zenlink_id(address).in_array(index))
+			.collect()
+	}
+
+	fn address_to_zenlink_id(address: Address) -> MayRevert<ZenlinkAssetId> {
+		Runtime::address_to_asset_id(address.into())
+			.and_then(AssetIdToZenlinkId::<SelfParaIdOf<Runtime>>::convert)
+			.ok_or_else(|| RevertReason::custom("Unknown asset address").into())
+	}
+
+	fn u256_to_amount(value: U256) -> MayRevert<AssetBalance> {
+		value
+			.try_into()
+			.map_err(|_| RevertReason::value_is_too_large("balance type").into())
+	}
+}
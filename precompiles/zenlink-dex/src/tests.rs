@@ -0,0 +1,376 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use frame_support::{assert_ok, traits::fungibles::Inspect};
+use precompile_utils::testing::*;
+use sha3::{Digest, Keccak256};
+
+use crate::{mock::*, *};
+
+fn precompile_address() -> H160 {
+	H160::from_low_u64_be(1)
+}
+
+fn asset_address(id: u32) -> H160 {
+	Runtime::asset_id_to_address(AssetId::Token(id))
+}
+
+fn zenlink_id(id: u32) -> ZenlinkAssetId {
+	AssetIdToZenlinkId::<SelfParaId>::convert(AssetId::Token(id)).unwrap()
+}
+
+fn path(ids: &[u32]) -> BoundedVec<Address, GetPathLimit> {
+	ids.iter().map(|id| Address(asset_address(*id))).collect::<Vec<_>>().into()
+}
+
+fn ext_with_pair() -> sp_io::TestExternalities {
+	let mut ext = ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000), (Bob.into(), 1000)])
+		.with_assets(vec![
+			(AssetId::Token(1), vec![(Alice.into(), 1_000_000), (Bob.into(), 1_000_000)]),
+			(AssetId::Token(2), vec![(Alice.into(), 1_000_000)]),
+		])
+		.build();
+	ext.execute_with(|| {
+		assert_ok!(ZenlinkProtocol::create_pair(
+			RuntimeOrigin::root(),
+			zenlink_id(1),
+			zenlink_id(2)
+		));
+	});
+	ext
+}
+
+fn add_liquidity(amount0: u128, amount1: u128) {
+	precompiles()
+		.prepare_test(
+			Alice,
+			precompile_address(),
+			PCall::add_liquidity {
+				asset0: Address(asset_address(1)),
+				asset1: Address(asset_address(2)),
+				amount0_desired: amount0.into(),
+				amount1_desired: amount1.into(),
+				amount0_min: U256::zero(),
+				amount1_min: U256::zero(),
+				deadline: 100,
+			},
+		)
+		.execute_returns(true);
+}
+
+#[test]
+fn selectors() {
+	assert!(PCall::get_pair_selectors().contains(&0xe6a43905));
+	assert!(PCall::get_amounts_out_selectors().contains(&0xd06ca61f));
+	assert!(PCall::get_amounts_in_selectors().contains(&0x1f00ca74));
+	assert!(PCall::swap_exact_assets_for_assets_selectors().contains(&0x48f34771));
+	assert!(PCall::swap_assets_for_exact_assets_selectors().contains(&0xf8770837));
+	assert!(PCall::add_liquidity_selectors().contains(&0x44e651db));
+	assert!(PCall::remove_liquidity_selectors().contains(&0x81744501));
+
+	assert_eq!(
+		crate::SELECTOR_LOG_SWAP,
+		&Keccak256::digest(b"Swap(address,address,address[],uint256,uint256)")[..]
+	);
+	assert_eq!(
+		crate::SELECTOR_LOG_LIQUIDITY_ADDED,
+		&Keccak256::digest(b"LiquidityAdded(address,address,address,uint256,uint256)")[..]
+	);
+	assert_eq!(
+		crate::SELECTOR_LOG_LIQUIDITY_REMOVED,
+		&Keccak256::digest(b"LiquidityRemoved(address,address,address,uint256,uint256)")[..]
+	);
+}
+
+#[test]
+fn modifiers() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice.into(), 1000)])
+		.build()
+		.execute_with(|| {
+			let mut tester =
+				PrecompilesModifierTester::new(precompiles(), Alice, precompile_address());
+
+			tester.test_view_modifier(PCall::get_pair_selectors());
+			tester.test_view_modifier(PCall::get_amounts_out_selectors());
+			tester.test_view_modifier(PCall::get_amounts_in_selectors());
+			tester.test_default_modifier(PCall::swap_exact_assets_for_assets_selectors());
+			tester.test_default_modifier(PCall::swap_assets_for_exact_assets_selectors());
+			tester.test_default_modifier(PCall::add_liquidity_selectors());
+			tester.test_default_modifier(PCall::remove_liquidity_selectors());
+		});
+}
+
+#[test]
+fn get_pair_fails_for_unknown_pair() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_pair {
+					asset0: Address(asset_address(1)),
+					asset1: Address(asset_address(2)),
+				},
+			)
+			.execute_reverts(|output| output == b"Pair does not exist");
+	});
+}
+
+#[test]
+fn get_pair_fails_for_unknown_asset_address() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_pair {
+					asset0: Address(H160::repeat_byte(0x11)),
+					asset1: Address(asset_address(2)),
+				},
+			)
+			.execute_reverts(|output| output == b"asset0: Unknown asset address");
+	});
+}
+
+#[test]
+fn get_amounts_out_fails_for_short_path() {
+	ExtBuilder::default().build().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_amounts_out { amount_in: U256::from(100), path: path(&[1]) },
+			)
+			.execute_reverts(|output| output == b"path: Path must contain at least two assets");
+	});
+}
+
+#[test]
+fn add_liquidity_works() {
+	ext_with_pair().execute_with(|| {
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::add_liquidity {
+					asset0: Address(asset_address(1)),
+					asset1: Address(asset_address(2)),
+					amount0_desired: U256::from(10_000),
+					amount1_desired: U256::from(20_000),
+					amount0_min: U256::zero(),
+					amount1_min: U256::zero(),
+					deadline: 100,
+				},
+			)
+			.expect_log(log4(
+				precompile_address(),
+				SELECTOR_LOG_LIQUIDITY_ADDED,
+				H160::from(Alice),
+				asset_address(1),
+				asset_address(2),
+				solidity::encode_event_data((U256::from(10_000), U256::from(20_000))),
+			))
+			.execute_returns(true);
+
+		let total_liquidity =
+			zenlink_protocol::Pallet::<Runtime>::get_pair_by_asset_id(zenlink_id(1), zenlink_id(2))
+				.unwrap()
+				.total_liquidity;
+
+		// Reserves follow the order of the given assets.
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::get_pair {
+					asset0: Address(asset_address(2)),
+					asset1: Address(asset_address(1)),
+				},
+			)
+			.execute_returns(EVMPairInfo {
+				lp_token: Address(Runtime::asset_id_to_address(AssetId::LPToken(1, 2))),
+				reserve0: U256::from(20_000),
+				reserve1: U256::from(10_000),
+				total_liquidity: total_liquidity.into(),
+			});
+	});
+}
+
+#[test]
+fn add_liquidity_fails_after_deadline() {
+	ext_with_pair().execute_with(|| {
+		System::set_block_number(101);
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::add_liquidity {
+					asset0: Address(asset_address(1)),
+					asset1: Address(asset_address(2)),
+					amount0_desired: U256::from(10_000),
+					amount1_desired: U256::from(20_000),
+					amount0_min: U256::zero(),
+					amount1_min: U256::zero(),
+					deadline: 100,
+				},
+			)
+			.execute_reverts(|output| output.starts_with(b"Dispatched call failed with error:"));
+	});
+}
+
+#[test]
+fn swap_exact_assets_for_assets_matches_quote() {
+	ext_with_pair().execute_with(|| {
+		add_liquidity(100_000, 100_000);
+
+		let amounts = zenlink_protocol::Pallet::<Runtime>::supply_out_amount(
+			1_000,
+			vec![zenlink_id(1), zenlink_id(2)],
+		);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::get_amounts_out { amount_in: U256::from(1_000), path: path(&[1, 2]) },
+			)
+			.expect_no_logs()
+			.execute_returns(vec![U256::from(1_000), U256::from(amounts)]);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::swap_exact_assets_for_assets {
+					amount_in: U256::from(1_000),
+					amount_out_min: U256::one(),
+					path: path(&[1, 2]),
+					to: Address(Bob.into()),
+					deadline: 100,
+				},
+			)
+			.expect_log(log3(
+				precompile_address(),
+				SELECTOR_LOG_SWAP,
+				H160::from(Bob),
+				H160::from(Bob),
+				solidity::encode_event_data((
+					vec![Address(asset_address(1)), Address(asset_address(2))],
+					U256::from(1_000),
+					U256::from(amounts),
+				)),
+			))
+			.execute_returns(true);
+
+		assert_eq!(Assets::balance(AssetId::Token(1), AccountId::from(Bob)), 999_000);
+		assert_eq!(Assets::balance(AssetId::Token(2), AccountId::from(Bob)), amounts);
+	});
+}
+
+#[test]
+fn swap_assets_for_exact_assets_logs_the_spent_amount() {
+	ext_with_pair().execute_with(|| {
+		add_liquidity(100_000, 100_000);
+
+		let amount_in = zenlink_protocol::Pallet::<Runtime>::desired_in_amount(
+			1_000,
+			vec![zenlink_id(1), zenlink_id(2)],
+		);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::swap_assets_for_exact_assets {
+					amount_out: U256::from(1_000),
+					amount_in_max: U256::from(2_000),
+					path: path(&[1, 2]),
+					to: Address(Bob.into()),
+					deadline: 100,
+				},
+			)
+			.expect_log(log3(
+				precompile_address(),
+				SELECTOR_LOG_SWAP,
+				H160::from(Bob),
+				H160::from(Bob),
+				solidity::encode_event_data((
+					vec![Address(asset_address(1)), Address(asset_address(2))],
+					U256::from(amount_in),
+					U256::from(1_000),
+				)),
+			))
+			.execute_returns(true);
+
+		assert_eq!(Assets::balance(AssetId::Token(1), AccountId::from(Bob)), 1_000_000 - amount_in);
+		assert_eq!(Assets::balance(AssetId::Token(2), AccountId::from(Bob)), 1_000);
+	});
+}
+
+#[test]
+fn swap_assets_for_exact_assets_fails_above_max_input() {
+	ext_with_pair().execute_with(|| {
+		add_liquidity(100_000, 100_000);
+
+		precompiles()
+			.prepare_test(
+				Bob,
+				precompile_address(),
+				PCall::swap_assets_for_exact_assets {
+					amount_out: U256::from(1_000),
+					amount_in_max: U256::from(1_000),
+					path: path(&[1, 2]),
+					to: Address(Bob.into()),
+					deadline: 100,
+				},
+			)
+			.execute_reverts(|output| output.starts_with(b"Dispatched call failed with error:"));
+	});
+}
+
+#[test]
+fn remove_liquidity_works() {
+	ext_with_pair().execute_with(|| {
+		add_liquidity(10_000, 10_000);
+
+		let lp_token = AssetId::LPToken(1, 2);
+		let liquidity = Assets::balance(lp_token, AccountId::from(Alice));
+
+		precompiles()
+			.prepare_test(
+				Alice,
+				precompile_address(),
+				PCall::remove_liquidity {
+					asset0: Address(asset_address(1)),
+					asset1: Address(asset_address(2)),
+					liquidity: U256::from(liquidity),
+					amount0_min: U256::zero(),
+					amount1_min: U256::zero(),
+					to: Address(Alice.into()),
+					deadline: 100,
+				},
+			)
+			.execute_returns(true);
+
+		assert_eq!(Assets::balance(lp_token, AccountId::from(Alice)), 0);
+	});
+}
+
+#[test]
+fn events_match_their_signatures() {
+	assert_eq!(Swap::solidity_event().signature(), Swap::SIGNATURE);
+	assert_eq!(LiquidityAdded::solidity_event().signature(), LiquidityAdded::SIGNATURE);
+	assert_eq!(LiquidityRemoved::solidity_event().signature(), LiquidityRemoved::SIGNATURE);
+}
+
+#[test]
+fn test_solidity_interface_has_all_function_selectors_documented_and_implemented() {
+	check_precompile_implements_solidity_interfaces(&["ZenlinkDex.sol"], PCall::supports_selector)
+}
+
+#[test]
+fn test_solidity_interface_matches_precompile() {
+	check_solidity_interface_matches("ZenlinkDex.sol", &PCall::solidity_interface())
+}
@@ -28,7 +28,9 @@ use sp_runtime_interface::runtime_interface;
 use parity_scale_codec::Decode;
 use sp_std::vec::Vec;
 
-use evm_tracing_events::{Event, EvmEvent, GasometerEvent, RuntimeEvent, StepEventFilter};
+use evm_tracing_events::{
+	Event, EvmEvent, GasometerEvent, RuntimeEvent, StepEventFilter, SubstrateEvent,
+};
 
 #[allow(clippy::unnecessary_mut_passed)]
 #[runtime_interface]
@@ -68,6 +70,19 @@ pub trait PeaqExt {
 		}
 	}
 
+	/// A `Substrate` event emitted by the runtime about EVM executions started outside of
+	/// Ethereum transactions.
+	/// peaq_runtime -> host.
+	///
+	/// A change of the encoding of `SubstrateEvent` must come with a new version of this
+	/// function, keeping this one for the runtimes using the current encoding.
+	#[version(1)]
+	fn substrate_event(&mut self, event: Vec<u8>) {
+		if let Ok(event) = SubstrateEvent::decode(&mut &event[..]) {
+			Event::Substrate(event).emit();
+		}
+	}
+
 	/// Allow the tracing module in the runtime to know how to filter Step event
 	/// content, as cloning the entire data is expensive and most of the time
 	/// not necessary.
@@ -17,12 +17,12 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use ethereum::{TransactionV0 as LegacyTransaction, TransactionV2 as Transaction};
-use ethereum_types::H256;
+use ethereum_types::{H160, H256, U256};
 use parity_scale_codec::{Decode, Encode};
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
-	// Api version is virtually 4.
+	// Api version is virtually 6.
 	//
 	// We realized that even using runtime overrides, using the ApiExt interface reads the api
 	// versions from the state runtime, meaning we cannot just reset the versioning as we see fit.
@@ -30,7 +30,10 @@ sp_api::decl_runtime_apis! {
 	// In order to be able to use ApiExt as part of the RPC handler logic we need to be always
 	// above the version that exists on chain for this Api, even if this Api is only meant
 	// to be used overridden.
-	#[api_version(4)]
+	//
+	// Since version 6, `trace_block` also traces the EVM executions of the Substrate extrinsics,
+	// each one in a call stack started by a `SubstrateEvent::CallListNew` event.
+	#[api_version(6)]
 	pub trait DebugRuntimeApi {
 		#[changed_in(4)]
 		fn trace_transaction(
@@ -47,6 +50,20 @@ sp_api::decl_runtime_apis! {
 			extrinsics: Vec<Block::Extrinsic>,
 			known_transactions: Vec<H256>,
 		) -> Result<(), sp_runtime::DispatchError>;
+
+		/// Trace a call which is not part of any block, executed on top of the state the runtime
+		/// api is called at. A call without recipient creates a contract with `data` as init code.
+		fn trace_call(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			max_fee_per_gas: Option<U256>,
+			max_priority_fee_per_gas: Option<U256>,
+			nonce: Option<U256>,
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+		) -> Result<(), sp_runtime::DispatchError>;
 	}
 }
 
@@ -55,6 +72,8 @@ pub enum TracerInput {
 	None,
 	Blockscout,
 	CallTracer,
+	FourByteTracer,
+	OpcountTracer,
 }
 
 /// DebugRuntimeApi V2 result. Trace response is stored in client and runtime api call response is
@@ -34,10 +34,12 @@ extern crate alloc;
 pub mod evm;
 pub mod gasometer;
 pub mod runtime;
+pub mod substrate;
 
 pub use self::evm::EvmEvent;
 pub use gasometer::GasometerEvent;
 pub use runtime::RuntimeEvent;
+pub use substrate::SubstrateEvent;
 
 use ethereum_types::{H160, U256};
 use parity_scale_codec::{Decode, Encode};
@@ -64,6 +66,7 @@ pub enum Event {
 	Gasometer(gasometer::GasometerEvent),
 	Runtime(runtime::RuntimeEvent),
 	CallListNew(),
+	Substrate(substrate::SubstrateEvent),
 }
 
 impl Event {
@@ -19,7 +19,7 @@ extern crate alloc;
 use super::Context;
 use alloc::vec::Vec;
 use ethereum_types::{H160, H256, U256};
-pub use evm::{ExitError, ExitReason, ExitSucceed, Opcode};
+pub use evm::{ExitError, ExitReason, ExitRevert, ExitSucceed, Opcode};
 use parity_scale_codec::{Decode, Encode};
 
 #[derive(Clone, Debug, Encode, Decode, PartialEq, Eq)]
@@ -0,0 +1,20 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+use alloc::vec::Vec;
+use ethereum_types::{H160, H256};
+use parity_scale_codec::{Decode, Encode};
+
+/// Events about EVM executions which are not Ethereum transactions, emitted by the runtime
+/// itself instead of the EVM.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum SubstrateEvent {
+	/// The EVM executions of the following Substrate extrinsic of the traced block are a new call
+	/// stack, like `Event::CallListNew` for Ethereum transactions.
+	CallListNew { extrinsic_index: u32 },
+	/// A precompile dispatched a runtime call.
+	Dispatch { pallet: Vec<u8>, call: Vec<u8> },
+	/// The current EVM call was executed by a precompile, emitted before its `Exit`.
+	Precompile,
+	/// A precompile emitted a log, which unlike the logs of contracts isn't a `LOG` step.
+	Log { address: H160, topics: Vec<H256>, data: Vec<u8> },
+}
@@ -23,6 +23,7 @@
 pub use ethereum::{TransactionV0 as LegacyTransaction, TransactionV2 as Transaction};
 use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
+use sp_core::H160;
 use sp_runtime::{traits::Block as BlockT, RuntimeDebug};
 use sp_std::vec::Vec;
 
@@ -39,7 +40,7 @@ pub struct TxPoolResponse {
 }
 
 sp_api::decl_runtime_apis! {
-	#[api_version(2)]
+	#[api_version(3)]
 	pub trait TxPoolRuntimeApi {
 		#[changed_in(2)]
 		fn extrinsic_filter(
@@ -50,5 +51,9 @@ sp_api::decl_runtime_apis! {
 			xt_ready: Vec<<Block as BlockT>::Extrinsic>,
 			xt_future: Vec<<Block as BlockT>::Extrinsic>,
 		) -> TxPoolResponse;
+		/// Prefix of the `(account, nonce)` tags provided in the pool by the native extrinsics of
+		/// the account `from` is mapped to, i.e. the encoding of the account.
+		#[api_version(3)]
+		fn native_tag_prefix(from: H160) -> Vec<u8>;
 	}
 }
@@ -0,0 +1,41 @@
+use frame_support::traits::{Contains, IsSubType};
+use frame_system::Config as SysConfig;
+use sp_std::marker::PhantomData;
+
+/// Calls EVM accounts can dispatch through the dispatch precompile. Batches are allowed as the
+/// calls they dispatch are filtered as well. `I` is the instance of the council collective.
+pub struct DispatchCallFilter<T, I>(PhantomData<(T, I)>);
+
+impl<T, I> Contains<<T as SysConfig>::RuntimeCall> for DispatchCallFilter<T, I>
+where
+	T: pallet_utility::Config
+		+ pallet_collective::Config<I>
+		+ pallet_vesting::Config
+		+ peaq_pallet_did::Config
+		+ peaq_pallet_rbac::Config
+		+ peaq_pallet_storage::Config,
+	I: 'static,
+	<T as SysConfig>::RuntimeCall: IsSubType<pallet_utility::Call<T>>
+		+ IsSubType<pallet_collective::Call<T, I>>
+		+ IsSubType<pallet_vesting::Call<T>>
+		+ IsSubType<peaq_pallet_did::Call<T>>
+		+ IsSubType<peaq_pallet_rbac::Call<T>>
+		+ IsSubType<peaq_pallet_storage::Call<T>>,
+{
+	fn contains(call: &<T as SysConfig>::RuntimeCall) -> bool {
+		if let Some(call) = IsSubType::<pallet_utility::Call<T>>::is_sub_type(call) {
+			return matches!(
+				call,
+				pallet_utility::Call::batch { .. } |
+					pallet_utility::Call::batch_all { .. } |
+					pallet_utility::Call::force_batch { .. }
+			)
+		}
+
+		IsSubType::<pallet_collective::Call<T, I>>::is_sub_type(call).is_some() ||
+			IsSubType::<pallet_vesting::Call<T>>::is_sub_type(call).is_some() ||
+			IsSubType::<peaq_pallet_did::Call<T>>::is_sub_type(call).is_some() ||
+			IsSubType::<peaq_pallet_rbac::Call<T>>::is_sub_type(call).is_some() ||
+			IsSubType::<peaq_pallet_storage::Call<T>>::is_sub_type(call).is_some()
+	}
+}
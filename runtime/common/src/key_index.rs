@@ -0,0 +1,45 @@
+use frame_system::Config as SysConfig;
+use key_index::{KeyChange, KeyChanges, KeyKind};
+use sp_std::marker::PhantomData;
+
+/// Names of the attributes added, updated and removed by the DID pallet.
+pub struct DidKeyChanges<T>(PhantomData<T>);
+
+impl<T> KeyChanges<<T as SysConfig>::RuntimeEvent, T::AccountId> for DidKeyChanges<T>
+where
+	T: peaq_pallet_did::Config,
+	<T as SysConfig>::RuntimeEvent: TryInto<peaq_pallet_did::Event<T>>,
+{
+	fn key_change(event: &<T as SysConfig>::RuntimeEvent) -> Option<KeyChange<T::AccountId>> {
+		let kind = KeyKind::DidAttribute;
+		match event.clone().try_into().ok()? {
+			peaq_pallet_did::Event::AttributeAdded(_, account, key, ..) |
+			peaq_pallet_did::Event::AttributeUpdated(_, account, key, ..) =>
+				Some(KeyChange::Inserted { kind, account, key }),
+			peaq_pallet_did::Event::AttributeRemoved(_, account, key) =>
+				Some(KeyChange::Removed { kind, account, key }),
+			_ => None,
+		}
+	}
+}
+
+/// Types of the items added, updated and removed by the storage pallet.
+pub struct StorageKeyChanges<T>(PhantomData<T>);
+
+impl<T> KeyChanges<<T as SysConfig>::RuntimeEvent, T::AccountId> for StorageKeyChanges<T>
+where
+	T: peaq_pallet_storage::Config,
+	<T as SysConfig>::RuntimeEvent: TryInto<peaq_pallet_storage::Event<T>>,
+{
+	fn key_change(event: &<T as SysConfig>::RuntimeEvent) -> Option<KeyChange<T::AccountId>> {
+		let kind = KeyKind::StorageItem;
+		match event.clone().try_into().ok()? {
+			peaq_pallet_storage::Event::ItemAdded(account, key, _) |
+			peaq_pallet_storage::Event::ItemUpdated(account, key, _) =>
+				Some(KeyChange::Inserted { kind, account, key }),
+			peaq_pallet_storage::Event::ItemRemoved(account, key) =>
+				Some(KeyChange::Removed { kind, account, key }),
+			_ => None,
+		}
+	}
+}
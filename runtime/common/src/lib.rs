@@ -18,6 +18,10 @@ pub mod wrapper;
 pub use wrapper::*;
 pub mod zenlink;
 pub use zenlink::*;
+pub mod key_index;
+pub use key_index::*;
+pub mod dispatch;
+pub use dispatch::*;
 
 // Contracts price units.
 pub const TOKEN_DECIMALS: u32 = 18;
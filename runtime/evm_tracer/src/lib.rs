@@ -25,13 +25,16 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub mod tracer {
-	use evm_tracing_events::{EvmEvent, GasometerEvent, RuntimeEvent, StepEventFilter};
+	use ethereum_types::{H160, H256};
+	use evm_tracing_events::{
+		EvmEvent, GasometerEvent, RuntimeEvent, StepEventFilter, SubstrateEvent,
+	};
 	use parity_scale_codec::Encode;
 
 	use evm::tracing::{using as evm_using, EventListener as EvmListener};
 	use evm_gasometer::tracing::{using as gasometer_using, EventListener as GasometerListener};
 	use evm_runtime::tracing::{using as runtime_using, EventListener as RuntimeListener};
-	use sp_std::{cell::RefCell, rc::Rc};
+	use sp_std::{cell::RefCell, rc::Rc, vec::Vec};
 
 	struct ListenerProxy<T>(pub Rc<RefCell<T>>);
 	impl<T: GasometerListener> GasometerListener for ListenerProxy<T> {
@@ -90,6 +93,33 @@ pub mod tracer {
 		pub fn emit_new() {
 			peaq_primitives_ext::peaq_ext::call_list_new();
 		}
+
+		/// Start a new call stack for the EVM executions of the Substrate extrinsic at
+		/// `extrinsic_index` in the traced block.
+		pub fn emit_new_substrate(extrinsic_index: u32) {
+			let event = SubstrateEvent::CallListNew { extrinsic_index };
+			peaq_primitives_ext::peaq_ext::substrate_event(event.encode());
+		}
+
+		/// Annotate the current EVM call with the runtime call it dispatched (precompiles).
+		pub fn emit_dispatch(pallet: &str, call: &str) {
+			let event = SubstrateEvent::Dispatch {
+				pallet: pallet.as_bytes().to_vec(),
+				call: call.as_bytes().to_vec(),
+			};
+			peaq_primitives_ext::peaq_ext::substrate_event(event.encode());
+		}
+
+		/// Mark the current EVM call as executed by a precompile.
+		pub fn emit_precompile() {
+			peaq_primitives_ext::peaq_ext::substrate_event(SubstrateEvent::Precompile.encode());
+		}
+
+		/// Record a log emitted by the precompile executing the current EVM call.
+		pub fn emit_log(address: H160, topics: Vec<H256>, data: Vec<u8>) {
+			let event = SubstrateEvent::Log { address, topics, data };
+			peaq_primitives_ext::peaq_ext::substrate_event(event.encode());
+		}
 	}
 
 	impl EvmListener for EvmTracer {
@@ -990,6 +990,8 @@ construct_runtime!(
 		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>} = 39,
 		XcAssetConfig: xc_asset_config::{Pallet, Call, Storage, Event<T>} = 40,
 		AddressUnification: address_unification::{Pallet, Call, Storage, Event<T>} = 41,
+		PrecompileRegistry: precompile_registry::{Pallet, Call, Storage, Event<T>} = 42,
+		KeyIndex: key_index::{Pallet, Storage} = 43,
 
 		Vesting: pallet_vesting = 50,
 
@@ -1068,6 +1070,8 @@ mod benches {
 		[pallet_assets, Assets]
 		[xc_asset_config, XCAssetConfig]
 		[address_unification, AddressUnification]
+		[precompile_registry, PrecompileRegistry]
+		[pallet_evm_precompile_peaq_storage, PeaqStoragePrecompileBench::<Runtime>]
 	);
 }
 
@@ -1332,8 +1336,9 @@ impl_runtime_apis! {
 				let mut config = <Runtime as pallet_evm::Config>::config().clone();
 				config.estimate = true;
 
-				// Apply all extrinsics. Ethereum extrinsics are traced.
-				for ext in extrinsics.into_iter() {
+				// Apply all extrinsics. Known Ethereum extrinsics are traced, as well as the EVM
+				// executions started by Substrate extrinsics (`pallet_evm` calls, XCM, ...).
+				for (index, ext) in extrinsics.into_iter().enumerate() {
 					match &ext.0.function {
 						RuntimeCall::Ethereum(transact { transaction }) => {
 							if known_transactions.contains(&transaction.hash()) {
@@ -1345,7 +1350,9 @@ impl_runtime_apis! {
 							}
 						}
 						_ => {
-							let _ = Executive::apply_extrinsic(ext);
+							// The call stack of an extrinsic without EVM execution stays empty.
+							EvmTracer::emit_new_substrate(index as u32);
+							EvmTracer::new().trace(|| Executive::apply_extrinsic(ext));
 						}
 					};
 				}
@@ -1357,6 +1364,105 @@ impl_runtime_apis! {
 				"Missing `evm-tracing` compile time feature flag.",
 			))
 		}
+
+		fn trace_call(
+			#[allow(unused_variables)]
+			from: H160,
+			#[allow(unused_variables)]
+			to: Option<H160>,
+			#[allow(unused_variables)]
+			data: Vec<u8>,
+			#[allow(unused_variables)]
+			value: U256,
+			#[allow(unused_variables)]
+			gas_limit: U256,
+			#[allow(unused_variables)]
+			max_fee_per_gas: Option<U256>,
+			#[allow(unused_variables)]
+			max_priority_fee_per_gas: Option<U256>,
+			#[allow(unused_variables)]
+			nonce: Option<U256>,
+			#[allow(unused_variables)]
+			access_list: Option<Vec<(H160, Vec<H256>)>>,
+		) -> Result<
+			(),
+			sp_runtime::DispatchError,
+		> {
+			#[cfg(feature = "evm-tracing")]
+			{
+				use peaq_evm_tracer::tracer::EvmTracer;
+
+				let is_transactional = false;
+				let validate = true;
+
+				// Same estimation of the transaction size as `EthereumRuntimeRPCApi::call`.
+				let mut estimated_transaction_len = data.len() + 258;
+				if access_list.is_some() {
+					estimated_transaction_len += access_list.encoded_size();
+				}
+
+				let gas_limit = gas_limit.min(u64::MAX.into()).low_u64();
+				let without_base_extrinsic_weight = true;
+
+				let (weight_limit, proof_size_base_cost) =
+					match <Runtime as pallet_evm::Config>::GasWeightMapping::gas_to_weight(
+						gas_limit,
+						without_base_extrinsic_weight
+					) {
+						weight_limit if weight_limit.proof_size() > 0 => {
+							(Some(weight_limit), Some(estimated_transaction_len as u64))
+						}
+						_ => (None, None),
+					};
+
+				// A failing call is traced as well, its error is part of the trace.
+				// Without a recipient, the call deploys `data` as `eth_call` does.
+				let config = <Runtime as pallet_evm::Config>::config();
+				EvmTracer::new().trace(|| match to {
+					Some(to) => {
+						let _ = <Runtime as pallet_evm::Config>::Runner::call(
+							from,
+							to,
+							data,
+							value,
+							gas_limit.unique_saturated_into(),
+							max_fee_per_gas,
+							max_priority_fee_per_gas,
+							nonce,
+							access_list.unwrap_or_default(),
+							is_transactional,
+							validate,
+							weight_limit,
+							proof_size_base_cost,
+							config,
+						);
+					},
+					None => {
+						let _ = <Runtime as pallet_evm::Config>::Runner::create(
+							from,
+							data,
+							value,
+							gas_limit.unique_saturated_into(),
+							max_fee_per_gas,
+							max_priority_fee_per_gas,
+							nonce,
+							access_list.unwrap_or_default(),
+							is_transactional,
+							validate,
+							weight_limit,
+							proof_size_base_cost,
+							config,
+						);
+					},
+				});
+
+				Ok(())
+			}
+			#[cfg(not(feature = "evm-tracing"))]
+			Err(sp_runtime::DispatchError::Other(
+				"Missing `evm-tracing` compile time feature flag.",
+			))
+		}
 	}
 
 	impl peaq_rpc_primitives_txpool::TxPoolRuntimeApi<Block> for Runtime {
@@ -1381,6 +1487,12 @@ impl_runtime_apis! {
 					.collect(),
 			}
 		}
+		fn native_tag_prefix(from: H160) -> Vec<u8> {
+			use address_unification::EVMAddressMapping;
+
+			// `frame_system::CheckNonce` provides the `(account, nonce)` tag.
+			AddressUnification::get_account_id_or_default(&from).encode()
+		}
 	}
 
 	impl fp_rpc::EthereumRuntimeRPCApi<Block> for Runtime {
@@ -1723,6 +1835,16 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl key_index_runtime_api::KeyIndexApi<Block, AccountId> for Runtime {
+		fn did_attribute_names(did_account: AccountId, offset: u32, limit: u32) -> Vec<Vec<u8>> {
+			KeyIndex::page(key_index::KeyKind::DidAttribute, did_account, offset, limit)
+		}
+
+		fn storage_item_types(account: AccountId, offset: u32, limit: u32) -> Vec<Vec<u8>> {
+			KeyIndex::page(key_index::KeyKind::StorageItem, account, offset, limit)
+		}
+	}
+
 	impl peaq_pallet_rbac_runtime_api::PeaqRBACRuntimeApi<Block, AccountId, RbacEntityId> for Runtime {
 		fn fetch_role(
 			account: AccountId,
@@ -1880,6 +2002,7 @@ impl_runtime_apis! {
 			use frame_benchmarking::{baseline, Benchmarking, BenchmarkList};
 			use frame_support::traits::StorageInfoTrait;
 			use frame_system_benchmarking::Pallet as SystemBench;
+			use pallet_evm_precompile_peaq_storage::benchmarking::Pallet as PeaqStoragePrecompileBench;
 			use baseline::Pallet as BaselineBench;
 
 			let mut list = Vec::<BenchmarkList>::new();
@@ -1895,6 +2018,7 @@ impl_runtime_apis! {
 		) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
 			use frame_benchmarking::{baseline, Benchmarking, BenchmarkBatch, TrackedStorageKey};
 			use frame_system_benchmarking::Pallet as SystemBench;
+			use pallet_evm_precompile_peaq_storage::benchmarking::Pallet as PeaqStoragePrecompileBench;
 			use baseline::Pallet as BaselineBench;
 
 			impl frame_system_benchmarking::Config for Runtime {}
@@ -2040,6 +2164,21 @@ impl address_unification::Config for Runtime {
 	type WeightInfo = address_unification::weights::SubstrateWeight<Runtime>;
 }
 
+impl precompile_registry::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type ControlOrigin = EitherOfDiverse<
+		EnsureRoot<AccountId>,
+		pallet_collective::EnsureProportionAtLeast<AccountId, CouncilCollective, 1, 2>,
+	>;
+	type WeightInfo = precompile_registry::weights::SubstrateWeight<Runtime>;
+}
+
+impl key_index::Config for Runtime {
+	type KeyChanges =
+		(runtime_common::DidKeyChanges<Runtime>, runtime_common::StorageKeyChanges<Runtime>);
+	type WeightInfo = key_index::weights::SubstrateWeight<Runtime>;
+}
+
 impl EVMAddressToAssetId<AssetId> for Runtime {
 	fn address_to_asset_id(address: H160) -> Option<AssetId> {
 		AssetIdToEVMAddress::<EVMAssetPrefix>::convert(address)
@@ -1,12 +1,19 @@
+use crate::Runtime;
 use frame_support::parameter_types;
 use pallet_evm_precompile_assets_erc20::Erc20AssetsPrecompileSet;
+use pallet_evm_precompile_assets_factory::AssetsFactoryPrecompile;
 use pallet_evm_precompile_blake2::Blake2F;
 use pallet_evm_precompile_bn128::{Bn128Add, Bn128Mul, Bn128Pairing};
+use pallet_evm_precompile_filtered_dispatch::FilteredDispatchPrecompile;
 use pallet_evm_precompile_modexp::Modexp;
 use pallet_evm_precompile_peaq_did::PeaqDIDPrecompile;
+use pallet_evm_precompile_peaq_mor::PeaqMorPrecompile;
 use pallet_evm_precompile_peaq_storage::PeaqStoragePrecompile;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
+use pallet_evm_precompile_xcm_utils::XcmUtilsPrecompile;
+use pallet_evm_precompile_zenlink_dex::ZenlinkDexPrecompile;
+use precompile_registry::Pallet as PrecompileRegistry;
 use precompile_utils::precompile_set::*;
 
 type EthereumPrecompilesChecks = (AcceptDelegateCall, CallableByContract, CallableByPrecompile);
@@ -16,9 +23,17 @@ parameter_types! {
 	pub EVMAssetPrefix: &'static [u8] = ASSET_PRECOMPILE_ADDRESS_PREFIX;
 }
 
+/// Calls EVM accounts can dispatch through the dispatch precompile, which only testnets have.
+pub type DispatchCallFilter =
+	runtime_common::DispatchCallFilter<Runtime, pallet_collective::Instance1>;
+
 /// The following distribution has been decided for the precompiles
 /// 0-1023: Ethereum Mainnet Precompiles
 /// 1024-2047 Precompiles that are not in Ethereum Mainnet but are neither Moonbeam specific
+/// 2048-4095 Peaq specific precompiles
+/// The dispatch precompile and the Peaq specific precompiles added after the DID and storage
+/// ones can be disabled through the `PrecompileRegistry` pallet. Only they pay the read of the
+/// disabled set.
 pub type PeaqPrecompiles<R> = PrecompileSetBuilder<
 	R,
 	(
@@ -43,12 +58,20 @@ pub type PeaqPrecompiles<R> = PrecompileSetBuilder<
 					Sha3FIPS256,
 					(CallableByContract, CallableByPrecompile),
 				>,
-				// PrecompileAt<AddressU64<1025>, Dispatch<R>>,
+				DisableablePrecompiles<
+					PrecompileRegistry<R>,
+					PrecompileAt<
+						AddressU64<1025>,
+						FilteredDispatchPrecompile<R, DispatchCallFilter>,
+						CallableByContract,
+					>,
+				>,
 				PrecompileAt<
 					AddressU64<1026>,
 					ECRecoverPublicKey,
 					(CallableByContract, CallableByPrecompile),
 				>,
+				// Peaq specific precompiles:
 				PrecompileAt<
 					AddressU64<2048>,
 					PeaqDIDPrecompile<R>,
@@ -59,6 +82,24 @@ pub type PeaqPrecompiles<R> = PrecompileSetBuilder<
 					PeaqStoragePrecompile<R>,
 					(AcceptDelegateCall, CallableByContract),
 				>,
+				// Newer Peaq specific precompiles, which can be disabled by governance:
+				DisableablePrecompiles<
+					PrecompileRegistry<R>,
+					(
+						PrecompileAt<
+							AddressU64<2050>,
+							AssetsFactoryPrecompile<R>,
+							(CallableByContract, CallableByPrecompile),
+						>,
+						PrecompileAt<
+							AddressU64<2051>,
+							ZenlinkDexPrecompile<R>,
+							(CallableByContract, CallableByPrecompile),
+						>,
+						PrecompileAt<AddressU64<2052>, PeaqMorPrecompile<R>, CallableByContract>,
+						PrecompileAt<AddressU64<2053>, XcmUtilsPrecompile<R>, CallableByContract>,
+					),
+				>,
 			),
 		>,
 		PrecompileSetStartingWith<
@@ -0,0 +1,64 @@
+// Copyright (C) 2020-2023 Peaq Foundation.
+
+//! Calls the precompiles of the runtime with random calldata, see
+//! `precompile_utils::testing::fuzz`. The `fuzz` crate at the root of the repository runs the
+//! same checks with `cargo fuzz`.
+
+use frame_support::traits::Get;
+use pallet_evm_precompile_assets_erc20::Erc20AssetsPrecompileSetCall;
+use pallet_evm_precompile_assets_factory::AssetsFactoryPrecompileCall;
+use pallet_evm_precompile_filtered_dispatch::FilteredDispatchPrecompileCall;
+use pallet_evm_precompile_peaq_did::PeaqDIDPrecompileCall;
+use pallet_evm_precompile_peaq_mor::PeaqMorPrecompileCall;
+use pallet_evm_precompile_peaq_storage::PeaqStoragePrecompileCall;
+use pallet_evm_precompile_xcm_utils::XcmUtilsPrecompileCall;
+use pallet_evm_precompile_zenlink_dex::ZenlinkDexPrecompileCall;
+use peaq_dev_runtime::{
+	BuildStorage, DispatchCallFilter, GenesisConfig, PrecompilesValue, Runtime,
+};
+use precompile_utils::testing::fuzz::check_random_calls;
+use sp_core::H160;
+
+fn precompile_addresses() -> Vec<H160> {
+	let asset = |id: u8| {
+		let mut address = [0u8; 20];
+		address[..4].copy_from_slice(&[255u8; 4]);
+		address[19] = id;
+		H160(address)
+	};
+
+	(1..=9)
+		.chain(1024..=1026)
+		.chain(2048..=2053)
+		.map(H160::from_low_u64_be)
+		.chain([asset(0), asset(1)])
+		.collect()
+}
+
+fn selectors() -> Vec<u32> {
+	[
+		Erc20AssetsPrecompileSetCall::<Runtime, ()>::selectors(),
+		AssetsFactoryPrecompileCall::<Runtime>::selectors(),
+		FilteredDispatchPrecompileCall::<Runtime, DispatchCallFilter>::selectors(),
+		PeaqDIDPrecompileCall::<Runtime>::selectors(),
+		PeaqMorPrecompileCall::<Runtime>::selectors(),
+		PeaqStoragePrecompileCall::<Runtime>::selectors(),
+		XcmUtilsPrecompileCall::<Runtime>::selectors(),
+		ZenlinkDexPrecompileCall::<Runtime>::selectors(),
+	]
+	.concat()
+}
+
+#[test]
+fn fuzz_calls_fail_cleanly() {
+	check_random_calls(
+		precompile_addresses(),
+		vec![H160::repeat_byte(0xaa), H160::repeat_byte(0xbb)],
+		selectors(),
+		|| {
+			let storage = GenesisConfig::default().build_storage().expect("Default genesis builds");
+			sp_io::TestExternalities::new(storage)
+		},
+		PrecompilesValue::get,
+	);
+}